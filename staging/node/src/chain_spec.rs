@@ -31,6 +31,7 @@ fn props() -> Properties {
 
 pub fn development_config() -> Result<ChainSpec, String> {
 	let wasm_binary = WASM_BINARY.ok_or_else(|| "Development wasm not available".to_string())?;
+	validate_genesis(&testnet_genesis(wasm_binary))?;
 	Ok(ChainSpec::from_genesis(
 		"Development",
 		"dev",
@@ -45,6 +46,33 @@ pub fn development_config() -> Result<ChainSpec, String> {
 	))
 }
 
+/// Load the genesis configuration of the chain specification `id`, which is either `dev` or the
+/// path to a JSON chain specification that is not raw.
+pub fn load_genesis(id: &str) -> Result<RuntimeGenesisConfig, String> {
+	if id == "dev" {
+		let wasm_binary =
+			WASM_BINARY.ok_or_else(|| "Development wasm not available".to_string())?;
+		return Ok(testnet_genesis(wasm_binary))
+	}
+
+	let file = std::fs::File::open(id).map_err(|e| format!("cannot open {}: {}", id, e))?;
+	let spec: serde_json::Value =
+		serde_json::from_reader(file).map_err(|e| format!("cannot parse {}: {}", id, e))?;
+	let genesis = spec
+		.pointer("/genesis/runtime")
+		.ok_or_else(|| format!("{} has no runtime genesis configuration, is it raw?", id))?;
+	serde_json::from_value(genesis.clone())
+		.map_err(|e| format!("invalid genesis configuration in {}: {}", id, e))
+}
+
+/// Check the genesis configuration, and describe every problem with it, one per line.
+pub fn validate_genesis(genesis: &RuntimeGenesisConfig) -> Result<(), String> {
+	runtime::interface::validate_genesis(genesis).map_err(|problems| {
+		let problems = problems.iter().map(|p| p.to_string()).collect::<Vec<_>>();
+		format!("invalid genesis configuration:\n{}", problems.join("\n"))
+	})
+}
+
 /// Configure initial storage state for FRAME modules.
 fn testnet_genesis(wasm_binary: &[u8]) -> RuntimeGenesisConfig {
 	use frame::traits::Get;
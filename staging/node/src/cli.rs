@@ -49,6 +49,14 @@ pub struct Cli {
 	pub run: RunCmd,
 }
 
+#[derive(Debug, Clone, clap::Parser)]
+pub struct ValidateGenesisCmd {
+	/// The chain specification to validate: `dev`, or the path to a JSON chain specification that
+	/// is not raw.
+	#[arg(long, default_value = "dev")]
+	pub chain: String,
+}
+
 #[derive(Debug, clap::Subcommand)]
 pub enum Subcommand {
 	/// Key management cli utilities
@@ -78,4 +86,12 @@ pub enum Subcommand {
 
 	/// Db meta columns information.
 	ChainInfo(sc_cli::ChainInfoCmd),
+
+	/// Validate the genesis configuration of a chain specification.
+	ValidateGenesis(ValidateGenesisCmd),
+
+	/// Sub-commands concerned with benchmarking.
+	#[cfg(feature = "runtime-benchmarks")]
+	#[command(subcommand)]
+	Benchmark(frame_benchmarking_cli::BenchmarkCmd),
 }
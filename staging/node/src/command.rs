@@ -116,6 +116,22 @@ pub fn run() -> sc_cli::Result<()> {
 			let runner = cli.create_runner(cmd)?;
 			runner.sync_run(|config| cmd.run::<runtime::interface::OpaqueBlock>(&config))
 		},
+		Some(Subcommand::ValidateGenesis(cmd)) => {
+			let genesis = chain_spec::load_genesis(&cmd.chain)?;
+			chain_spec::validate_genesis(&genesis)?;
+			println!("The genesis configuration of {} is valid.", cmd.chain);
+			Ok(())
+		},
+		#[cfg(feature = "runtime-benchmarks")]
+		Some(Subcommand::Benchmark(cmd)) => {
+			use frame_benchmarking_cli::BenchmarkCmd;
+			let runner = cli.create_runner(cmd)?;
+			runner.sync_run(|config| match cmd {
+				BenchmarkCmd::Pallet(cmd) =>
+					cmd.run::<runtime::interface::OpaqueBlock, service::HostFunctions>(config),
+				_ => Err("Only barrel benchmarks are supported by this node".into()),
+			})
+		},
 		None => {
 			let runner = cli.create_runner(&cli.run)?;
 			runner.run_node_until_exit(|config| async move {
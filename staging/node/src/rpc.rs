@@ -22,8 +22,12 @@
 
 #![warn(missing_docs)]
 
+pub mod currency;
+
 use jsonrpsee::RpcModule;
-use runtime::interface::{AccountId, Nonce, OpaqueBlock};
+use runtime::interface::{AccountId, CurrencyApi, Nonce, OpaqueBlock};
+use sc_client_api::BlockchainEvents;
+use sc_rpc::SubscriptionTaskExecutor;
 use sc_transaction_pool_api::TransactionPool;
 use sp_block_builder::BlockBuilder;
 use sp_blockchain::{Error as BlockChainError, HeaderBackend, HeaderMetadata};
@@ -39,6 +43,8 @@ pub struct FullDeps<C, P> {
 	pub pool: Arc<P>,
 	/// Whether to deny unsafe calls
 	pub deny_unsafe: DenyUnsafe,
+	/// Executor to drive the subscriptions.
+	pub subscription_executor: SubscriptionTaskExecutor,
 }
 
 /// Instantiate all full RPC extensions.
@@ -55,17 +61,21 @@ where
 		// OpaqueBlock,
 	>,
 	C: HeaderBackend<OpaqueBlock> + HeaderMetadata<OpaqueBlock, Error = BlockChainError> + 'static,
+	C: BlockchainEvents<OpaqueBlock>,
 	C: Send + Sync + 'static,
 	P: TransactionPool + 'static,
 	C::Api: BlockBuilder<OpaqueBlock>,
 	C::Api: matter_frame_rpc_system::AccountNonceApi<OpaqueBlock, AccountId, Nonce>,
+	C::Api: CurrencyApi<OpaqueBlock, AccountId>,
 {
+	use currency::{TutorialCurrency, TutorialCurrencyApiServer};
 	use matter_frame_rpc_system::{System, SystemApiServer};
 
 	let mut module = RpcModule::new(());
-	let FullDeps { client, pool, deny_unsafe } = deps;
+	let FullDeps { client, pool, deny_unsafe, subscription_executor } = deps;
 
 	module.merge(System::new(client.clone(), pool.clone(), deny_unsafe).into_rpc())?;
+	module.merge(TutorialCurrency::new(client.clone(), subscription_executor).into_rpc())?;
 	// NOTE: we have intentionally ignored adding tx-payments's custom RPC here.
 
 	Ok(module)
@@ -0,0 +1,196 @@
+//! RPC methods of the tutorial currency, built on top of its `CurrencyApi` runtime API.
+
+use futures::{future, FutureExt, StreamExt};
+use jsonrpsee::{
+	core::{RpcResult, SubscriptionResult},
+	proc_macros::rpc,
+	types::error::{CallError, ErrorObject},
+	SubscriptionSink,
+};
+use runtime::interface::{
+	AccountId, CurrencyApi, Hash, OpaqueBlock, TutorialBalance, MAX_PROJECTED_ERAS,
+};
+use sc_client_api::BlockchainEvents;
+use sc_rpc::SubscriptionTaskExecutor;
+use sp_api::{ApiExt, ProvideRuntimeApi};
+use sp_blockchain::HeaderBackend;
+use sp_rpc::number::NumberOrHex;
+use std::sync::Arc;
+
+/// The error code returned when the runtime API call fails.
+const RUNTIME_ERROR: i32 = 1;
+
+/// The error code returned when the runtime does not support the requested method.
+const UNSUPPORTED_ERROR: i32 = 2;
+
+/// The error code returned when the parameters of the request are out of range.
+const INVALID_PARAMS_ERROR: i32 = 3;
+
+/// The `tutorialCurrency_*` RPC methods.
+#[rpc(client, server)]
+pub trait TutorialCurrencyApi {
+	/// The free balance of `who`, at the given block or the best block.
+	#[method(name = "tutorialCurrency_balanceOf")]
+	fn balance_of(&self, who: AccountId, at: Option<Hash>) -> RpcResult<NumberOrHex>;
+
+	/// The total issuance of the currency, at the given block or the best block.
+	#[method(name = "tutorialCurrency_totalIssuance")]
+	fn total_issuance(&self, at: Option<Hash>) -> RpcResult<NumberOrHex>;
+
+	/// The number of accounts that exist in the currency, at the given block or the best block.
+	#[method(name = "tutorialCurrency_holdersCount")]
+	fn holders_count(&self, at: Option<Hash>) -> RpcResult<u32>;
+
+	/// The total issuance after `eras` more eras of inflation, projected from the given block or
+	/// the best block.
+	///
+	/// `eras` can be at most `MAX_PROJECTED_ERAS`. This needs version 2 of `CurrencyApi` in the
+	/// runtime at that block.
+	#[method(name = "tutorialCurrency_projectedIssuance")]
+	fn projected_issuance(&self, eras: u32, at: Option<Hash>) -> RpcResult<NumberOrHex>;
+
+	/// Subscribe to the free balance of `who`.
+	///
+	/// The current balance is sent right away, and then again every time it changes in a new best
+	/// block.
+	#[subscription(
+		name = "tutorialCurrency_subscribeBalance" => "tutorialCurrency_balance",
+		unsubscribe = "tutorialCurrency_unsubscribeBalance",
+		item = NumberOrHex,
+	)]
+	fn subscribe_balance(&self, who: AccountId);
+}
+
+/// Implementation of [`TutorialCurrencyApiServer`].
+pub struct TutorialCurrency<C> {
+	client: Arc<C>,
+	executor: SubscriptionTaskExecutor,
+}
+
+impl<C> TutorialCurrency<C> {
+	/// Create a new instance of the tutorial currency RPC methods.
+	pub fn new(client: Arc<C>, executor: SubscriptionTaskExecutor) -> Self {
+		Self { client, executor }
+	}
+}
+
+fn runtime_error(err: impl std::fmt::Debug) -> jsonrpsee::core::Error {
+	CallError::Custom(ErrorObject::owned(
+		RUNTIME_ERROR,
+		"Unable to query the tutorial currency.",
+		Some(format!("{:?}", err)),
+	))
+	.into()
+}
+
+fn unsupported_error(reason: String) -> jsonrpsee::core::Error {
+	CallError::Custom(ErrorObject::owned(UNSUPPORTED_ERROR, reason, None::<()>)).into()
+}
+
+fn invalid_params_error(reason: String) -> jsonrpsee::core::Error {
+	CallError::Custom(ErrorObject::owned(INVALID_PARAMS_ERROR, reason, None::<()>)).into()
+}
+
+impl<C> TutorialCurrency<C>
+where
+	C: ProvideRuntimeApi<OpaqueBlock> + HeaderBackend<OpaqueBlock> + 'static,
+	C::Api: CurrencyApi<OpaqueBlock, AccountId>,
+{
+	fn at_or_best(&self, at: Option<Hash>) -> Hash {
+		at.unwrap_or_else(|| self.client.info().best_hash)
+	}
+}
+
+impl<C> TutorialCurrencyApiServer for TutorialCurrency<C>
+where
+	C: ProvideRuntimeApi<OpaqueBlock>
+		+ HeaderBackend<OpaqueBlock>
+		+ BlockchainEvents<OpaqueBlock>
+		+ Send
+		+ Sync
+		+ 'static,
+	C::Api: CurrencyApi<OpaqueBlock, AccountId>,
+{
+	fn balance_of(&self, who: AccountId, at: Option<Hash>) -> RpcResult<NumberOrHex> {
+		let at = self.at_or_best(at);
+		self.client
+			.runtime_api()
+			.balance_of(at, who)
+			.map(Into::into)
+			.map_err(runtime_error)
+	}
+
+	fn total_issuance(&self, at: Option<Hash>) -> RpcResult<NumberOrHex> {
+		let at = self.at_or_best(at);
+		self.client
+			.runtime_api()
+			.total_issuance(at)
+			.map(Into::into)
+			.map_err(runtime_error)
+	}
+
+	fn holders_count(&self, at: Option<Hash>) -> RpcResult<u32> {
+		let at = self.at_or_best(at);
+		self.client.runtime_api().holders_count(at).map_err(runtime_error)
+	}
+
+	fn projected_issuance(&self, eras: u32, at: Option<Hash>) -> RpcResult<NumberOrHex> {
+		if eras > MAX_PROJECTED_ERAS {
+			return Err(invalid_params_error(format!(
+				"Cannot project more than {} eras, {} requested.",
+				MAX_PROJECTED_ERAS, eras
+			)))
+		}
+
+		let at = self.at_or_best(at);
+		let api = self.client.runtime_api();
+		let version = api
+			.api_version::<dyn CurrencyApi<OpaqueBlock, AccountId>>(at)
+			.map_err(runtime_error)?
+			.unwrap_or_default();
+		if version < 2 {
+			return Err(unsupported_error(format!(
+				"The runtime implements version {} of CurrencyApi, projectedIssuance needs 2.",
+				version
+			)))
+		}
+
+		api.projected_issuance(at, eras)
+			.map_err(runtime_error)?
+			.map(Into::into)
+			.ok_or_else(|| {
+				invalid_params_error(format!("The runtime cannot project {} eras.", eras))
+			})
+	}
+
+	fn subscribe_balance(&self, mut sink: SubscriptionSink, who: AccountId) -> SubscriptionResult {
+		let client = self.client.clone();
+		let balance_at = move |at: Hash| -> Option<TutorialBalance> {
+			client.runtime_api().balance_of(at, who.clone()).ok()
+		};
+
+		let initial = balance_at(self.client.info().best_hash);
+		let mut last = initial;
+		let changes = self
+			.client
+			.import_notification_stream()
+			.filter(|notification| future::ready(notification.is_new_best))
+			.filter_map(move |notification| {
+				let balance = balance_at(notification.hash);
+				let changed = balance.is_some() && balance != last;
+				if changed {
+					last = balance;
+				}
+				future::ready(balance.filter(|_| changed))
+			});
+
+		let stream = futures::stream::iter(initial).chain(changes).map(NumberOrHex::from);
+		let fut = async move {
+			sink.pipe_from_stream(stream).await;
+		};
+		self.executor
+			.spawn("tutorial-currency-rpc-subscription", Some("rpc"), fut.boxed());
+
+		Ok(())
+	}
+}
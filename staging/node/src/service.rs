@@ -27,11 +27,11 @@ use std::sync::Arc;
 use crate::cli::Consensus;
 
 #[cfg(feature = "runtime-benchmarks")]
-type HostFunctions =
+pub(crate) type HostFunctions =
 	(sp_io::MatterHostFunctions, frame_benchmarking::benchmarking::HostFunctions);
 
 #[cfg(not(feature = "runtime-benchmarks"))]
-type HostFunctions = sp_io::MatterHostFunctions;
+pub(crate) type HostFunctions = sp_io::MatterHostFunctions;
 
 pub(crate) type FullClient =
 	sc_service::TFullClient<Block, RuntimeApi, WasmExecutor<HostFunctions>>;
@@ -158,9 +158,13 @@ pub fn new_full(config: Configuration, consensus: Consensus) -> Result<TaskManag
 		let client = client.clone();
 		let pool = transaction_pool.clone();
 
-		Box::new(move |deny_unsafe, _| {
-			let deps =
-				crate::rpc::FullDeps { client: client.clone(), pool: pool.clone(), deny_unsafe };
+		Box::new(move |deny_unsafe, subscription_executor| {
+			let deps = crate::rpc::FullDeps {
+				client: client.clone(),
+				pool: pool.clone(),
+				deny_unsafe,
+				subscription_executor,
+			};
 			crate::rpc::create_full(deps).map_err(Into::into)
 		})
 	};
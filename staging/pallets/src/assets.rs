@@ -0,0 +1,992 @@
+pub use barrel::*;
+pub use weights::WeightInfo;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+mod impl_fungibles;
+pub mod migrations;
+pub mod weights;
+
+/// A multi-asset companion to the `currency` barrel.
+///
+/// Anyone can create a new asset by putting a deposit on hold in [`Config::Currency`]. Each asset
+/// has its own balances, total supply, minimum balance, admin, freeze flag and metadata.
+///
+/// This barrel implements the `fungibles` traits, so any single asset of it can be used where a
+/// `fungible` is expected, through `fungible::ItemOf`.
+#[frame::barrel]
+pub mod barrel {
+	use super::WeightInfo;
+	use frame::{
+		deps::sp_runtime::ArithmeticError,
+		derive::{Decode, Encode, MaxEncodedLen, TypeInfo},
+		prelude::*,
+		traits::{
+			fungible::{self, Mutate, MutateHold},
+			tokens::Precision,
+			Zero,
+		},
+	};
+
+	pub use crate::currency::Balance;
+
+	pub type DepositBalanceOf<T> =
+		<<T as Config>::Currency as fungible::Inspect<AccountIdOf<T>>>::Balance;
+	type AccountIdOf<T> = <T as frame_system::Config>::AccountId;
+
+	/// The in-code storage version.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
+	/// The details of an asset.
+	#[derive(Encode, Decode, MaxEncodedLen, TypeInfo, Clone, Eq, PartialEq, Debug)]
+	pub struct AssetDetails<AccountId, DepositBalance> {
+		/// The creator of the asset, who paid the deposit.
+		pub owner: AccountId,
+		/// The account that can mint, burn, freeze and thaw the asset.
+		pub admin: AccountId,
+		/// The total supply of the asset, including held funds.
+		pub supply: Balance,
+		/// The deposit held from `owner` for this asset.
+		pub deposit: DepositBalance,
+		/// Whether transfers of this asset are currently disabled.
+		pub is_frozen: bool,
+		/// The minimum free balance of an account in this asset, below which it is reaped.
+		pub min_balance: Balance,
+	}
+
+	/// The metadata of an asset.
+	#[derive(Encode, Decode, MaxEncodedLen, TypeInfo, Clone, Eq, PartialEq, Debug, Default)]
+	pub struct AssetMetadata<BoundedString> {
+		pub name: BoundedString,
+		pub symbol: BoundedString,
+		pub decimals: u8,
+	}
+
+	#[barrel::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching event type.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+		/// The identifier of an asset.
+		type AssetId: Parameter + Member + MaxEncodedLen + Copy;
+
+		/// The currency in which asset deposits are held.
+		type Currency: Mutate<Self::AccountId>
+			+ MutateHold<Self::AccountId, Reason = Self::RuntimeHoldReason>;
+
+		/// The overarching hold reason.
+		///
+		/// This is used both for the asset deposits in [`Config::Currency`], and for the funds of
+		/// an asset that are held through the `fungibles` traits.
+		type RuntimeHoldReason: Parameter + Member + MaxEncodedLen + Copy + From<HoldReason>;
+
+		/// The deposit needed to create an asset.
+		#[barrel::constant]
+		type AssetDeposit: Get<DepositBalanceOf<Self>>;
+
+		/// The maximum length of the name and symbol of an asset.
+		#[barrel::constant]
+		type StringLimit: Get<u32>;
+
+		/// Weight information for the calls of this barrel.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[barrel::barrel]
+	#[barrel::storage_version(STORAGE_VERSION)]
+	pub struct Barrel<T>(_);
+
+	/// The reasons for which this barrel holds funds.
+	#[barrel::composite_enum]
+	pub enum HoldReason {
+		/// The deposit for creating an asset.
+		AssetDeposit,
+	}
+
+	/// The details of each asset.
+	#[barrel::storage]
+	pub type Asset<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AssetId,
+		AssetDetails<T::AccountId, DepositBalanceOf<T>>,
+	>;
+
+	/// The free balance of each account, per asset.
+	///
+	/// An account has an entry here as long as its balance is at least the
+	/// [`AssetDetails::min_balance`] of the asset.
+	#[barrel::storage]
+	pub type Balances<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AssetId,
+		Blake2_128Concat,
+		T::AccountId,
+		Balance,
+		ValueQuery,
+	>;
+
+	/// The funds of each account that are held, per asset and [`Config::RuntimeHoldReason`].
+	#[barrel::storage]
+	pub type Holds<T: Config> = StorageNMap<
+		_,
+		(
+			NMapKey<Blake2_128Concat, T::AssetId>,
+			NMapKey<Blake2_128Concat, T::AccountId>,
+			NMapKey<Blake2_128Concat, T::RuntimeHoldReason>,
+		),
+		Balance,
+		ValueQuery,
+	>;
+
+	/// The metadata of each asset.
+	#[barrel::storage]
+	pub type Metadata<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AssetId,
+		AssetMetadata<BoundedVec<u8, T::StringLimit>>,
+		ValueQuery,
+	>;
+
+	#[barrel::event]
+	#[barrel::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// `asset_id` was created by `owner`, and is managed by `admin`.
+		Created { asset_id: T::AssetId, owner: T::AccountId, admin: T::AccountId },
+		/// The admin of `asset_id` was changed to `admin`.
+		AdminChanged { asset_id: T::AssetId, admin: T::AccountId },
+		/// `amount` of `asset_id` was minted into `to`.
+		Issued { asset_id: T::AssetId, to: T::AccountId, amount: Balance },
+		/// `amount` of `asset_id` was burned from `who`.
+		Burned { asset_id: T::AssetId, who: T::AccountId, amount: Balance },
+		/// `amount` of `asset_id` was transferred from `from` to `to`.
+		Transferred { asset_id: T::AssetId, from: T::AccountId, to: T::AccountId, amount: Balance },
+		/// `amount` of `asset_id` was put on hold for `reason`.
+		Held {
+			asset_id: T::AssetId,
+			who: T::AccountId,
+			reason: T::RuntimeHoldReason,
+			amount: Balance,
+		},
+		/// `amount` of `asset_id` held for `reason` was released.
+		Released {
+			asset_id: T::AssetId,
+			who: T::AccountId,
+			reason: T::RuntimeHoldReason,
+			amount: Balance,
+		},
+		/// `amount` of `asset_id` held for `reason` was burned.
+		BurnedHeld {
+			asset_id: T::AssetId,
+			who: T::AccountId,
+			reason: T::RuntimeHoldReason,
+			amount: Balance,
+		},
+		/// Transfers of `asset_id` were disabled.
+		Frozen { asset_id: T::AssetId },
+		/// Transfers of `asset_id` were enabled again.
+		Thawed { asset_id: T::AssetId },
+		/// The metadata of `asset_id` was set.
+		MetadataSet { asset_id: T::AssetId },
+		/// `asset_id` was destroyed, and its deposit returned.
+		Destroyed { asset_id: T::AssetId },
+		/// `who` was reaped for falling below the minimum balance of `asset_id`, and the `amount`
+		/// it had left was burned.
+		DustLost { asset_id: T::AssetId, who: T::AccountId, amount: Balance },
+	}
+
+	#[barrel::error]
+	pub enum Error<T> {
+		/// An asset with this id already exists.
+		AssetExists,
+		/// No asset with this id exists.
+		UnknownAsset,
+		/// The caller is not allowed to manage this asset.
+		NoPermission,
+		/// The account does not have enough free balance of this asset.
+		InsufficientBalance,
+		/// The asset is frozen.
+		AssetFrozen,
+		/// The name or symbol is too long.
+		BadMetadata,
+		/// The asset still has a non-zero supply.
+		InUse,
+		/// The minimum balance of an asset must be non-zero.
+		MinBalanceZero,
+		/// The balance of the account would be below the minimum balance of the asset.
+		BelowMinimum,
+	}
+
+	#[derive(frame::derive::DefaultNoBound)]
+	#[barrel::genesis_config]
+	pub struct GenesisConfig<T: Config> {
+		/// Assets to create, as `(asset_id, owner, min_balance)`. The owner is also the admin, and
+		/// no deposit is taken.
+		pub assets: Vec<(T::AssetId, T::AccountId, Balance)>,
+		/// Initial balances, as `(asset_id, who, amount)`, each at least the minimum balance of
+		/// the asset.
+		pub accounts: Vec<(T::AssetId, T::AccountId, Balance)>,
+	}
+
+	// TODO:
+	// https://github.com/bitzaldot/bitzal-sdk/pull/1642/files#diff-1a8ad3ec3e24e92089201972e112619421ef6c31484f65d45d30da7a8fae69fbR41
+	use frame::deps::sp_runtime;
+	#[barrel::genesis_build]
+	impl<T: Config> BuildGenesisConfig for GenesisConfig<T> {
+		fn build(&self) {
+			for (asset_id, owner, min_balance) in &self.assets {
+				assert!(!Asset::<T>::contains_key(asset_id), "duplicate asset in genesis");
+				assert!(!min_balance.is_zero(), "zero minimum balance in genesis");
+				Asset::<T>::insert(
+					asset_id,
+					AssetDetails {
+						owner: owner.clone(),
+						admin: owner.clone(),
+						supply: Zero::zero(),
+						deposit: Zero::zero(),
+						is_frozen: false,
+						min_balance: *min_balance,
+					},
+				);
+			}
+
+			for (asset_id, who, amount) in &self.accounts {
+				assert!(
+					!Balances::<T>::contains_key(asset_id, who),
+					"duplicate asset balance in genesis"
+				);
+				Asset::<T>::mutate(asset_id, |maybe_details| {
+					let details = maybe_details.as_mut().expect("unknown asset in genesis");
+					assert!(*amount >= details.min_balance, "genesis balance below minimum");
+					details.supply =
+						details.supply.checked_add(*amount).expect("genesis asset supply overflow");
+				});
+				Balances::<T>::insert(asset_id, who, amount);
+			}
+		}
+	}
+
+	#[barrel::call]
+	impl<T: Config> Barrel<T> {
+		/// Create a new asset with id `asset_id`, managed by `admin`, in which accounts must hold
+		/// at least `min_balance`.
+		///
+		/// [`Config::AssetDeposit`] is held from the caller until the asset is destroyed.
+		#[barrel::call_index(0)]
+		#[barrel::weight(T::WeightInfo::create())]
+		pub fn create(
+			origin: OriginFor<T>,
+			asset_id: T::AssetId,
+			admin: T::AccountId,
+			min_balance: Balance,
+		) -> DispatchResult {
+			let owner = ensure_signed(origin)?;
+			ensure!(!Asset::<T>::contains_key(asset_id), Error::<T>::AssetExists);
+			ensure!(!min_balance.is_zero(), Error::<T>::MinBalanceZero);
+
+			let deposit = T::AssetDeposit::get();
+			T::Currency::hold(&HoldReason::AssetDeposit.into(), &owner, deposit)?;
+			Asset::<T>::insert(
+				asset_id,
+				AssetDetails {
+					owner: owner.clone(),
+					admin: admin.clone(),
+					supply: Zero::zero(),
+					deposit,
+					is_frozen: false,
+					min_balance,
+				},
+			);
+
+			Self::deposit_event(Event::Created { asset_id, owner, admin });
+			Ok(())
+		}
+
+		/// Change the admin of `asset_id`. Only callable by the owner.
+		#[barrel::call_index(1)]
+		#[barrel::weight(T::WeightInfo::set_admin())]
+		pub fn set_admin(
+			origin: OriginFor<T>,
+			asset_id: T::AssetId,
+			admin: T::AccountId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Asset::<T>::try_mutate(asset_id, |maybe_details| -> DispatchResult {
+				let details = maybe_details.as_mut().ok_or(Error::<T>::UnknownAsset)?;
+				ensure!(details.owner == who, Error::<T>::NoPermission);
+				details.admin = admin.clone();
+				Ok(())
+			})?;
+
+			Self::deposit_event(Event::AdminChanged { asset_id, admin });
+			Ok(())
+		}
+
+		/// Mint `amount` of `asset_id` into `to`. Only callable by the admin.
+		#[barrel::call_index(2)]
+		#[barrel::weight(T::WeightInfo::mint())]
+		pub fn mint(
+			origin: OriginFor<T>,
+			asset_id: T::AssetId,
+			to: T::AccountId,
+			amount: Balance,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let mut details = Self::ensure_admin(asset_id, &who)?;
+
+			details.supply = details.supply.checked_add(amount).ok_or(ArithmeticError::Overflow)?;
+			Self::credit(asset_id, &details, &to, amount)?;
+			Asset::<T>::insert(asset_id, details);
+
+			Self::deposit_event(Event::Issued { asset_id, to, amount });
+			Ok(())
+		}
+
+		/// Burn `amount` of `asset_id` from the free balance of `from`. Only callable by the
+		/// admin.
+		///
+		/// If less than the minimum balance of the asset is left, `from` is reaped and the rest is
+		/// burned too.
+		#[barrel::call_index(3)]
+		#[barrel::weight(T::WeightInfo::burn())]
+		pub fn burn(
+			origin: OriginFor<T>,
+			asset_id: T::AssetId,
+			from: T::AccountId,
+			amount: Balance,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let mut details = Self::ensure_admin(asset_id, &who)?;
+
+			let dust = Self::debit(asset_id, &details, &from, amount)?;
+			details.supply =
+				details.supply.checked_sub(amount).ok_or(ArithmeticError::Underflow)?;
+			details.supply = details.supply.saturating_sub(dust);
+			Asset::<T>::insert(asset_id, details);
+
+			Self::deposit_event(Event::Burned { asset_id, who: from, amount });
+			Ok(())
+		}
+
+		/// Transfer `amount` of `asset_id` to `to`.
+		///
+		/// If less than the minimum balance of the asset is left, the caller is reaped and the
+		/// rest is burned.
+		#[barrel::call_index(4)]
+		#[barrel::weight(T::WeightInfo::transfer())]
+		pub fn transfer(
+			origin: OriginFor<T>,
+			asset_id: T::AssetId,
+			to: T::AccountId,
+			amount: Balance,
+		) -> DispatchResult {
+			let from = ensure_signed(origin)?;
+			let mut details = Asset::<T>::get(asset_id).ok_or(Error::<T>::UnknownAsset)?;
+			ensure!(!details.is_frozen, Error::<T>::AssetFrozen);
+
+			if from != to {
+				let dust = Self::debit(asset_id, &details, &from, amount)?;
+				Self::credit(asset_id, &details, &to, amount)?;
+				if !dust.is_zero() {
+					details.supply = details.supply.saturating_sub(dust);
+					Asset::<T>::insert(asset_id, details);
+				}
+			} else {
+				ensure!(
+					Balances::<T>::get(asset_id, &from) >= amount,
+					Error::<T>::InsufficientBalance
+				);
+			}
+
+			Self::deposit_event(Event::Transferred { asset_id, from, to, amount });
+			Ok(())
+		}
+
+		/// Disable transfers of `asset_id`. Only callable by the admin.
+		#[barrel::call_index(5)]
+		#[barrel::weight(T::WeightInfo::freeze())]
+		pub fn freeze(origin: OriginFor<T>, asset_id: T::AssetId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let mut details = Self::ensure_admin(asset_id, &who)?;
+			details.is_frozen = true;
+			Asset::<T>::insert(asset_id, details);
+
+			Self::deposit_event(Event::Frozen { asset_id });
+			Ok(())
+		}
+
+		/// Enable transfers of `asset_id` again. Only callable by the admin.
+		#[barrel::call_index(6)]
+		#[barrel::weight(T::WeightInfo::thaw())]
+		pub fn thaw(origin: OriginFor<T>, asset_id: T::AssetId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let mut details = Self::ensure_admin(asset_id, &who)?;
+			details.is_frozen = false;
+			Asset::<T>::insert(asset_id, details);
+
+			Self::deposit_event(Event::Thawed { asset_id });
+			Ok(())
+		}
+
+		/// Set the metadata of `asset_id`. Only callable by the owner.
+		#[barrel::call_index(7)]
+		#[barrel::weight(T::WeightInfo::set_metadata())]
+		pub fn set_metadata(
+			origin: OriginFor<T>,
+			asset_id: T::AssetId,
+			name: Vec<u8>,
+			symbol: Vec<u8>,
+			decimals: u8,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let details = Asset::<T>::get(asset_id).ok_or(Error::<T>::UnknownAsset)?;
+			ensure!(details.owner == who, Error::<T>::NoPermission);
+
+			let name = BoundedVec::try_from(name).map_err(|_| Error::<T>::BadMetadata)?;
+			let symbol = BoundedVec::try_from(symbol).map_err(|_| Error::<T>::BadMetadata)?;
+			Metadata::<T>::insert(asset_id, AssetMetadata { name, symbol, decimals });
+
+			Self::deposit_event(Event::MetadataSet { asset_id });
+			Ok(())
+		}
+
+		/// Destroy `asset_id`, and release its deposit. Only callable by the owner, once the whole
+		/// supply of the asset has been burned.
+		#[barrel::call_index(8)]
+		#[barrel::weight(T::WeightInfo::destroy())]
+		pub fn destroy(origin: OriginFor<T>, asset_id: T::AssetId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let details = Asset::<T>::get(asset_id).ok_or(Error::<T>::UnknownAsset)?;
+			ensure!(details.owner == who, Error::<T>::NoPermission);
+			ensure!(details.supply.is_zero(), Error::<T>::InUse);
+
+			T::Currency::release(
+				&HoldReason::AssetDeposit.into(),
+				&details.owner,
+				details.deposit,
+				Precision::BestEffort,
+			)?;
+			Asset::<T>::remove(asset_id);
+			Metadata::<T>::remove(asset_id);
+
+			Self::deposit_event(Event::Destroyed { asset_id });
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Barrel<T> {
+		/// The free balance of `who` in `asset_id`.
+		pub fn balance(asset_id: T::AssetId, who: &T::AccountId) -> Balance {
+			Balances::<T>::get(asset_id, who)
+		}
+
+		/// The total supply of `asset_id`, or zero if it does not exist.
+		pub fn supply(asset_id: T::AssetId) -> Balance {
+			Asset::<T>::get(asset_id).map(|d| d.supply).unwrap_or_default()
+		}
+
+		fn ensure_admin(
+			asset_id: T::AssetId,
+			who: &T::AccountId,
+		) -> Result<AssetDetails<T::AccountId, DepositBalanceOf<T>>, DispatchError> {
+			let details = Asset::<T>::get(asset_id).ok_or(Error::<T>::UnknownAsset)?;
+			ensure!(&details.admin == who, Error::<T>::NoPermission);
+			Ok(details)
+		}
+
+		/// Add `amount` to the free balance of `who` in the asset with `details`, which must end
+		/// up at least at its minimum balance. Does not alter the supply.
+		fn credit(
+			asset_id: T::AssetId,
+			details: &AssetDetails<T::AccountId, DepositBalanceOf<T>>,
+			who: &T::AccountId,
+			amount: Balance,
+		) -> DispatchResult {
+			Balances::<T>::try_mutate(asset_id, who, |free| -> DispatchResult {
+				*free = free.checked_add(amount).ok_or(ArithmeticError::Overflow)?;
+				ensure!(*free >= details.min_balance, Error::<T>::BelowMinimum);
+				Ok(())
+			})
+		}
+
+		/// Remove `amount` from the free balance of `who` in the asset with `details`. Does not
+		/// alter the supply.
+		///
+		/// If less than the minimum balance of the asset is left, `who` is reaped. Returns what it
+		/// had left, which the caller must burn.
+		fn debit(
+			asset_id: T::AssetId,
+			details: &AssetDetails<T::AccountId, DepositBalanceOf<T>>,
+			who: &T::AccountId,
+			amount: Balance,
+		) -> Result<Balance, DispatchError> {
+			let dust = Balances::<T>::try_mutate_exists(
+				asset_id,
+				who,
+				|maybe_free| -> Result<Balance, DispatchError> {
+					let free = maybe_free.unwrap_or_default();
+					let remaining =
+						free.checked_sub(amount).ok_or(Error::<T>::InsufficientBalance)?;
+					if remaining < details.min_balance {
+						*maybe_free = None;
+						Ok(remaining)
+					} else {
+						*maybe_free = Some(remaining);
+						Ok(Zero::zero())
+					}
+				},
+			)?;
+
+			if !dust.is_zero() {
+				Self::deposit_event(Event::DustLost { asset_id, who: who.clone(), amount: dust });
+			}
+			Ok(dust)
+		}
+
+		/// Ensure the invariants of this barrel hold:
+		///
+		/// * the supply of each asset is equal to the sum of its free and held balances.
+		/// * free balances are at least the minimum balance of their asset.
+		/// * balances and holds only exist for existing assets.
+		#[cfg(any(feature = "try-runtime", test))]
+		pub fn do_try_state() -> Result<(), sp_runtime::TryRuntimeError> {
+			for (asset_id, details) in Asset::<T>::iter() {
+				let free = Balances::<T>::iter_prefix_values(asset_id)
+					.try_fold(0 as Balance, |acc, x| acc.checked_add(x))
+					.ok_or("free balance overflow")?;
+				let held = Holds::<T>::iter_prefix_values((asset_id,))
+					.try_fold(0 as Balance, |acc, x| acc.checked_add(x))
+					.ok_or("held balance overflow")?;
+				ensure!(
+					free.checked_add(held) == Some(details.supply),
+					"sum of balances does not match the asset supply"
+				);
+			}
+
+			for (asset_id, _, free) in Balances::<T>::iter() {
+				let details = Asset::<T>::get(asset_id).ok_or("balance of unknown asset")?;
+				ensure!(free >= details.min_balance, "balance below the minimum balance");
+			}
+			for ((asset_id, _, _), _) in Holds::<T>::iter() {
+				ensure!(Asset::<T>::contains_key(asset_id), "hold of unknown asset");
+			}
+
+			Ok(())
+		}
+	}
+
+	#[barrel::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Barrel<T> {
+		#[cfg(feature = "try-runtime")]
+		fn try_state(_: BlockNumberFor<T>) -> Result<(), sp_runtime::TryRuntimeError> {
+			Self::do_try_state()
+		}
+	}
+
+	#[cfg(test)]
+	pub(crate) mod tests {
+		use crate::{
+			assets::barrel::{self as barrel_assets, *},
+			currency::barrel as barrel_currency,
+		};
+		use frame::{
+			testing_prelude::*,
+			traits::{
+				fungible::{self, ItemOf},
+				fungibles::{self, InspectHold as _, MutateHold as _},
+				tokens::{Fortitude, Precision, Preservation},
+			},
+		};
+
+		construct_runtime!(
+			pub struct Runtime {
+				System: frame_system,
+				Currency: barrel_currency,
+				Assets: barrel_assets,
+			}
+		);
+
+		#[derive_impl(frame_system::config_preludes::TestDefaultConfig as frame_system::DefaultConfig)]
+		impl frame_system::Config for Runtime {
+			type Block = MockBlock<Runtime>;
+			type AccountData = barrel_currency::AccountData;
+		}
+
+		parameter_types! {
+			pub const ExistentialDeposit: Balance = 1;
+			pub const AssetDeposit: Balance = 10;
+			pub const StakingAsset: u32 = ASSET;
+		}
+
+		impl barrel_currency::Config for Runtime {
+			type RuntimeEvent = RuntimeEvent;
+			type MintOrigin = frame_system::EnsureRoot<AccountId>;
+			type FreezeOrigin = frame_system::EnsureRoot<AccountId>;
+			type ExistentialDeposit = ExistentialDeposit;
+			type DustRemoval = ();
+			type RuntimeHoldReason = RuntimeHoldReason;
+			type MaxVestingSchedules = ConstU32<1>;
+			type MaxTransfers = ConstU32<4>;
+			type MaxScheduledPerBlock = ConstU32<4>;
+			type MaxAgendaBlocks = ConstU32<4>;
+			type MaxReleasesPerBlock = ConstU32<4>;
+			type WeightInfo = ();
+			type AccountStore = System;
+			type EraDuration = ConstU64<10>;
+			type BlocksPerYear = ConstU64<100>;
+			type YearlyInflation = ();
+			type OnInflation = ();
+			type TransferFee = ();
+			type FeeBurn = ();
+			type FeeCollector = ();
+			type OnTransfer = ();
+			type OnBalanceChanged = ();
+			type OnAccountCreated = ();
+			type OnAccountKilled = ();
+		}
+
+		impl barrel_assets::Config for Runtime {
+			type RuntimeEvent = RuntimeEvent;
+			type AssetId = u32;
+			type Currency = Currency;
+			type RuntimeHoldReason = RuntimeHoldReason;
+			type AssetDeposit = AssetDeposit;
+			type StringLimit = ConstU32<4>;
+			type WeightInfo = ();
+		}
+
+		type AccountId = <Runtime as frame_system::Config>::AccountId;
+
+		const ALICE: AccountId = 1;
+		const BOB: AccountId = 2;
+		const EVE: AccountId = 3;
+
+		const ASSET: u32 = 7;
+
+		struct ExtBuilder {
+			assets: Vec<(u32, AccountId, Balance)>,
+			accounts: Vec<(u32, AccountId, Balance)>,
+		}
+
+		impl Default for ExtBuilder {
+			fn default() -> Self {
+				Self {
+					assets: vec![(ASSET, ALICE, 1)],
+					accounts: vec![(ASSET, ALICE, 100), (ASSET, BOB, 100)],
+				}
+			}
+		}
+
+		impl ExtBuilder {
+			fn build_and_execute(self, test: impl FnOnce() -> ()) {
+				let system = frame_system::GenesisConfig::default();
+				let currency = barrel_currency::GenesisConfig {
+					balances: vec![(ALICE, 100), (BOB, 100), (EVE, 5)],
+					..Default::default()
+				};
+				let assets =
+					barrel_assets::GenesisConfig { assets: self.assets, accounts: self.accounts };
+				let runtime_genesis = RuntimeGenesisConfig { system, currency, assets };
+
+				let mut ext = TestState::new(runtime_genesis.build_storage().unwrap());
+				// events are not deposited in block 0.
+				ext.execute_with(|| frame_system::Barrel::<Runtime>::set_block_number(1));
+				ext.execute_with(test);
+
+				ext.execute_with(|| Barrel::<Runtime>::do_try_state().unwrap())
+			}
+		}
+
+		#[allow(unused)]
+		pub(crate) fn test_state_new() -> TestState {
+			let currency = barrel_currency::GenesisConfig {
+				balances: vec![(ALICE, 100), (BOB, 100), (EVE, 5)],
+				..Default::default()
+			};
+			let runtime_genesis = RuntimeGenesisConfig { currency, ..Default::default() };
+			let mut ext = TestState::new(runtime_genesis.build_storage().unwrap());
+			ext.execute_with(|| frame_system::Barrel::<Runtime>::set_block_number(1));
+			ext
+		}
+
+		fn assets_events() -> Vec<Event<Runtime>> {
+			frame_system::Barrel::<Runtime>::events()
+				.into_iter()
+				.filter_map(|r| match r.event {
+					RuntimeEvent::Assets(e) => Some(e),
+					_ => None,
+				})
+				.collect()
+		}
+
+		#[test]
+		fn initial_state_works() {
+			ExtBuilder::default().build_and_execute(|| {
+				assert_eq!(Barrel::<Runtime>::balance(ASSET, &ALICE), 100);
+				assert_eq!(Barrel::<Runtime>::balance(ASSET, &BOB), 100);
+				assert_eq!(Barrel::<Runtime>::supply(ASSET), 200);
+				assert_eq!(Asset::<Runtime>::get(ASSET).unwrap().admin, ALICE);
+			});
+		}
+
+		#[test]
+		fn create_holds_deposit() {
+			ExtBuilder::default().build_and_execute(|| {
+				// given the initial state, when:
+				assert_ok!(Barrel::<Runtime>::create(RuntimeOrigin::signed(BOB), 1, EVE, 1));
+
+				// then:
+				let details = Asset::<Runtime>::get(1).unwrap();
+				assert_eq!((details.owner, details.admin, details.deposit), (BOB, EVE, 10));
+				assert_eq!(
+					barrel_currency::Barrel::<Runtime>::balance_on_hold(
+						HoldReason::AssetDeposit.into(),
+						&BOB
+					),
+					10
+				);
+				assert_eq!(
+					assets_events(),
+					vec![Event::Created { asset_id: 1, owner: BOB, admin: EVE }]
+				);
+
+				// cannot create the same asset twice, or without enough funds for the deposit.
+				assert_noop!(
+					Barrel::<Runtime>::create(RuntimeOrigin::signed(ALICE), 1, ALICE, 1),
+					Error::<Runtime>::AssetExists
+				);
+				assert!(Barrel::<Runtime>::create(RuntimeOrigin::signed(EVE), 2, EVE, 1).is_err());
+			});
+		}
+
+		#[test]
+		fn mint_burn_and_transfer_work() {
+			ExtBuilder::default().build_and_execute(|| {
+				// given the initial state, when:
+				assert_ok!(Barrel::<Runtime>::mint(RuntimeOrigin::signed(ALICE), ASSET, EVE, 50));
+				assert_ok!(Barrel::<Runtime>::transfer(RuntimeOrigin::signed(EVE), ASSET, BOB, 20));
+				assert_ok!(Barrel::<Runtime>::burn(RuntimeOrigin::signed(ALICE), ASSET, BOB, 120));
+
+				// then:
+				assert_eq!(Barrel::<Runtime>::balance(ASSET, &EVE), 30);
+				assert_eq!(Barrel::<Runtime>::balance(ASSET, &BOB), 0);
+				assert!(!Balances::<Runtime>::contains_key(ASSET, &BOB));
+				assert_eq!(Barrel::<Runtime>::supply(ASSET), 130);
+
+				// only the admin can mint and burn.
+				assert_noop!(
+					Barrel::<Runtime>::mint(RuntimeOrigin::signed(BOB), ASSET, BOB, 1),
+					Error::<Runtime>::NoPermission
+				);
+				assert_noop!(
+					Barrel::<Runtime>::burn(RuntimeOrigin::signed(BOB), ASSET, ALICE, 1),
+					Error::<Runtime>::NoPermission
+				);
+				assert_noop!(
+					Barrel::<Runtime>::transfer(RuntimeOrigin::signed(EVE), ASSET, BOB, 31),
+					Error::<Runtime>::InsufficientBalance
+				);
+				assert_noop!(
+					Barrel::<Runtime>::transfer(RuntimeOrigin::signed(EVE), 1, BOB, 1),
+					Error::<Runtime>::UnknownAsset
+				);
+			});
+		}
+
+		#[test]
+		fn accounts_below_min_balance_are_reaped() {
+			ExtBuilder::default().build_and_execute(|| {
+				// given an asset of bob, in which accounts must hold at least 5.
+				assert_noop!(
+					Barrel::<Runtime>::create(RuntimeOrigin::signed(BOB), 1, BOB, 0),
+					Error::<Runtime>::MinBalanceZero
+				);
+				assert_ok!(Barrel::<Runtime>::create(RuntimeOrigin::signed(BOB), 1, BOB, 5));
+				assert_eq!(<Barrel<Runtime> as fungibles::Inspect<_>>::minimum_balance(1), 5);
+
+				// when, then an account cannot be created with less than that.
+				assert_noop!(
+					Barrel::<Runtime>::mint(RuntimeOrigin::signed(BOB), 1, EVE, 4),
+					Error::<Runtime>::BelowMinimum
+				);
+				assert_ok!(Barrel::<Runtime>::mint(RuntimeOrigin::signed(BOB), 1, EVE, 20));
+				assert_noop!(
+					Barrel::<Runtime>::transfer(RuntimeOrigin::signed(EVE), 1, ALICE, 4),
+					Error::<Runtime>::BelowMinimum
+				);
+
+				// when eve transfers all but 3 of its balance:
+				assert_ok!(Barrel::<Runtime>::transfer(RuntimeOrigin::signed(EVE), 1, ALICE, 17));
+
+				// then eve is reaped, and the 3 it had left are burned.
+				assert!(!Balances::<Runtime>::contains_key(1, &EVE));
+				assert_eq!(Barrel::<Runtime>::balance(1, &ALICE), 17);
+				assert_eq!(Barrel::<Runtime>::supply(1), 17);
+				assert!(assets_events().contains(&Event::DustLost {
+					asset_id: 1,
+					who: EVE,
+					amount: 3
+				}));
+			});
+		}
+
+		#[test]
+		fn freeze_and_thaw_work() {
+			ExtBuilder::default().build_and_execute(|| {
+				// given the initial state, when:
+				assert_ok!(Barrel::<Runtime>::freeze(RuntimeOrigin::signed(ALICE), ASSET));
+
+				// then:
+				assert_noop!(
+					Barrel::<Runtime>::transfer(RuntimeOrigin::signed(BOB), ASSET, EVE, 10),
+					Error::<Runtime>::AssetFrozen
+				);
+				assert_eq!(
+					<Barrel<Runtime> as fungibles::Inspect<_>>::reducible_balance(
+						ASSET,
+						&BOB,
+						Preservation::Expendable,
+						Fortitude::Polite
+					),
+					0
+				);
+
+				// when:
+				assert_ok!(Barrel::<Runtime>::thaw(RuntimeOrigin::signed(ALICE), ASSET));
+
+				// then:
+				assert_ok!(Barrel::<Runtime>::transfer(RuntimeOrigin::signed(BOB), ASSET, EVE, 10));
+				assert_eq!(
+					assets_events(),
+					vec![
+						Event::Frozen { asset_id: ASSET },
+						Event::Thawed { asset_id: ASSET },
+						Event::Transferred { asset_id: ASSET, from: BOB, to: EVE, amount: 10 },
+					]
+				);
+			});
+		}
+
+		#[test]
+		fn metadata_works() {
+			ExtBuilder::default().build_and_execute(|| {
+				// given the initial state, when:
+				assert_ok!(Barrel::<Runtime>::set_metadata(
+					RuntimeOrigin::signed(ALICE),
+					ASSET,
+					b"Gold".to_vec(),
+					b"GLD".to_vec(),
+					10
+				));
+
+				// then:
+				let metadata = Metadata::<Runtime>::get(ASSET);
+				assert_eq!(metadata.name.into_inner(), b"Gold".to_vec());
+				assert_eq!(metadata.symbol.into_inner(), b"GLD".to_vec());
+				assert_eq!(metadata.decimals, 10);
+
+				assert_noop!(
+					Barrel::<Runtime>::set_metadata(
+						RuntimeOrigin::signed(ALICE),
+						ASSET,
+						b"Golden".to_vec(),
+						b"GLD".to_vec(),
+						10
+					),
+					Error::<Runtime>::BadMetadata
+				);
+				assert_noop!(
+					Barrel::<Runtime>::set_metadata(
+						RuntimeOrigin::signed(BOB),
+						ASSET,
+						vec![],
+						vec![],
+						0
+					),
+					Error::<Runtime>::NoPermission
+				);
+			});
+		}
+
+		#[test]
+		fn destroy_releases_deposit() {
+			ExtBuilder::default().build_and_execute(|| {
+				// given an asset of bob with some supply.
+				assert_ok!(Barrel::<Runtime>::create(RuntimeOrigin::signed(BOB), 1, BOB, 1));
+				assert_ok!(Barrel::<Runtime>::mint(RuntimeOrigin::signed(BOB), 1, BOB, 5));
+
+				// when, then it cannot be destroyed while it has supply.
+				assert_noop!(
+					Barrel::<Runtime>::destroy(RuntimeOrigin::signed(BOB), 1),
+					Error::<Runtime>::InUse
+				);
+
+				// when the supply is burned:
+				assert_ok!(Barrel::<Runtime>::burn(RuntimeOrigin::signed(BOB), 1, BOB, 5));
+				assert_ok!(Barrel::<Runtime>::destroy(RuntimeOrigin::signed(BOB), 1));
+
+				// then:
+				assert!(!Asset::<Runtime>::contains_key(1));
+				assert_eq!(barrel_currency::Barrel::<Runtime>::reserved_balance(&BOB), 0);
+				assert_eq!(barrel_currency::Barrel::<Runtime>::free_balance(&BOB), 100);
+			});
+		}
+
+		#[test]
+		fn fungibles_hold_works() {
+			ExtBuilder::default().build_and_execute(|| {
+				// given the initial state, when:
+				let reason = HoldReason::AssetDeposit.into();
+				assert_ok!(<Barrel<Runtime> as fungibles::MutateHold<_>>::hold(
+					ASSET, &reason, &BOB, 60
+				));
+
+				// then:
+				assert_eq!(Barrel::<Runtime>::balance(ASSET, &BOB), 40);
+				assert_eq!(Barrel::<Runtime>::balance_on_hold(ASSET, &reason, &BOB), 60);
+				assert_eq!(Barrel::<Runtime>::supply(ASSET), 200);
+
+				// when:
+				assert_ok!(Barrel::<Runtime>::burn_held(
+					ASSET,
+					&reason,
+					&BOB,
+					10,
+					Precision::Exact,
+					Fortitude::Polite
+				));
+				assert_ok!(Barrel::<Runtime>::release(ASSET, &reason, &BOB, 50, Precision::Exact));
+
+				// then:
+				assert_eq!(Barrel::<Runtime>::balance(ASSET, &BOB), 90);
+				assert_eq!(Barrel::<Runtime>::total_balance_on_hold(ASSET, &BOB), 0);
+				assert_eq!(Barrel::<Runtime>::supply(ASSET), 190);
+			});
+		}
+
+		#[test]
+		fn single_asset_can_be_used_as_fungible() {
+			ExtBuilder::default().build_and_execute(|| {
+				type Gold = ItemOf<Assets, StakingAsset, AccountId>;
+
+				// given the initial state, then:
+				assert_eq!(<Gold as fungible::Inspect<_>>::balance(&ALICE), 100);
+				assert_eq!(<Gold as fungible::Inspect<_>>::total_issuance(), 200);
+
+				// when:
+				assert_ok!(<Gold as fungible::Mutate<_>>::transfer(
+					&ALICE,
+					&EVE,
+					30,
+					Preservation::Expendable
+				));
+
+				// then:
+				assert_eq!(Barrel::<Runtime>::balance(ASSET, &ALICE), 70);
+				assert_eq!(Barrel::<Runtime>::balance(ASSET, &EVE), 30);
+			});
+		}
+	}
+}
@@ -0,0 +1,170 @@
+//! Benchmarks for the assets barrel.
+
+use super::*;
+use frame::{
+	prelude::*,
+	traits::{fungible, Saturating, Zero},
+};
+use frame_benchmarking::v2::*;
+use frame_system::RawOrigin;
+
+/// The minimum balance of the benchmarked asset.
+const MIN_BALANCE: Balance = 10;
+
+/// Create an account that can pay the deposit of an asset.
+fn depositor<T: Config>(name: &'static str) -> T::AccountId {
+	let who = account(name, 0, 0);
+	let amount = T::AssetDeposit::get()
+		.saturating_add(<T::Currency as fungible::Inspect<_>>::minimum_balance())
+		.saturating_mul(2u32.into());
+	<T::Currency as fungible::Mutate<_>>::mint_into(&who, amount).unwrap();
+	who
+}
+
+/// Create an asset owned and managed by `owner`, with id `asset_id`.
+fn created<T: Config>(asset_id: T::AssetId) -> T::AccountId {
+	let owner = depositor::<T>("owner");
+	Barrel::<T>::create(
+		RawOrigin::Signed(owner.clone()).into(),
+		asset_id,
+		owner.clone(),
+		MIN_BALANCE,
+	)
+	.unwrap();
+	owner
+}
+
+#[benchmarks(where T::AssetId: From<u32>)]
+mod benchmarks {
+	use super::*;
+
+	#[benchmark]
+	fn create() {
+		let owner = depositor::<T>("owner");
+		let asset_id: T::AssetId = 0u32.into();
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(owner.clone()), asset_id, owner.clone(), MIN_BALANCE);
+
+		assert_eq!(Asset::<T>::get(asset_id).unwrap().owner, owner);
+	}
+
+	#[benchmark]
+	fn set_admin() {
+		let asset_id: T::AssetId = 0u32.into();
+		let owner = created::<T>(asset_id);
+		let admin: T::AccountId = account("admin", 0, 0);
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(owner), asset_id, admin.clone());
+
+		assert_eq!(Asset::<T>::get(asset_id).unwrap().admin, admin);
+	}
+
+	#[benchmark]
+	fn mint() {
+		let asset_id: T::AssetId = 0u32.into();
+		let owner = created::<T>(asset_id);
+		let to: T::AccountId = account("to", 0, 0);
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(owner), asset_id, to.clone(), MIN_BALANCE);
+
+		assert_eq!(Barrel::<T>::balance(asset_id, &to), MIN_BALANCE);
+	}
+
+	// worst case: the account is reaped, and its dust burned.
+	#[benchmark]
+	fn burn() {
+		let asset_id: T::AssetId = 0u32.into();
+		let owner = created::<T>(asset_id);
+		let from: T::AccountId = account("from", 0, 0);
+		Barrel::<T>::mint(RawOrigin::Signed(owner.clone()).into(), asset_id, from.clone(), 100)
+			.unwrap();
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(owner), asset_id, from.clone(), 100 - MIN_BALANCE + 1);
+
+		assert!(!Balances::<T>::contains_key(asset_id, &from));
+		assert!(Barrel::<T>::supply(asset_id).is_zero());
+	}
+
+	// worst case: the recipient is created, and the sender is reaped.
+	#[benchmark]
+	fn transfer() {
+		let asset_id: T::AssetId = 0u32.into();
+		let owner = created::<T>(asset_id);
+		let from: T::AccountId = account("from", 0, 0);
+		let to: T::AccountId = account("to", 0, 0);
+		Barrel::<T>::mint(RawOrigin::Signed(owner).into(), asset_id, from.clone(), 100).unwrap();
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(from.clone()), asset_id, to.clone(), 100 - MIN_BALANCE + 1);
+
+		assert!(!Balances::<T>::contains_key(asset_id, &from));
+		assert_eq!(Barrel::<T>::balance(asset_id, &to), 100 - MIN_BALANCE + 1);
+	}
+
+	#[benchmark]
+	fn freeze() {
+		let asset_id: T::AssetId = 0u32.into();
+		let owner = created::<T>(asset_id);
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(owner), asset_id);
+
+		assert!(Asset::<T>::get(asset_id).unwrap().is_frozen);
+	}
+
+	#[benchmark]
+	fn thaw() {
+		let asset_id: T::AssetId = 0u32.into();
+		let owner = created::<T>(asset_id);
+		Barrel::<T>::freeze(RawOrigin::Signed(owner.clone()).into(), asset_id).unwrap();
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(owner), asset_id);
+
+		assert!(!Asset::<T>::get(asset_id).unwrap().is_frozen);
+	}
+
+	// worst case: the name and symbol are as long as they can be.
+	#[benchmark]
+	fn set_metadata() {
+		let asset_id: T::AssetId = 0u32.into();
+		let owner = created::<T>(asset_id);
+		let string = vec![b'x'; T::StringLimit::get() as usize];
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(owner), asset_id, string.clone(), string.clone(), 12);
+
+		assert_eq!(Metadata::<T>::get(asset_id).name.into_inner(), string);
+	}
+
+	// worst case: the asset has metadata, which is removed too.
+	#[benchmark]
+	fn destroy() {
+		let asset_id: T::AssetId = 0u32.into();
+		let owner = created::<T>(asset_id);
+		let string = vec![b'x'; T::StringLimit::get() as usize];
+		Barrel::<T>::set_metadata(
+			RawOrigin::Signed(owner.clone()).into(),
+			asset_id,
+			string.clone(),
+			string,
+			12,
+		)
+		.unwrap();
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(owner), asset_id);
+
+		assert!(!Asset::<T>::contains_key(asset_id));
+	}
+
+	impl_benchmark_test_suite!(
+		Barrel,
+		crate::assets::barrel::tests::test_state_new(),
+		crate::assets::barrel::tests::Runtime
+	);
+}
@@ -0,0 +1,257 @@
+//! Implementation of the `fungibles` traits of FRAME for the assets barrel.
+//!
+//! Together with `fungible::ItemOf`, this allows other barrels that are generic over a `fungible`
+//! currency to use any single asset of this barrel.
+
+use super::barrel::*;
+use frame::{
+	prelude::*,
+	traits::{
+		fungibles,
+		tokens::{DepositConsequence, Fortitude, Preservation, Provenance, WithdrawConsequence},
+		Zero,
+	},
+};
+
+impl<T: Config> fungibles::Inspect<T::AccountId> for Barrel<T> {
+	type AssetId = T::AssetId;
+	type Balance = Balance;
+
+	fn total_issuance(asset: T::AssetId) -> Balance {
+		Self::supply(asset)
+	}
+
+	/// Asset accounts exist as long as they have at least the minimum balance of the asset.
+	fn minimum_balance(asset: T::AssetId) -> Balance {
+		Asset::<T>::get(asset).map_or(Zero::zero(), |details| details.min_balance)
+	}
+
+	fn total_balance(asset: T::AssetId, who: &T::AccountId) -> Balance {
+		Self::balance(asset, who)
+			.saturating_add(<Self as fungibles::InspectHold<_>>::total_balance_on_hold(asset, who))
+	}
+
+	fn balance(asset: T::AssetId, who: &T::AccountId) -> Balance {
+		Self::balance(asset, who)
+	}
+
+	fn reducible_balance(
+		asset: T::AssetId,
+		who: &T::AccountId,
+		preservation: Preservation,
+		_force: Fortitude,
+	) -> Balance {
+		match Asset::<T>::get(asset) {
+			Some(details) if !details.is_frozen => {
+				let free = Self::balance(asset, who);
+				if preservation == Preservation::Expendable {
+					free
+				} else {
+					free.saturating_sub(Self::minimum_balance(asset))
+				}
+			},
+			_ => Zero::zero(),
+		}
+	}
+
+	fn can_deposit(
+		asset: T::AssetId,
+		who: &T::AccountId,
+		amount: Balance,
+		provenance: Provenance,
+	) -> DepositConsequence {
+		let details = match Asset::<T>::get(asset) {
+			Some(details) => details,
+			None => return DepositConsequence::UnknownAsset,
+		};
+		if amount.is_zero() {
+			return DepositConsequence::Success
+		}
+		if details.is_frozen {
+			return DepositConsequence::Blocked
+		}
+
+		if provenance == Provenance::Minted && details.supply.checked_add(amount).is_none() {
+			return DepositConsequence::Overflow
+		}
+		match Self::balance(asset, who).checked_add(amount) {
+			None => return DepositConsequence::Overflow,
+			Some(balance) if balance < details.min_balance =>
+				return DepositConsequence::BelowMinimum,
+			_ => {},
+		}
+
+		DepositConsequence::Success
+	}
+
+	fn can_withdraw(
+		asset: T::AssetId,
+		who: &T::AccountId,
+		amount: Balance,
+	) -> WithdrawConsequence<Balance> {
+		let details = match Asset::<T>::get(asset) {
+			Some(details) => details,
+			None => return WithdrawConsequence::UnknownAsset,
+		};
+		if amount.is_zero() {
+			return WithdrawConsequence::Success
+		}
+		if details.is_frozen {
+			return WithdrawConsequence::Frozen
+		}
+
+		if details.supply < amount {
+			return WithdrawConsequence::Underflow
+		}
+		let Some(remaining) = Self::balance(asset, who).checked_sub(amount) else {
+			return WithdrawConsequence::BalanceLow
+		};
+		if !remaining.is_zero() && remaining < details.min_balance {
+			return WithdrawConsequence::ReducedToZero(remaining)
+		}
+
+		WithdrawConsequence::Success
+	}
+
+	fn asset_exists(asset: T::AssetId) -> bool {
+		Asset::<T>::contains_key(asset)
+	}
+}
+
+impl<T: Config> fungibles::Unbalanced<T::AccountId> for Barrel<T> {
+	fn handle_dust(dust: fungibles::Dust<T::AccountId, Self>) {
+		let asset = dust.0;
+		let amount = dust.1;
+		Asset::<T>::mutate(asset, |maybe_details| {
+			if let Some(details) = maybe_details {
+				details.supply = details.supply.saturating_sub(amount);
+			}
+		});
+	}
+
+	/// Set the free balance of `who` to `amount`.
+	///
+	/// If `amount` is below the minimum balance of the asset, `who` is reaped, and `amount` is
+	/// returned as dust.
+	fn write_balance(
+		asset: T::AssetId,
+		who: &T::AccountId,
+		amount: Balance,
+	) -> Result<Option<Balance>, DispatchError> {
+		let details = Asset::<T>::get(asset).ok_or(Error::<T>::UnknownAsset)?;
+		if amount < details.min_balance {
+			Balances::<T>::remove(asset, who);
+			Ok(Some(amount).filter(|dust| !dust.is_zero()))
+		} else {
+			Balances::<T>::insert(asset, who, amount);
+			Ok(None)
+		}
+	}
+
+	fn set_total_issuance(asset: T::AssetId, amount: Balance) {
+		Asset::<T>::mutate(asset, |maybe_details| {
+			if let Some(details) = maybe_details {
+				details.supply = amount;
+			}
+		});
+	}
+}
+
+impl<T: Config> fungibles::Mutate<T::AccountId> for Barrel<T> {
+	fn done_mint_into(asset_id: T::AssetId, who: &T::AccountId, amount: Balance) {
+		Self::deposit_event(Event::Issued { asset_id, to: who.clone(), amount });
+	}
+
+	fn done_burn_from(asset_id: T::AssetId, who: &T::AccountId, amount: Balance) {
+		Self::deposit_event(Event::Burned { asset_id, who: who.clone(), amount });
+	}
+
+	fn done_transfer(
+		asset_id: T::AssetId,
+		source: &T::AccountId,
+		dest: &T::AccountId,
+		amount: Balance,
+	) {
+		Self::deposit_event(Event::Transferred {
+			asset_id,
+			from: source.clone(),
+			to: dest.clone(),
+			amount,
+		});
+	}
+}
+
+impl<T: Config> fungibles::InspectHold<T::AccountId> for Barrel<T> {
+	type Reason = T::RuntimeHoldReason;
+
+	fn total_balance_on_hold(asset: T::AssetId, who: &T::AccountId) -> Balance {
+		Holds::<T>::iter_prefix_values((asset, who.clone())).sum()
+	}
+
+	fn reducible_total_balance_on_hold(
+		asset: T::AssetId,
+		who: &T::AccountId,
+		_force: Fortitude,
+	) -> Balance {
+		Self::total_balance_on_hold(asset, who)
+	}
+
+	fn balance_on_hold(asset: T::AssetId, reason: &Self::Reason, who: &T::AccountId) -> Balance {
+		Holds::<T>::get((asset, who, reason))
+	}
+
+	fn hold_available(asset: T::AssetId, _reason: &Self::Reason, _who: &T::AccountId) -> bool {
+		Asset::<T>::contains_key(asset)
+	}
+}
+
+impl<T: Config> fungibles::UnbalancedHold<T::AccountId> for Barrel<T> {
+	fn set_balance_on_hold(
+		asset: T::AssetId,
+		reason: &Self::Reason,
+		who: &T::AccountId,
+		amount: Balance,
+	) -> DispatchResult {
+		ensure!(Asset::<T>::contains_key(asset), Error::<T>::UnknownAsset);
+		if amount.is_zero() {
+			Holds::<T>::remove((asset, who, reason));
+		} else {
+			Holds::<T>::insert((asset, who, reason), amount);
+		}
+		Ok(())
+	}
+}
+
+impl<T: Config> fungibles::MutateHold<T::AccountId> for Barrel<T> {
+	fn done_hold(asset_id: T::AssetId, reason: &Self::Reason, who: &T::AccountId, amount: Balance) {
+		Self::deposit_event(Event::Held { asset_id, who: who.clone(), reason: *reason, amount });
+	}
+
+	fn done_release(
+		asset_id: T::AssetId,
+		reason: &Self::Reason,
+		who: &T::AccountId,
+		amount: Balance,
+	) {
+		Self::deposit_event(Event::Released {
+			asset_id,
+			who: who.clone(),
+			reason: *reason,
+			amount,
+		});
+	}
+
+	fn done_burn_held(
+		asset_id: T::AssetId,
+		reason: &Self::Reason,
+		who: &T::AccountId,
+		amount: Balance,
+	) {
+		Self::deposit_event(Event::BurnedHeld {
+			asset_id,
+			who: who.clone(),
+			reason: *reason,
+			amount,
+		});
+	}
+}
@@ -0,0 +1,130 @@
+//! Storage migrations for the assets barrel.
+
+use super::barrel::*;
+#[cfg(feature = "try-runtime")]
+use frame::deps::sp_runtime::TryRuntimeError;
+use frame::{
+	deps::frame_support::traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
+	derive::{Decode, Encode},
+	prelude::*,
+};
+
+/// Version 1: each asset has a [`AssetDetails::min_balance`], instead of every asset account
+/// existing as long as its balance is non-zero.
+pub mod v1 {
+	use super::*;
+
+	mod v0 {
+		use super::*;
+
+		/// The details of an asset, before it had a minimum balance.
+		#[derive(Encode, Decode)]
+		pub struct AssetDetails<AccountId, DepositBalance> {
+			pub owner: AccountId,
+			pub admin: AccountId,
+			pub supply: Balance,
+			pub deposit: DepositBalance,
+			pub is_frozen: bool,
+		}
+	}
+
+	/// Give each existing asset a minimum balance of one, which is what all of them had until
+	/// now, so that no existing account is reaped.
+	pub struct MigrateToMinBalance<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToMinBalance<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if Barrel::<T>::on_chain_storage_version() != 0 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			let mut migrated: u64 = 0;
+			Asset::<T>::translate::<v0::AssetDetails<T::AccountId, DepositBalanceOf<T>>, _>(
+				|_, old| {
+					migrated += 1;
+					Some(AssetDetails {
+						owner: old.owner,
+						admin: old.admin,
+						supply: old.supply,
+						deposit: old.deposit,
+						is_frozen: old.is_frozen,
+						min_balance: 1,
+					})
+				},
+			);
+
+			StorageVersion::new(1).put::<Barrel<T>>();
+			T::DbWeight::get().reads_writes(migrated + 1, migrated + 1)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+			ensure!(Barrel::<T>::on_chain_storage_version() == 0, "can only upgrade from v0");
+			Ok((Asset::<T>::iter_keys().count() as u64).encode())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(state: Vec<u8>) -> Result<(), TryRuntimeError> {
+			let count: u64 = Decode::decode(&mut &state[..])
+				.map_err(|_| "cannot decode the pre-upgrade state")?;
+
+			ensure!(Barrel::<T>::on_chain_storage_version() == 1, "storage version not updated");
+			ensure!(
+				Asset::<T>::iter_values().filter(|details| details.min_balance == 1).count() as u64 ==
+					count,
+				"asset not migrated"
+			);
+			Ok(())
+		}
+	}
+
+	#[cfg(test)]
+	mod tests {
+		use super::*;
+		use crate::assets::barrel::tests::{test_state_new, Runtime};
+		use frame::{deps::frame_support::storage::unhashed, testing_prelude::*};
+
+		#[test]
+		fn migrate_to_min_balance_works() {
+			test_state_new().execute_with(|| {
+				// given a v0 asset.
+				StorageVersion::new(0).put::<Barrel<Runtime>>();
+				let old = v0::AssetDetails {
+					owner: 1u64,
+					admin: 2u64,
+					supply: 100 as Balance,
+					deposit: 10 as Balance,
+					is_frozen: true,
+				};
+				unhashed::put_raw(&Asset::<Runtime>::hashed_key_for(7), &old.encode());
+				#[cfg(feature = "try-runtime")]
+				let state = MigrateToMinBalance::<Runtime>::pre_upgrade().unwrap();
+
+				// when:
+				MigrateToMinBalance::<Runtime>::on_runtime_upgrade();
+
+				// then:
+				#[cfg(feature = "try-runtime")]
+				MigrateToMinBalance::<Runtime>::post_upgrade(state).unwrap();
+				assert_eq!(Barrel::<Runtime>::on_chain_storage_version(), 1);
+				assert_eq!(
+					Asset::<Runtime>::get(7),
+					Some(AssetDetails {
+						owner: 1,
+						admin: 2,
+						supply: 100,
+						deposit: 10,
+						is_frozen: true,
+						min_balance: 1
+					})
+				);
+
+				// and running it again is a no-op.
+				let key = Asset::<Runtime>::hashed_key_for(7);
+				unhashed::put_raw(&key, &old.encode());
+				MigrateToMinBalance::<Runtime>::on_runtime_upgrade();
+				assert_eq!(unhashed::get_raw(&key), Some(old.encode()));
+			});
+		}
+	}
+}
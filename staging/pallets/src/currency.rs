@@ -1,26 +1,559 @@
 pub use barrel::*;
+pub use weights::WeightInfo;
 
-#[frame::barrel(dev_mode)]
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+mod impl_fungible;
+pub mod migrations;
+pub mod runtime_api;
+pub mod weights;
+
+#[frame::barrel]
 pub mod barrel {
-	use frame::prelude::*;
+	use super::WeightInfo;
+	use frame::{
+		deps::{
+			frame_support::{
+				storage::with_storage_layer,
+				traits::{OnKilledAccount, OnNewAccount},
+			},
+			sp_runtime::{ArithmeticError, Perbill},
+		},
+		derive::{Decode, Encode, MaxEncodedLen, TypeInfo},
+		prelude::*,
+		traits::{Saturating, StoredMap, UniqueSaturatedInto, Zero},
+	};
 	pub type Balance = u128;
 
+	/// The in-code storage version.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
+	/// The largest number of eras that [`Barrel::projected_issuance`] projects over.
+	///
+	/// The projection takes one step per era, and can be requested by anyone through the runtime
+	/// API, so it must be bounded.
+	pub const MAX_PROJECTED_ERAS: u32 = 10_000;
+
+	/// The balances of an account, kept in [`Config::AccountStore`].
+	///
+	/// An account exists as long as any of these is non-zero.
+	#[derive(
+		Encode, Decode, MaxEncodedLen, TypeInfo, Clone, Copy, Default, Eq, PartialEq, Debug,
+	)]
+	pub struct AccountData {
+		/// The balance that can be transferred.
+		pub free: Balance,
+		/// The sum of all funds of the account that are held, for any reason.
+		pub reserved: Balance,
+	}
+
+	/// A linear vesting schedule.
+	///
+	/// `locked` is fully locked until `starting_block`, after which `per_block` is unlocked every
+	/// block.
+	#[derive(Encode, Decode, MaxEncodedLen, TypeInfo, Clone, Copy, Eq, PartialEq, Debug)]
+	pub struct VestingSchedule<BlockNumber> {
+		/// The amount that is locked initially.
+		pub locked: Balance,
+		/// The amount that is unlocked per block, after `starting_block`.
+		pub per_block: Balance,
+		/// The block at which unlocking starts.
+		pub starting_block: BlockNumber,
+	}
+
+	impl<BlockNumber: Saturating + UniqueSaturatedInto<Balance> + Copy> VestingSchedule<BlockNumber> {
+		/// The amount that is still locked at block `now`.
+		pub fn locked_at(&self, now: BlockNumber) -> Balance {
+			let vested_blocks: Balance =
+				now.saturating_sub(self.starting_block).unique_saturated_into();
+			self.locked.saturating_sub(self.per_block.saturating_mul(vested_blocks))
+		}
+
+		/// Whether this schedule can ever unlock its funds.
+		pub(crate) fn is_valid(&self) -> bool {
+			!self.locked.is_zero() && !self.per_block.is_zero()
+		}
+	}
+
+	/// The identifier of a scheduled transfer.
+	pub type ScheduledTransferId = u32;
+
+	/// A transfer whose funds are held in escrow until they are released to `to` at block `at`.
+	#[derive(Encode, Decode, MaxEncodedLen, TypeInfo, Clone, Eq, PartialEq, Debug)]
+	pub struct ScheduledTransfer<AccountId, BlockNumber> {
+		/// The sender, who holds the funds until they are released.
+		pub from: AccountId,
+		/// The recipient.
+		pub to: AccountId,
+		/// The amount that is transferred.
+		pub amount: Balance,
+		/// The block at which the funds are released.
+		pub at: BlockNumber,
+	}
+
+	/// Something that can handle the dust of an account that is reaped for falling below the
+	/// existential deposit.
+	pub trait OnDust<AccountId> {
+		/// Handle `amount` of dust left behind by `who`.
+		///
+		/// Returns the part of `amount` that was not absorbed, which is burned.
+		fn on_dust(who: &AccountId, amount: Balance) -> Balance;
+	}
+
+	/// Burn all dust.
+	impl<AccountId> OnDust<AccountId> for () {
+		fn on_dust(_: &AccountId, amount: Balance) -> Balance {
+			amount
+		}
+	}
+
+	/// An [`OnDust`] implementation that sends all dust to the account returned by `A`.
+	///
+	/// If the dust cannot be deposited into `A`, it is burned.
+	pub struct DustTo<T, A>(PhantomData<(T, A)>);
+	impl<T: Config, A: Get<T::AccountId>> OnDust<T::AccountId> for DustTo<T, A> {
+		fn on_dust(_: &T::AccountId, amount: Balance) -> Balance {
+			match Barrel::<T>::credit(&A::get(), amount) {
+				Ok(_) => 0,
+				Err(_) => amount,
+			}
+		}
+	}
+
+	/// Something that receives the inflation minted at the end of each era.
+	pub trait OnInflation {
+		/// Receive `amount` of newly minted funds.
+		///
+		/// Returns the part of `amount` that was not received, which is not minted.
+		fn on_inflation(amount: Balance) -> Balance;
+	}
+
+	/// Mint no inflation.
+	impl OnInflation for () {
+		fn on_inflation(amount: Balance) -> Balance {
+			amount
+		}
+	}
+
+	/// An [`OnInflation`] implementation that deposits all inflation into the account returned by
+	/// `A`, such as a reward pot or a treasury.
+	///
+	/// If the inflation cannot be deposited into `A`, it is not minted.
+	pub struct InflationTo<T, A>(PhantomData<(T, A)>);
+	impl<T: Config, A: Get<T::AccountId>> OnInflation for InflationTo<T, A> {
+		fn on_inflation(amount: Balance) -> Balance {
+			match Barrel::<T>::credit(&A::get(), amount) {
+				Ok(_) => 0,
+				Err(_) => amount,
+			}
+		}
+	}
+
+	/// Something that reacts to funds being transferred from one account to another.
+	#[impl_trait_for_tuples::impl_for_tuples(30)]
+	pub trait OnTransfer<AccountId> {
+		/// `amount` was transferred from `from` to `to`.
+		fn on_transfer(from: &AccountId, to: &AccountId, amount: Balance);
+	}
+
+	/// Something that reacts to the [`AccountData`] of an account changing, for any reason.
+	///
+	/// This is called once the change is complete, so that [`Holds`] are already up to date. It
+	/// must not alter any balance itself.
+	pub trait OnBalanceChanged<AccountId> {
+		/// The account data of `who` changed to `data`.
+		fn on_balance_changed(who: &AccountId, data: &AccountData);
+
+		/// The worst case weight of a single call to [`OnBalanceChanged::on_balance_changed`].
+		///
+		/// This is charged by the calls of this barrel, once for each change that they make.
+		fn weight() -> Weight;
+	}
+
+	#[impl_trait_for_tuples::impl_for_tuples(30)]
+	impl<AccountId> OnBalanceChanged<AccountId> for Tuple {
+		fn on_balance_changed(who: &AccountId, data: &AccountData) {
+			for_tuples!( #( Tuple::on_balance_changed(who, data); )* );
+		}
+
+		fn weight() -> Weight {
+			let mut weight = Weight::zero();
+			for_tuples!( #( weight = weight.saturating_add(Tuple::weight()); )* );
+			weight
+		}
+	}
+
+	/// The fee charged on top of each transfer.
+	///
+	/// The fee must not decrease as the transferred amount grows.
+	pub trait TransferFee {
+		/// The fee for transferring `amount`.
+		fn fee(amount: Balance) -> Balance;
+	}
+
+	/// Charge no fee.
+	impl TransferFee for () {
+		fn fee(_: Balance) -> Balance {
+			0
+		}
+	}
+
+	/// A flat fee of `F` per transfer.
+	pub struct FlatFee<F>(PhantomData<F>);
+	impl<F: Get<Balance>> TransferFee for FlatFee<F> {
+		fn fee(_: Balance) -> Balance {
+			F::get()
+		}
+	}
+
+	/// A fee of `P` of the transferred amount, rounded up.
+	pub struct PercentageFee<P>(PhantomData<P>);
+	impl<P: Get<Perbill>> TransferFee for PercentageFee<P> {
+		fn fee(amount: Balance) -> Balance {
+			P::get().mul_ceil(amount)
+		}
+	}
+
+	/// A fee of a percentage of the transferred amount, which depends on the amount.
+	///
+	/// `Tiers` are `(threshold, rate)` pairs, sorted by threshold. The rate of the last tier whose
+	/// threshold is reached applies to the whole amount, rounded up. Transfers below the first
+	/// threshold are free. Rates must not decrease from one tier to the next.
+	pub struct TieredFee<Tiers>(PhantomData<Tiers>);
+	impl<Tiers: Get<Vec<(Balance, Perbill)>>> TransferFee for TieredFee<Tiers> {
+		fn fee(amount: Balance) -> Balance {
+			Tiers::get()
+				.into_iter()
+				.take_while(|(threshold, _)| *threshold <= amount)
+				.last()
+				.map_or(0, |(_, rate)| rate.mul_ceil(amount))
+		}
+	}
+
 	#[barrel::config]
-	pub trait Config: frame_system::Config {}
+	pub trait Config: frame_system::Config {
+		/// The overarching event type.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+		/// The origin that is allowed to create new funds via [`Barrel::mint`].
+		type MintOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+		/// The origin that is allowed to freeze and thaw accounts, via [`Barrel::freeze_account`]
+		/// and [`Barrel::thaw_account`].
+		type FreezeOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+		/// The minimum free balance that an account must have in order to exist.
+		///
+		/// Accounts whose free balance falls below this, and that have no funds on hold, are
+		/// reaped.
+		#[barrel::constant]
+		type ExistentialDeposit: Get<Balance>;
+
+		/// What to do with the dust of reaped accounts.
+		type DustRemoval: OnDust<Self::AccountId>;
+
+		/// The reasons for which funds can be held.
+		///
+		/// Held funds are not part of the free balance of an account, and can therefore not be
+		/// transferred. They still count towards the [`TotalIssuance`].
+		type RuntimeHoldReason: Parameter + Member + MaxEncodedLen + Copy + From<HoldReason>;
+
+		/// The maximum number of vesting schedules that an account can have.
+		#[barrel::constant]
+		type MaxVestingSchedules: Get<u32>;
+
+		/// The maximum number of transfers in a single [`Barrel::transfer_many`].
+		#[barrel::constant]
+		type MaxTransfers: Get<u32>;
+
+		/// The maximum number of transfers that can be scheduled to be released at the same block.
+		#[barrel::constant]
+		type MaxScheduledPerBlock: Get<u32>;
+
+		/// The maximum number of distinct blocks that transfers can be scheduled to be released at,
+		/// at once.
+		#[barrel::constant]
+		type MaxAgendaBlocks: Get<u32>;
+
+		/// The maximum number of scheduled transfers that are released in a single block.
+		///
+		/// Transfers that are due beyond this are carried over to the following blocks. This also
+		/// bounds the number of blocks whose agenda is looked at in a single block.
+		#[barrel::constant]
+		type MaxReleasesPerBlock: Get<u32>;
+
+		/// Handlers for transfers made through this barrel.
+		///
+		/// Their weight is not charged, unlike that of [`Config::OnBalanceChanged`].
+		type OnTransfer: OnTransfer<Self::AccountId>;
+
+		/// Handlers for any change to the [`AccountData`] of an account, including its creation
+		/// and removal.
+		///
+		/// Their [`OnBalanceChanged::weight`] is charged for each change made by a call.
+		type OnBalanceChanged: OnBalanceChanged<Self::AccountId>;
+
+		/// Handlers for the creation of an account, when it is first endowed.
+		///
+		/// Not to be confused with `frame_system::Config::OnNewAccount`. Their weight is not
+		/// charged, so handlers that do more than a constant amount of work should use
+		/// [`Config::OnBalanceChanged`] instead.
+		type OnAccountCreated: OnNewAccount<Self::AccountId>;
+
+		/// Handlers for the removal of an account, when it is reaped or runs out of funds.
+		///
+		/// Not to be confused with `frame_system::Config::OnKilledAccount`. Their weight is not
+		/// charged, so handlers that do more than a constant amount of work should use
+		/// [`Config::OnBalanceChanged`] instead.
+		type OnAccountKilled: OnKilledAccount<Self::AccountId>;
+
+		/// Where the [`AccountData`] of each account is stored.
+		///
+		/// This is typically `frame_system`, in which case `frame_system::Config::AccountData`
+		/// must be, or contain, [`AccountData`].
+		type AccountStore: StoredMap<Self::AccountId, AccountData>;
+
+		/// The number of blocks in an era, at the end of which inflation is minted.
+		#[barrel::constant]
+		type EraDuration: Get<BlockNumberFor<Self>>;
+
+		/// The number of blocks in a year, used to convert [`Config::YearlyInflation`] into the
+		/// amount that is minted per era.
+		#[barrel::constant]
+		type BlocksPerYear: Get<BlockNumberFor<Self>>;
+
+		/// The inflation over a year, as a fraction of the total issuance.
+		#[barrel::constant]
+		type YearlyInflation: Get<Perbill>;
+
+		/// The beneficiary of the inflation minted at the end of each era.
+		type OnInflation: OnInflation;
+
+		/// The fee that the sender pays on top of each transfer made through the calls of this
+		/// barrel.
+		///
+		/// This is a protocol fee, separate from any transaction fee.
+		type TransferFee: TransferFee;
+
+		/// The part of each transfer fee that is burned. The rest is credited to
+		/// [`Config::FeeCollector`].
+		#[barrel::constant]
+		type FeeBurn: Get<Perbill>;
+
+		/// The account that collects the part of the transfer fees that is not burned.
+		///
+		/// If the fee cannot be credited to it, because it would fall below the existential
+		/// deposit, it is burned instead.
+		type FeeCollector: Get<Self::AccountId>;
+
+		/// Weight information for the calls of this barrel.
+		type WeightInfo: WeightInfo;
+	}
 
 	#[barrel::barrel]
+	#[barrel::storage_version(STORAGE_VERSION)]
 	pub struct Barrel<T>(_);
 
+	/// The reasons for which this barrel holds funds.
+	#[barrel::composite_enum]
+	pub enum HoldReason {
+		/// Funds held in escrow until a scheduled transfer is released.
+		ScheduledTransfer,
+	}
+
+	/// The number of accounts that currently exist.
 	#[barrel::storage]
-	pub type Balances<T: Config> = StorageMap<_, _, T::AccountId, Balance>;
+	pub type HoldersCount<T: Config> = StorageValue<_, u32, ValueQuery>;
 
 	#[barrel::storage]
 	pub type TotalIssuance<T: Config> = StorageValue<_, Balance, ValueQuery>;
 
+	/// The funds of each account that are held, per [`Config::RuntimeHoldReason`].
+	#[barrel::storage]
+	pub type Holds<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		T::RuntimeHoldReason,
+		Balance,
+		ValueQuery,
+	>;
+
+	/// The vesting schedules of each account.
+	///
+	/// The funds that are still locked by these schedules cannot be transferred, burned or held.
+	#[barrel::storage]
+	pub type Vesting<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		BoundedVec<VestingSchedule<BlockNumberFor<T>>, T::MaxVestingSchedules>,
+		ValueQuery,
+	>;
+
+	/// The accounts that are frozen.
+	///
+	/// The funds of a frozen account cannot be transferred, burned or held until it is thawed. It
+	/// can still receive funds.
+	#[barrel::storage]
+	pub type FrozenAccounts<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, ()>;
+
+	/// The amount that a spender is allowed to transfer on behalf of an owner, keyed by owner and
+	/// spender.
+	#[barrel::storage]
+	pub type Allowances<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		T::AccountId,
+		Balance,
+		ValueQuery,
+	>;
+
+	/// The identifier of the next scheduled transfer.
+	#[barrel::storage]
+	pub type NextScheduledTransferId<T: Config> = StorageValue<_, ScheduledTransferId, ValueQuery>;
+
+	/// The transfers that are scheduled and not released yet.
+	#[barrel::storage]
+	pub type ScheduledTransfers<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		ScheduledTransferId,
+		ScheduledTransfer<T::AccountId, BlockNumberFor<T>>,
+	>;
+
+	/// The transfers that are scheduled to be released at each block.
+	///
+	/// Transfers that are due, but could not be released yet, remain in the agenda of their block.
+	#[barrel::storage]
+	pub type TransferAgenda<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		BlockNumberFor<T>,
+		BoundedVec<ScheduledTransferId, T::MaxScheduledPerBlock>,
+		ValueQuery,
+	>;
+
+	/// The blocks with a [`TransferAgenda`], in ascending order.
+	///
+	/// Blocks without any scheduled transfer are skipped when releasing them, and this is only
+	/// written when a block is added or removed.
+	#[barrel::storage]
+	pub type AgendaBlocks<T: Config> =
+		StorageValue<_, BoundedVec<BlockNumberFor<T>, T::MaxAgendaBlocks>, ValueQuery>;
+
+	#[barrel::event]
+	#[barrel::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// `amount` was minted into `to`.
+		Minted { to: T::AccountId, amount: Balance },
+		/// `amount` of inflation was minted at the end of an era.
+		Inflated { amount: Balance },
+		/// `who` paid a transfer fee, of which `burned` was burned and `collected` was credited to
+		/// the fee collector.
+		TransferFeePaid { who: T::AccountId, burned: Balance, collected: Balance },
+		/// `amount` was transferred from `from` to `to`.
+		Transferred { from: T::AccountId, to: T::AccountId, amount: Balance },
+		/// `amount` was burned from `who`.
+		Burned { who: T::AccountId, amount: Balance },
+		/// `amount` of `who`'s free balance was put on hold for `reason`.
+		Reserved { who: T::AccountId, reason: T::RuntimeHoldReason, amount: Balance },
+		/// `amount` held for `reason` was released back into `who`'s free balance.
+		Unreserved { who: T::AccountId, reason: T::RuntimeHoldReason, amount: Balance },
+		/// `amount` held for `reason` was slashed from `who` and burned.
+		Slashed { who: T::AccountId, reason: T::RuntimeHoldReason, amount: Balance },
+		/// `amount` held by `from` for `reason` was moved into the free balance of `to`.
+		ReserveRepatriated {
+			from: T::AccountId,
+			to: T::AccountId,
+			reason: T::RuntimeHoldReason,
+			amount: Balance,
+		},
+		/// A new account `who` was created with `free` balance.
+		Endowed { who: T::AccountId, free: Balance },
+		/// `who` was reaped, leaving behind `dust`.
+		Reaped { who: T::AccountId, dust: Balance },
+		/// A new vesting `schedule` was added to `who`.
+		VestingScheduleAdded { who: T::AccountId, schedule: VestingSchedule<BlockNumberFor<T>> },
+		/// All vesting schedules of `who` have completed.
+		VestingCompleted { who: T::AccountId },
+		/// The allowance of `spender` over the funds of `owner` was set to `amount`.
+		Approved { owner: T::AccountId, spender: T::AccountId, amount: Balance },
+		/// `spender` used `amount` of its allowance over the funds of `owner`.
+		AllowanceSpent { owner: T::AccountId, spender: T::AccountId, amount: Balance },
+		/// `who` was frozen.
+		AccountFrozen { who: T::AccountId },
+		/// `who` was thawed.
+		AccountThawed { who: T::AccountId },
+		/// `amount` was put in escrow by `from`, to be transferred to `to` at block `at`.
+		TransferScheduled {
+			id: ScheduledTransferId,
+			from: T::AccountId,
+			to: T::AccountId,
+			amount: Balance,
+			at: BlockNumberFor<T>,
+		},
+		/// The scheduled transfer `id` was cancelled, and its funds released back to the sender.
+		ScheduledTransferCancelled { id: ScheduledTransferId },
+		/// The scheduled transfer `id` was released to its recipient.
+		ScheduledTransferReleased { id: ScheduledTransferId },
+		/// The scheduled transfer `id` could not be released to its recipient because of `error`,
+		/// and its funds were released back to the sender instead.
+		ScheduledTransferFailed { id: ScheduledTransferId, error: DispatchError },
+	}
+
+	#[barrel::error]
+	pub enum Error<T> {
+		/// The account has never received any funds.
+		NonExistentAccount,
+		/// The account does not have enough free balance for this operation.
+		InsufficientBalance,
+		/// The account does not have enough funds on hold for the given reason.
+		InsufficientHold,
+		/// The operation would create an account with less than the existential deposit.
+		ExistentialDeposit,
+		/// The operation would reap the account, but it must be kept alive.
+		KeepAlive,
+		/// The operation would use funds that are still locked by a vesting schedule.
+		VestingLocked,
+		/// The vesting schedule would never unlock any funds.
+		InvalidSchedule,
+		/// The account already has the maximum number of vesting schedules.
+		TooManySchedules,
+		/// The spender is not allowed to transfer this much on behalf of the owner.
+		InsufficientAllowance,
+		/// The account is frozen.
+		AccountFrozen,
+		/// The account is already frozen.
+		AlreadyFrozen,
+		/// The account is not frozen.
+		NotFrozen,
+		/// A transfer can only be scheduled for a future block.
+		ReleaseNotInFuture,
+		/// A transfer of nothing cannot be scheduled.
+		ZeroAmount,
+		/// The maximum number of transfers is already scheduled for this block.
+		AgendaFull,
+		/// Transfers are already scheduled for the maximum number of blocks.
+		TooManyAgendaBlocks,
+		/// No transfer is scheduled with this identifier.
+		UnknownScheduledTransfer,
+		/// Only the sender of a scheduled transfer can cancel it.
+		NotTransferSender,
+		/// A scheduled transfer can only be cancelled before its release block.
+		TooLateToCancel,
+	}
+
 	#[derive(frame::derive::DefaultNoBound)]
 	#[barrel::genesis_config]
 	pub struct GenesisConfig<T: Config> {
 		pub balances: Vec<(T::AccountId, Balance)>,
+		/// Vesting schedules of accounts in `balances`, as `(who, locked, per_block,
+		/// starting_block)`.
+		pub vesting: Vec<(T::AccountId, Balance, Balance, BlockNumberFor<T>)>,
 	}
 
 	// TODO:
@@ -30,195 +563,2587 @@ pub mod barrel {
 	impl<T: Config> BuildGenesisConfig for GenesisConfig<T> {
 		fn build(&self) {
 			for (who, amount) in &self.balances {
-				assert!(!Balances::<T>::contains_key(who), "duplicate balance in genesis");
-				Balances::<T>::insert(who, amount);
-				TotalIssuance::<T>::mutate(|t| *t += amount);
+				assert!(Barrel::<T>::account(who).is_none(), "duplicate balance in genesis");
+				assert!(*amount >= T::ExistentialDeposit::get(), "genesis balance below ED");
+				Barrel::<T>::try_mutate_account(who, |data| {
+					data.free = *amount;
+					Ok(())
+				})
+				.expect("cannot create genesis account");
+				TotalIssuance::<T>::mutate(|t| {
+					*t = t.checked_add(*amount).expect("genesis total issuance overflow")
+				});
 			}
+
+			for (who, locked, per_block, starting_block) in &self.vesting {
+				let schedule = VestingSchedule {
+					locked: *locked,
+					per_block: *per_block,
+					starting_block: *starting_block,
+				};
+				assert!(schedule.is_valid(), "invalid vesting schedule in genesis");
+				assert!(
+					Barrel::<T>::free_balance(who) >= *locked,
+					"vesting schedule in genesis locks more than the free balance"
+				);
+				Vesting::<T>::try_append(who, schedule)
+					.expect("too many vesting schedules in genesis");
+			}
+		}
+	}
+
+	#[barrel::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Barrel<T> {
+		fn on_initialize(now: BlockNumberFor<T>) -> Weight {
+			let inflation = if (now % T::EraDuration::get()).is_zero() && !now.is_zero() {
+				Self::mint_inflation();
+				T::WeightInfo::mint_inflation().saturating_add(Self::balance_hooks_weight(1))
+			} else {
+				Weight::zero()
+			};
+			inflation.saturating_add(Self::release_scheduled_transfers(now))
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn try_state(_: BlockNumberFor<T>) -> Result<(), sp_runtime::TryRuntimeError> {
+			Self::do_try_state()
 		}
 	}
 
 	#[barrel::call]
 	impl<T: Config> Barrel<T> {
+		#[barrel::call_index(0)]
+		#[barrel::weight(
+			T::WeightInfo::mint().saturating_add(Barrel::<T>::balance_hooks_weight(1))
+		)]
 		pub fn mint(origin: OriginFor<T>, to: T::AccountId, amount: Balance) -> DispatchResult {
-			let _anyone = ensure_signed(origin)?;
+			T::MintOrigin::ensure_origin(origin)?;
 
-			Balances::<T>::mutate(to, |b| *b = Some(b.unwrap_or(0) + amount));
-			TotalIssuance::<T>::mutate(|t| *t += amount);
+			Self::increase_issuance(amount)?;
+			Self::credit(&to, amount)?;
 
+			Self::deposit_event(Event::Minted { to, amount });
 			Ok(())
 		}
 
+		/// Transfer `amount` to `to`.
+		///
+		/// The sender is reaped if their free balance falls below the existential deposit.
+		#[barrel::call_index(1)]
+		#[barrel::weight(
+			T::WeightInfo::transfer().saturating_add(Barrel::<T>::balance_hooks_weight(4))
+		)]
 		pub fn transfer(origin: OriginFor<T>, to: T::AccountId, amount: Balance) -> DispatchResult {
 			let sender = ensure_signed(origin)?;
+			Self::do_transfer(&sender, &to, amount, false)
+		}
 
-			let sender_balance = Balances::<T>::get(&sender).ok_or("NonExistentAccount")?;
-			if sender_balance < amount {
-				return Err("notEnoughBalance".into())
-			}
-			let reminder = sender_balance - amount;
+		/// Same as [`Barrel::transfer`], but fails if the sender would be reaped.
+		#[barrel::call_index(2)]
+		#[barrel::weight(
+			T::WeightInfo::transfer_keep_alive()
+				.saturating_add(Barrel::<T>::balance_hooks_weight(3))
+		)]
+		pub fn transfer_keep_alive(
+			origin: OriginFor<T>,
+			to: T::AccountId,
+			amount: Balance,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::do_transfer(&sender, &to, amount, true)
+		}
+
+		/// Transfer all of the free balance of the sender to `to`, less the transfer fee.
+		///
+		/// If `keep_alive` is set, the existential deposit is kept in the sender's account.
+		#[barrel::call_index(3)]
+		#[barrel::weight(
+			T::WeightInfo::transfer_all().saturating_add(Barrel::<T>::balance_hooks_weight(4))
+		)]
+		pub fn transfer_all(
+			origin: OriginFor<T>,
+			to: T::AccountId,
+			keep_alive: bool,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let reducible = Self::reducible_balance(&sender, keep_alive);
+			let amount = reducible.saturating_sub(T::TransferFee::fee(reducible));
+
+			Self::do_transfer(&sender, &to, amount, keep_alive)
+		}
 
-			Balances::<T>::mutate(to, |b| *b = Some(b.unwrap_or(0) + amount));
-			Balances::<T>::insert(&sender, reminder);
+		/// Destroy `amount` of the caller's funds, reducing the total issuance.
+		#[barrel::call_index(4)]
+		#[barrel::weight(
+			T::WeightInfo::burn().saturating_add(Barrel::<T>::balance_hooks_weight(2))
+		)]
+		pub fn burn(origin: OriginFor<T>, amount: Balance) -> DispatchResult {
+			let who = ensure_signed(origin)?;
 
+			Self::debit(&who, amount, false)?;
+			Self::decrease_issuance(amount)?;
+
+			Self::deposit_event(Event::Burned { who: who.clone(), amount });
+			Self::reap_if_dust(&who);
 			Ok(())
 		}
-	}
 
-	#[cfg(test)]
-	mod tests {
-		use crate::currency::barrel::{self as barrel_currency, *};
-		use frame::testing_prelude::*;
+		/// Execute all of `transfers` from the caller, in order.
+		///
+		/// This is all-or-nothing: if any of the transfers fails, the whole call fails and none of
+		/// them are executed. A [`Event::Transferred`] is emitted for each of them.
+		#[barrel::call_index(5)]
+		#[barrel::weight(
+			T::WeightInfo::transfer_many(transfers.len() as u32)
+				.saturating_add(Barrel::<T>::balance_hooks_weight(3 * transfers.len() as u32 + 1))
+		)]
+		pub fn transfer_many(
+			origin: OriginFor<T>,
+			transfers: BoundedVec<(T::AccountId, Balance), T::MaxTransfers>,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
 
-		construct_runtime!(
-			pub struct Runtime {
-				System: frame_system,
-				Currency: barrel_currency,
+			// a dispatch is executed in its own storage layer, so an error in any of these reverts
+			// all of the previous ones.
+			for (to, amount) in transfers {
+				Self::do_transfer(&sender, &to, amount, false)?;
 			}
-		);
 
-		#[derive_impl(frame_system::config_preludes::TestDefaultConfig as frame_system::DefaultConfig)]
-		impl frame_system::Config for Runtime {
-			type Block = MockBlock<Runtime>;
+			Ok(())
 		}
 
-		impl barrel_currency::Config for Runtime {}
+		/// Transfer `schedule.locked` to `to`, and lock it according to `schedule`.
+		#[barrel::call_index(6)]
+		#[barrel::weight(
+			T::WeightInfo::vested_transfer().saturating_add(Barrel::<T>::balance_hooks_weight(4))
+		)]
+		pub fn vested_transfer(
+			origin: OriginFor<T>,
+			to: T::AccountId,
+			schedule: VestingSchedule<BlockNumberFor<T>>,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!(schedule.is_valid(), Error::<T>::InvalidSchedule);
+			ensure!(
+				Vesting::<T>::decode_len(&to).unwrap_or_default() <
+					T::MaxVestingSchedules::get() as usize,
+				Error::<T>::TooManySchedules
+			);
 
-		const ALICE: <Runtime as frame_system::Config>::AccountId = 1;
-		const BOB: <Runtime as frame_system::Config>::AccountId = 2;
-		const EVE: <Runtime as frame_system::Config>::AccountId = 3;
+			Self::do_transfer(&sender, &to, schedule.locked, false)?;
+			Vesting::<T>::try_append(&to, schedule).map_err(|_| Error::<T>::TooManySchedules)?;
 
-		#[allow(unused)]
-		fn test_state_new() -> TestState {
-			let system = frame_system::GenesisConfig::default();
-			let currency =
-				barrel_currency::GenesisConfig { balances: vec![(ALICE, 100), (BOB, 100)] };
-			let runtime_genesis = RuntimeGenesisConfig { system, currency };
+			Self::deposit_event(Event::VestingScheduleAdded { who: to, schedule });
+			Ok(())
+		}
 
-			TestState::new(runtime_genesis.build_storage().unwrap())
+		/// Remove the vesting schedules of the caller that have fully unlocked.
+		#[barrel::call_index(7)]
+		#[barrel::weight(T::WeightInfo::vest())]
+		pub fn vest(origin: OriginFor<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let now = frame_system::Barrel::<T>::block_number();
+
+			Vesting::<T>::mutate_exists(&who, |maybe_schedules| {
+				if let Some(schedules) = maybe_schedules {
+					schedules.retain(|s| !s.locked_at(now).is_zero());
+					if schedules.is_empty() {
+						*maybe_schedules = None;
+						Self::deposit_event(Event::VestingCompleted { who: who.clone() });
+					}
+				}
+			});
+
+			Ok(())
 		}
 
-		struct ExtBuilder {
-			balances: Vec<(<Runtime as frame_system::Config>::AccountId, Balance)>,
+		/// Allow `spender` to transfer up to `amount` of the caller's funds via
+		/// [`Barrel::transfer_from`].
+		///
+		/// This overwrites any previous allowance of `spender`.
+		#[barrel::call_index(8)]
+		#[barrel::weight(T::WeightInfo::approve())]
+		pub fn approve(
+			origin: OriginFor<T>,
+			spender: T::AccountId,
+			amount: Balance,
+		) -> DispatchResult {
+			let owner = ensure_signed(origin)?;
+			Self::set_allowance(&owner, &spender, amount);
+			Ok(())
 		}
 
-		impl Default for ExtBuilder {
-			fn default() -> Self {
-				Self { balances: vec![(ALICE, 100), (BOB, 100)] }
-			}
+		/// Increase the allowance of `spender` over the caller's funds by `delta`.
+		#[barrel::call_index(9)]
+		#[barrel::weight(T::WeightInfo::increase_allowance())]
+		pub fn increase_allowance(
+			origin: OriginFor<T>,
+			spender: T::AccountId,
+			delta: Balance,
+		) -> DispatchResult {
+			let owner = ensure_signed(origin)?;
+			let amount = Allowances::<T>::get(&owner, &spender)
+				.checked_add(delta)
+				.ok_or(ArithmeticError::Overflow)?;
+			Self::set_allowance(&owner, &spender, amount);
+			Ok(())
 		}
 
-		impl ExtBuilder {
-			fn add_balance(
-				mut self,
-				who: <Runtime as frame_system::Config>::AccountId,
-				amount: Balance,
-			) -> Self {
-				self.balances.push((who, amount));
-				self
+		/// Decrease the allowance of `spender` over the caller's funds by `delta`.
+		#[barrel::call_index(10)]
+		#[barrel::weight(T::WeightInfo::decrease_allowance())]
+		pub fn decrease_allowance(
+			origin: OriginFor<T>,
+			spender: T::AccountId,
+			delta: Balance,
+		) -> DispatchResult {
+			let owner = ensure_signed(origin)?;
+			let amount = Allowances::<T>::get(&owner, &spender)
+				.checked_sub(delta)
+				.ok_or(Error::<T>::InsufficientAllowance)?;
+			Self::set_allowance(&owner, &spender, amount);
+			Ok(())
+		}
+
+		/// Transfer `amount` from `owner` to `to`, using the caller's allowance over the funds of
+		/// `owner`.
+		///
+		/// The transfer fee is paid by `owner`, so it is spent from the allowance as well.
+		#[barrel::call_index(11)]
+		#[barrel::weight(
+			T::WeightInfo::transfer_from().saturating_add(Barrel::<T>::balance_hooks_weight(4))
+		)]
+		pub fn transfer_from(
+			origin: OriginFor<T>,
+			owner: T::AccountId,
+			to: T::AccountId,
+			amount: Balance,
+		) -> DispatchResult {
+			let spender = ensure_signed(origin)?;
+			// a transfer to oneself is free, see `do_transfer`.
+			let fee = if owner == to { 0 } else { T::TransferFee::fee(amount) };
+			let spent = amount.checked_add(fee).ok_or(ArithmeticError::Overflow)?;
+			let remaining = Allowances::<T>::get(&owner, &spender)
+				.checked_sub(spent)
+				.ok_or(Error::<T>::InsufficientAllowance)?;
+
+			Self::do_transfer(&owner, &to, amount, false)?;
+			if remaining.is_zero() {
+				Allowances::<T>::remove(&owner, &spender);
+			} else {
+				Allowances::<T>::insert(&owner, &spender, remaining);
 			}
 
-			fn build_and_execute(self, test: impl FnOnce() -> ()) {
-				let system = frame_system::GenesisConfig::default();
-				let currency = barrel_currency::GenesisConfig { balances: self.balances };
-				let runtime_genesis = RuntimeGenesisConfig { system, currency };
+			Self::deposit_event(Event::AllowanceSpent { owner, spender, amount: spent });
+			Ok(())
+		}
 
-				let mut ext = TestState::new(runtime_genesis.build_storage().unwrap());
-				ext.execute_with(test);
+		/// Freeze `who`, so that none of its funds can be moved until it is thawed.
+		#[barrel::call_index(12)]
+		#[barrel::weight(T::WeightInfo::freeze_account())]
+		pub fn freeze_account(origin: OriginFor<T>, who: T::AccountId) -> DispatchResult {
+			T::FreezeOrigin::ensure_origin(origin)?;
+			ensure!(!FrozenAccounts::<T>::contains_key(&who), Error::<T>::AlreadyFrozen);
 
-				ext.execute_with(|| {
-					assert_eq!(
-						Balances::<Runtime>::iter().map(|(_, x)| x).sum::<u128>(),
-						TotalIssuance::<Runtime>::get()
-					);
-				})
+			FrozenAccounts::<T>::insert(&who, ());
+			Self::deposit_event(Event::AccountFrozen { who });
+			Ok(())
+		}
+
+		/// Thaw `who`, which was previously frozen by [`Barrel::freeze_account`].
+		#[barrel::call_index(13)]
+		#[barrel::weight(T::WeightInfo::thaw_account())]
+		pub fn thaw_account(origin: OriginFor<T>, who: T::AccountId) -> DispatchResult {
+			T::FreezeOrigin::ensure_origin(origin)?;
+			ensure!(FrozenAccounts::<T>::contains_key(&who), Error::<T>::NotFrozen);
+
+			FrozenAccounts::<T>::remove(&who);
+			Self::deposit_event(Event::AccountThawed { who });
+			Ok(())
+		}
+
+		/// Transfer `amount` to `to` at block `at`.
+		///
+		/// `amount` is held in escrow until then, and the transfer fee is charged right away. The
+		/// transfer can be cancelled via [`Barrel::cancel_scheduled_transfer`] before block `at`.
+		/// If it cannot be made at block `at`, e.g. because the sender is frozen, the funds are
+		/// released back to the sender.
+		#[barrel::call_index(14)]
+		#[barrel::weight(
+			T::WeightInfo::schedule_transfer().saturating_add(Barrel::<T>::balance_hooks_weight(3))
+		)]
+		pub fn schedule_transfer(
+			origin: OriginFor<T>,
+			to: T::AccountId,
+			amount: Balance,
+			at: BlockNumberFor<T>,
+		) -> DispatchResult {
+			let from = ensure_signed(origin)?;
+			let now = frame_system::Barrel::<T>::block_number();
+			ensure!(!amount.is_zero(), Error::<T>::ZeroAmount);
+			ensure!(at > now, Error::<T>::ReleaseNotInFuture);
+
+			let id = NextScheduledTransferId::<T>::get();
+			TransferAgenda::<T>::try_append(at, id).map_err(|_| Error::<T>::AgendaFull)?;
+			AgendaBlocks::<T>::try_mutate(|blocks| match blocks.binary_search(&at) {
+				Ok(_) => Ok(()),
+				Err(index) =>
+					blocks.try_insert(index, at).map_err(|_| Error::<T>::TooManyAgendaBlocks),
+			})?;
+			Self::reserve(HoldReason::ScheduledTransfer.into(), &from, amount)?;
+			let fee = T::TransferFee::fee(amount);
+			if !fee.is_zero() {
+				// `from` has a non-zero amount on hold, so it cannot be reaped here.
+				Self::debit(&from, fee, false)?;
+				Self::distribute_fee(&from, fee);
 			}
+
+			NextScheduledTransferId::<T>::put(id.checked_add(1).ok_or(ArithmeticError::Overflow)?);
+			ScheduledTransfers::<T>::insert(
+				id,
+				ScheduledTransfer { from: from.clone(), to: to.clone(), amount, at },
+			);
+
+			Self::deposit_event(Event::TransferScheduled { id, from, to, amount, at });
+			Ok(())
 		}
 
-		#[test]
-		fn initial_state_works() {
-			ExtBuilder::default().build_and_execute(|| {
-				assert_eq!(Balances::<Runtime>::get(&ALICE), Some(100));
-				assert_eq!(Balances::<Runtime>::get(&BOB), Some(100));
-				assert_eq!(Balances::<Runtime>::get(&EVE), None);
-				assert_eq!(TotalIssuance::<Runtime>::get(), 200);
+		/// Cancel the scheduled transfer `id`, and release its funds back to the caller.
+		///
+		/// Only the sender can cancel a transfer, and only before its release block. The transfer
+		/// fee is not refunded.
+		#[barrel::call_index(15)]
+		#[barrel::weight(
+			T::WeightInfo::cancel_scheduled_transfer()
+				.saturating_add(Barrel::<T>::balance_hooks_weight(2))
+		)]
+		pub fn cancel_scheduled_transfer(
+			origin: OriginFor<T>,
+			id: ScheduledTransferId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let transfer =
+				ScheduledTransfers::<T>::get(id).ok_or(Error::<T>::UnknownScheduledTransfer)?;
+			ensure!(transfer.from == who, Error::<T>::NotTransferSender);
+			ensure!(
+				frame_system::Barrel::<T>::block_number() < transfer.at,
+				Error::<T>::TooLateToCancel
+			);
+
+			ScheduledTransfers::<T>::remove(id);
+			TransferAgenda::<T>::mutate_exists(transfer.at, |maybe_agenda| {
+				if let Some(agenda) = maybe_agenda {
+					agenda.retain(|i| *i != id);
+					if agenda.is_empty() {
+						*maybe_agenda = None;
+						AgendaBlocks::<T>::mutate(|blocks| blocks.retain(|b| *b != transfer.at));
+					}
+				}
 			});
+			Self::unreserve(HoldReason::ScheduledTransfer.into(), &who, transfer.amount)?;
+
+			Self::deposit_event(Event::ScheduledTransferCancelled { id });
+			Ok(())
 		}
+	}
 
-		#[test]
-		fn ext_builder_works() {
-			ExtBuilder::default().add_balance(EVE, 42).build_and_execute(|| {
-				assert_eq!(Balances::<Runtime>::get(&EVE), Some(42));
-				assert_eq!(TotalIssuance::<Runtime>::get(), 242);
-			})
+	impl<T: Config> Barrel<T> {
+		/// The free balance of `who`, i.e. what can be transferred.
+		pub fn free_balance(who: &T::AccountId) -> Balance {
+			T::AccountStore::get(who).free
 		}
 
-		#[test]
-		#[should_panic]
-		fn duplicate_genesis_fails() {
-			ExtBuilder::default()
-				.add_balance(EVE, 42)
-				.add_balance(EVE, 43)
-				.build_and_execute(|| {
-					assert_eq!(Balances::<Runtime>::get(&EVE), None);
-					assert_eq!(TotalIssuance::<Runtime>::get(), 242);
-				})
+		/// The [`AccountData`] of `who`, or `None` if the account does not exist.
+		pub fn account(who: &T::AccountId) -> Option<AccountData> {
+			let data = T::AccountStore::get(who);
+			(data != AccountData::default()).then_some(data)
 		}
 
-		#[test]
-		fn test_mint() {
-			ExtBuilder::default().build_and_execute(|| {
-				// given the initial state, when:
-				assert_ok!(Barrel::<Runtime>::mint(RuntimeOrigin::signed(ALICE), BOB, 100));
+		/// The balance of `who` that is held for `reason`.
+		pub fn balance_on_hold(reason: T::RuntimeHoldReason, who: &T::AccountId) -> Balance {
+			Holds::<T>::get(who, reason)
+		}
 
-				// then:
-				assert_eq!(Balances::<Runtime>::get(&BOB), Some(200));
-				assert_eq!(TotalIssuance::<Runtime>::get(), 300);
+		/// The total balance of `who` that is held, for any reason.
+		pub fn reserved_balance(who: &T::AccountId) -> Balance {
+			T::AccountStore::get(who).reserved
+		}
 
-				// given:
-				assert_ok!(Barrel::<Runtime>::mint(RuntimeOrigin::signed(ALICE), EVE, 100));
+		/// Whether `who` is frozen.
+		pub fn is_frozen(who: &T::AccountId) -> bool {
+			FrozenAccounts::<T>::contains_key(who)
+		}
 
-				// then:
-				assert_eq!(Balances::<Runtime>::get(&EVE), Some(100));
-				assert_eq!(TotalIssuance::<Runtime>::get(), 400);
-			});
+		/// The number of accounts that currently exist.
+		pub fn holders_count() -> u32 {
+			HoldersCount::<T>::get()
 		}
 
-		#[test]
-		fn transfer_works() {
-			ExtBuilder::default().build_and_execute(|| {
-				// given the the initial state, when:
-				assert_ok!(Barrel::<Runtime>::transfer(RuntimeOrigin::signed(ALICE), BOB, 50));
+		/// The weight of [`Config::OnBalanceChanged`] for `changes` changes to account data.
+		///
+		/// A transfer changes the sender, the recipient and the fee collector, and the sender
+		/// again if it is reaped.
+		pub fn balance_hooks_weight(changes: u32) -> Weight {
+			T::OnBalanceChanged::weight().saturating_mul(changes.into())
+		}
 
-				// then:
-				assert_eq!(Balances::<Runtime>::get(&ALICE), Some(50));
-				assert_eq!(Balances::<Runtime>::get(&BOB), Some(150));
-				assert_eq!(TotalIssuance::<Runtime>::get(), 200);
+		/// The balance of `who` that is still locked by vesting schedules.
+		pub fn vesting_locked(who: &T::AccountId) -> Balance {
+			let now = frame_system::Barrel::<T>::block_number();
+			Vesting::<T>::get(who)
+				.iter()
+				.fold(0 as Balance, |acc, s| acc.saturating_add(s.locked_at(now)))
+		}
 
-				// when:
-				assert_ok!(Barrel::<Runtime>::transfer(RuntimeOrigin::signed(BOB), ALICE, 50));
+		/// The part of the free balance of `who` that can be transferred away.
+		///
+		/// This excludes what is locked by vesting schedules, and if `keep_alive` is set, the
+		/// existential deposit. Nothing can be transferred away from a frozen account.
+		pub fn reducible_balance(who: &T::AccountId, keep_alive: bool) -> Balance {
+			if Self::is_frozen(who) {
+				return 0
+			}
+			let locked = Self::vesting_locked(who);
+			let untouchable =
+				if keep_alive { locked.max(T::ExistentialDeposit::get()) } else { locked };
+			Self::free_balance(who).saturating_sub(untouchable)
+		}
 
-				// then:
-				assert_eq!(Balances::<Runtime>::get(&ALICE), Some(100));
-				assert_eq!(Balances::<Runtime>::get(&BOB), Some(100));
-				assert_eq!(TotalIssuance::<Runtime>::get(), 200);
+		fn set_allowance(owner: &T::AccountId, spender: &T::AccountId, amount: Balance) {
+			if amount.is_zero() {
+				Allowances::<T>::remove(owner, spender);
+			} else {
+				Allowances::<T>::insert(owner, spender, amount);
+			}
+			Self::deposit_event(Event::Approved {
+				owner: owner.clone(),
+				spender: spender.clone(),
+				amount,
 			});
 		}
 
-		#[test]
-		fn transfer_from_non_existent_fails() {
-			ExtBuilder::default().build_and_execute(|| {
-				// given the the initial state, when:
-				assert_err!(
-					Barrel::<Runtime>::transfer(RuntimeOrigin::signed(EVE), ALICE, 10),
-					"NonExistentAccount"
-				);
+		/// Transfer `amount` from `from` to `to`, charging `from` the [`Config::TransferFee`].
+		fn do_transfer(
+			from: &T::AccountId,
+			to: &T::AccountId,
+			amount: Balance,
+			keep_alive: bool,
+		) -> DispatchResult {
+			if from == to {
+				// nothing moves, and no fee is charged, but the transfer must still be allowed.
+				Self::ensure_can_debit(from, amount, keep_alive)?;
+				return Ok(())
+			}
 
-				// then nothing has changed.
-				assert_eq!(Balances::<Runtime>::get(&ALICE), Some(100));
-				assert_eq!(Balances::<Runtime>::get(&BOB), Some(100));
-				assert_eq!(Balances::<Runtime>::get(&EVE), None);
-				assert_eq!(TotalIssuance::<Runtime>::get(), 200);
-			});
+			let fee = T::TransferFee::fee(amount);
+			Self::debit(
+				from,
+				amount.checked_add(fee).ok_or(ArithmeticError::Overflow)?,
+				keep_alive,
+			)?;
+			Self::credit(to, amount)?;
+
+			Self::deposit_event(Event::Transferred { from: from.clone(), to: to.clone(), amount });
+			T::OnTransfer::on_transfer(from, to, amount);
+			if !fee.is_zero() {
+				Self::distribute_fee(from, fee);
+			}
+			Self::reap_if_dust(from);
+			Ok(())
+		}
+
+		/// Split the `fee` that was debited from `payer` between burning it and crediting it to
+		/// [`Config::FeeCollector`], according to [`Config::FeeBurn`].
+		fn distribute_fee(payer: &T::AccountId, fee: Balance) {
+			let to_collector = fee.saturating_sub(T::FeeBurn::get().mul_ceil(fee));
+			let collected = if !to_collector.is_zero() &&
+				Self::credit(&T::FeeCollector::get(), to_collector).is_ok()
+			{
+				to_collector
+			} else {
+				0
+			};
+			let burned = fee - collected;
+
+			TotalIssuance::<T>::mutate(|t| *t = t.saturating_sub(burned));
+			Self::deposit_event(Event::TransferFeePaid { who: payer.clone(), burned, collected });
+		}
+
+		/// Add `amount` to the free balance of `who`, creating the account if needed.
+		///
+		/// Does not alter the total issuance.
+		pub(crate) fn credit(who: &T::AccountId, amount: Balance) -> DispatchResult {
+			let endowed = Self::try_mutate_account(who, |data| {
+				let endowed = *data == AccountData::default();
+				if endowed {
+					ensure!(amount >= T::ExistentialDeposit::get(), Error::<T>::ExistentialDeposit);
+				}
+				data.free = data.free.checked_add(amount).ok_or(ArithmeticError::Overflow)?;
+				Ok(endowed)
+			})?;
+
+			if endowed {
+				Self::deposit_event(Event::Endowed { who: who.clone(), free: amount });
+			}
+			Ok(())
+		}
+
+		/// Check that `amount` can be taken from the free balance of `who`, returning what would
+		/// remain.
+		fn ensure_can_debit(
+			who: &T::AccountId,
+			amount: Balance,
+			keep_alive: bool,
+		) -> Result<Balance, DispatchError> {
+			let data = Self::account(who).ok_or(Error::<T>::NonExistentAccount)?;
+			ensure!(!Self::is_frozen(who), Error::<T>::AccountFrozen);
+			let remaining = data.free.checked_sub(amount).ok_or(Error::<T>::InsufficientBalance)?;
+			ensure!(remaining >= Self::vesting_locked(who), Error::<T>::VestingLocked);
+			let would_reap = remaining < T::ExistentialDeposit::get() && data.reserved.is_zero();
+			ensure!(!(keep_alive && would_reap), Error::<T>::KeepAlive);
+			Ok(remaining)
+		}
+
+		/// Remove `amount` from the free balance of `who`.
+		///
+		/// Does not alter the total issuance. If `keep_alive` is not set, the caller must call
+		/// [`Barrel::reap_if_dust`] once done.
+		fn debit(who: &T::AccountId, amount: Balance, keep_alive: bool) -> DispatchResult {
+			let remaining = Self::ensure_can_debit(who, amount, keep_alive)?;
+			Self::try_mutate_account(who, |data| {
+				data.free = remaining;
+				Ok(())
+			})
+		}
+
+		/// Reap `who` if it has no funds on hold and its free balance is below the existential
+		/// deposit.
+		///
+		/// `who` must have existed before the operation that the caller just performed. If that
+		/// operation left `who` without any funds, it has already been removed from
+		/// [`Config::AccountStore`], and only the rest of its state is cleaned up here.
+		pub(crate) fn reap_if_dust(who: &T::AccountId) {
+			let AccountData { free, reserved } = T::AccountStore::get(who);
+			if free >= T::ExistentialDeposit::get() || !reserved.is_zero() {
+				return
+			}
+
+			if !free.is_zero() &&
+				Self::try_mutate_account(who, |data| {
+					data.free = Zero::zero();
+					Ok(())
+				})
+				.is_err()
+			{
+				// the account is kept alive by something else, e.g. consumer references in
+				// `frame_system`.
+				return
+			}
+			Vesting::<T>::remove(who);
+			let burned = if free.is_zero() { 0 } else { T::DustRemoval::on_dust(who, free) };
+			TotalIssuance::<T>::mutate(|t| *t = t.saturating_sub(burned));
+
+			Self::deposit_event(Event::Reaped { who: who.clone(), dust: free });
+		}
+
+		/// Move `amount` from the free balance of `who` to be held for `reason`.
+		pub fn reserve(
+			reason: T::RuntimeHoldReason,
+			who: &T::AccountId,
+			amount: Balance,
+		) -> DispatchResult {
+			let data = Self::account(who).ok_or(Error::<T>::NonExistentAccount)?;
+			ensure!(!Self::is_frozen(who), Error::<T>::AccountFrozen);
+			let remaining = data.free.checked_sub(amount).ok_or(Error::<T>::InsufficientBalance)?;
+			ensure!(remaining >= Self::vesting_locked(who), Error::<T>::VestingLocked);
+
+			Holds::<T>::try_mutate(who, reason, |held| -> DispatchResult {
+				*held = held.checked_add(amount).ok_or(ArithmeticError::Overflow)?;
+				Ok(())
+			})?;
+			Self::try_mutate_account(who, |data| {
+				data.free = remaining;
+				data.reserved =
+					data.reserved.checked_add(amount).ok_or(ArithmeticError::Overflow)?;
+				Ok(())
+			})?;
+
+			Self::deposit_event(Event::Reserved { who: who.clone(), reason, amount });
+			Ok(())
+		}
+
+		/// Release `amount` held for `reason` back into the free balance of `who`.
+		pub fn unreserve(
+			reason: T::RuntimeHoldReason,
+			who: &T::AccountId,
+			amount: Balance,
+		) -> DispatchResult {
+			Self::reduce_hold(reason, who, amount)?;
+			Self::try_mutate_account(who, |data| {
+				data.reserved = data.reserved.saturating_sub(amount);
+				data.free = data.free.checked_add(amount).ok_or(ArithmeticError::Overflow)?;
+				Ok(())
+			})?;
+
+			Self::deposit_event(Event::Unreserved { who: who.clone(), reason, amount });
+			Self::reap_if_dust(who);
+			Ok(())
+		}
+
+		/// Slash up to `amount` of the funds held by `who` for `reason`.
+		///
+		/// The slashed funds are burned. Returns the amount that was actually slashed, which is
+		/// less than `amount` if not enough funds were held.
+		pub fn slash_reserved(
+			reason: T::RuntimeHoldReason,
+			who: &T::AccountId,
+			amount: Balance,
+		) -> Balance {
+			let slashed = Holds::<T>::get(who, reason).min(amount);
+			if slashed.is_zero() {
+				return 0
+			}
+
+			// `Holds` is updated first, so that it is up to date for `OnBalanceChanged`.
+			let reduced = with_storage_layer(|| {
+				Self::reduce_hold(reason, who, slashed)?;
+				Self::try_mutate_account(who, |data| {
+					data.reserved = data.reserved.saturating_sub(slashed);
+					Ok(())
+				})
+			});
+			if reduced.is_err() {
+				return 0
+			}
+			TotalIssuance::<T>::mutate(|t| *t = t.saturating_sub(slashed));
+
+			Self::deposit_event(Event::Slashed { who: who.clone(), reason, amount: slashed });
+			Self::reap_if_dust(who);
+			slashed
+		}
+
+		/// Move `amount` held by `from` for `reason` into the free balance of `to`.
+		pub fn repatriate_reserved(
+			reason: T::RuntimeHoldReason,
+			from: &T::AccountId,
+			to: &T::AccountId,
+			amount: Balance,
+		) -> DispatchResult {
+			Self::reduce_hold(reason, from, amount)?;
+			Self::try_mutate_account(from, |data| {
+				data.reserved = data.reserved.saturating_sub(amount);
+				Ok(())
+			})?;
+			Self::credit(to, amount)?;
+
+			Self::deposit_event(Event::ReserveRepatriated {
+				from: from.clone(),
+				to: to.clone(),
+				reason,
+				amount,
+			});
+			T::OnTransfer::on_transfer(from, to, amount);
+			Self::reap_if_dust(from);
+			Ok(())
+		}
+
+		/// Mutate the [`AccountData`] of `who`.
+		///
+		/// The account is created in, or removed from, [`Config::AccountStore`] depending on
+		/// whether it has any funds before and after `f`. No other checks, such as the existential
+		/// deposit, are done here.
+		///
+		/// An existing account provides for itself in `frame_system`, so that its data is kept
+		/// there. It cannot be removed while other barrels still have consumer references on it.
+		///
+		/// Any change is reported to [`Config::OnBalanceChanged`], and the creation or removal of
+		/// the account to [`Config::OnAccountCreated`] or [`Config::OnAccountKilled`]. Callers
+		/// that also change [`Holds`] must do so before calling this.
+		pub(crate) fn try_mutate_account<R>(
+			who: &T::AccountId,
+			f: impl FnOnce(&mut AccountData) -> Result<R, DispatchError>,
+		) -> Result<R, DispatchError> {
+			let (result, before, after) = T::AccountStore::try_mutate_exists(
+				who,
+				|maybe_data| -> Result<_, DispatchError> {
+					let before = maybe_data.take().unwrap_or_default();
+					let mut data = before;
+					let result = f(&mut data)?;
+					match (before != AccountData::default(), data != AccountData::default()) {
+						(false, true) => {
+							frame_system::Barrel::<T>::inc_providers(who);
+						},
+						(true, false) => {
+							frame_system::Barrel::<T>::dec_providers(who)?;
+						},
+						_ => {},
+					}
+					*maybe_data = (data != AccountData::default()).then_some(data);
+					Ok((result, before, data))
+				},
+			)?;
+
+			let (existed, exists) =
+				(before != AccountData::default(), after != AccountData::default());
+			match (existed, exists) {
+				(false, true) => {
+					HoldersCount::<T>::mutate(|c| c.saturating_inc());
+					T::OnAccountCreated::on_new_account(who);
+				},
+				(true, false) => HoldersCount::<T>::mutate(|c| c.saturating_dec()),
+				_ => {},
+			}
+			if before != after {
+				T::OnBalanceChanged::on_balance_changed(who, &after);
+			}
+			if existed && !exists {
+				T::OnAccountKilled::on_killed_account(who);
+			}
+			Ok(result)
+		}
+
+		/// Reduce the funds held by `who` for `reason` by `amount`.
+		///
+		/// Only [`Holds`] is updated, the caller must update [`AccountData::reserved`].
+		fn reduce_hold(
+			reason: T::RuntimeHoldReason,
+			who: &T::AccountId,
+			amount: Balance,
+		) -> DispatchResult {
+			Holds::<T>::try_mutate_exists(who, reason, |maybe_held| {
+				let held = maybe_held.unwrap_or_default();
+				let remaining = held.checked_sub(amount).ok_or(Error::<T>::InsufficientHold)?;
+				*maybe_held = Some(remaining).filter(|h| !h.is_zero());
+				Ok(())
+			})
+		}
+
+		/// The amount of inflation that is minted at the end of an era, if the total issuance is
+		/// `issuance`.
+		///
+		/// This is [`Config::YearlyInflation`] of `issuance`, spread evenly over the eras of a
+		/// year.
+		pub fn era_inflation(issuance: Balance) -> Balance {
+			let blocks_per_year: Balance = T::BlocksPerYear::get().unique_saturated_into();
+			let era_duration: Balance = T::EraDuration::get().unique_saturated_into();
+			let eras_per_year = (blocks_per_year / era_duration.max(1)).max(1);
+			T::YearlyInflation::get().mul_floor(issuance) / eras_per_year
+		}
+
+		/// The total issuance after `eras` more eras, assuming that all of their inflation is
+		/// minted.
+		///
+		/// Returns `None` if `eras` is more than [`MAX_PROJECTED_ERAS`].
+		pub fn projected_issuance(eras: u32) -> Option<Balance> {
+			if eras > MAX_PROJECTED_ERAS {
+				return None
+			}
+			Some((0..eras).fold(TotalIssuance::<T>::get(), |issuance, _| {
+				issuance.saturating_add(Self::era_inflation(issuance))
+			}))
+		}
+
+		/// Mint the inflation of an era, and hand it over to [`Config::OnInflation`].
+		///
+		/// Returns the amount that was minted.
+		pub(crate) fn mint_inflation() -> Balance {
+			let issuance = TotalIssuance::<T>::get();
+			let inflation = Self::era_inflation(issuance).min(Balance::MAX - issuance);
+			if inflation.is_zero() {
+				return 0
+			}
+
+			let minted = inflation.saturating_sub(T::OnInflation::on_inflation(inflation));
+			if !minted.is_zero() {
+				TotalIssuance::<T>::mutate(|t| *t = t.saturating_add(minted));
+				Self::deposit_event(Event::Inflated { amount: minted });
+			}
+			minted
+		}
+
+		/// Release the scheduled transfers that are due at block `now`, along with those that were
+		/// due earlier but carried over, in the order in which they were due.
+		///
+		/// At most [`Config::MaxReleasesPerBlock`] transfers are released, and as many agendas
+		/// looked at. Only the blocks in [`AgendaBlocks`] are looked at, so that nothing is read
+		/// but [`AgendaBlocks`] until the first of them is due. Returns the weight consumed.
+		pub(crate) fn release_scheduled_transfers(now: BlockNumberFor<T>) -> Weight {
+			let blocks = AgendaBlocks::<T>::get();
+			let max = T::MaxReleasesPerBlock::get();
+			let (mut visited, mut released, mut emptied) = (0u32, 0u32, 0usize);
+
+			for block in blocks.iter().take_while(|block| **block <= now) {
+				if visited == max || released == max {
+					break
+				}
+				visited += 1;
+				let mut due = TransferAgenda::<T>::take(block).into_inner();
+				let carried_over = due.split_off(due.len().min((max - released) as usize));
+				released += due.len() as u32;
+				for id in due {
+					Self::release_scheduled_transfer(id);
+				}
+				if !carried_over.is_empty() {
+					TransferAgenda::<T>::insert(block, BoundedVec::truncate_from(carried_over));
+					break
+				}
+				emptied += 1;
+			}
+
+			if emptied > 0 {
+				let remaining = blocks.into_inner().split_off(emptied);
+				if remaining.is_empty() {
+					AgendaBlocks::<T>::kill();
+				} else {
+					AgendaBlocks::<T>::put(BoundedVec::truncate_from(remaining));
+				}
+			}
+			// each release changes the sender and the recipient, and may reap the sender.
+			T::WeightInfo::release_scheduled_transfers(visited, released)
+				.saturating_add(Self::balance_hooks_weight(3 * released))
+		}
+
+		/// Release the funds of the scheduled transfer `id` to its recipient, or back to its sender
+		/// if that fails.
+		fn release_scheduled_transfer(id: ScheduledTransferId) {
+			let Some(ScheduledTransfer { from, to, amount, .. }) =
+				ScheduledTransfers::<T>::take(id)
+			else {
+				return
+			};
+			let reason = HoldReason::ScheduledTransfer.into();
+
+			let released = with_storage_layer(|| -> DispatchResult {
+				ensure!(!Self::is_frozen(&from), Error::<T>::AccountFrozen);
+				Self::repatriate_reserved(reason, &from, &to, amount)
+			});
+			match released {
+				Ok(()) => Self::deposit_event(Event::ScheduledTransferReleased { id }),
+				Err(error) => {
+					// the funds are still held in escrow, so this can only fail if the state is
+					// corrupt, in which case there is nothing better to do than to leave them.
+					let _ = Self::unreserve(reason, &from, amount);
+					Self::deposit_event(Event::ScheduledTransferFailed { id, error });
+				},
+			}
+		}
+
+		fn increase_issuance(amount: Balance) -> DispatchResult {
+			TotalIssuance::<T>::try_mutate(|t| -> DispatchResult {
+				*t = t.checked_add(amount).ok_or(ArithmeticError::Overflow)?;
+				Ok(())
+			})
+		}
+
+		fn decrease_issuance(amount: Balance) -> DispatchResult {
+			TotalIssuance::<T>::try_mutate(|t| -> DispatchResult {
+				*t = t.checked_sub(amount).ok_or(ArithmeticError::Underflow)?;
+				Ok(())
+			})
+		}
+
+		/// Ensure the invariants of this barrel hold:
+		///
+		/// * the funds held by each account add up to its [`AccountData::reserved`].
+		/// * all accounts with vesting schedules exist.
+		/// * the funds held in escrow by each account add up to the amounts of its scheduled
+		///   transfers, every transfer in the [`TransferAgenda`] is scheduled, and the blocks of
+		///   the agendas are exactly [`AgendaBlocks`].
+		/// * the invariants of [`Barrel::do_try_state_accounts`], over all accounts.
+		///
+		/// [`Config::AccountStore`] cannot be iterated, so the accounts are those of
+		/// `frame_system`, where every account that has any [`AccountData`] is provided for.
+		#[cfg(any(feature = "try-runtime", test))]
+		pub fn do_try_state() -> Result<(), sp_runtime::TryRuntimeError> {
+			for (who, _) in Holds::<T>::iter_keys() {
+				let held = Holds::<T>::iter_prefix_values(&who)
+					.try_fold(0 as Balance, |acc, x| acc.checked_add(x))
+					.ok_or("held balance overflow")?;
+				ensure!(
+					held == Self::reserved_balance(&who),
+					"held balances do not match the reserved balance"
+				);
+			}
+			for who in Vesting::<T>::iter_keys() {
+				ensure!(Self::account(&who).is_some(), "account with vesting does not exist");
+			}
+
+			let mut escrowed: Vec<(T::AccountId, Balance)> = Vec::new();
+			for transfer in ScheduledTransfers::<T>::iter_values() {
+				match escrowed.iter_mut().find(|(who, _)| *who == transfer.from) {
+					Some((_, total)) => *total = total.saturating_add(transfer.amount),
+					None => escrowed.push((transfer.from, transfer.amount)),
+				}
+			}
+			let reason: T::RuntimeHoldReason = HoldReason::ScheduledTransfer.into();
+			ensure!(
+				Holds::<T>::iter().filter(|(_, r, _)| *r == reason).count() == escrowed.len(),
+				"funds held in escrow without a scheduled transfer"
+			);
+			for (who, total) in escrowed {
+				ensure!(
+					Holds::<T>::get(&who, reason) == total,
+					"funds held in escrow do not match the scheduled transfers"
+				);
+			}
+			for (_, ids) in TransferAgenda::<T>::iter() {
+				ensure!(
+					ids.iter().all(|id| ScheduledTransfers::<T>::contains_key(id)),
+					"agenda refers to an unknown scheduled transfer"
+				);
+			}
+			let blocks = AgendaBlocks::<T>::get();
+			ensure!(blocks.windows(2).all(|w| w[0] < w[1]), "agenda blocks are not sorted");
+			ensure!(
+				blocks.len() == TransferAgenda::<T>::iter_keys().count() &&
+					blocks.iter().all(|block| TransferAgenda::<T>::contains_key(block)),
+				"agenda blocks do not match the agendas"
+			);
+
+			Self::do_try_state_accounts(
+				frame_system::Account::<T>::iter_keys().map(|who| T::AccountStore::get(&who)),
+			)
+		}
+
+		/// Ensure the invariants that span all accounts hold, given the [`AccountData`] of every
+		/// account in [`Config::AccountStore`]:
+		///
+		/// * the sum of all free and held balances is equal to the [`TotalIssuance`].
+		/// * the number of existing accounts is equal to [`HoldersCount`].
+		/// * all accounts without funds on hold have at least the existential deposit.
+		#[cfg(any(feature = "try-runtime", test))]
+		pub fn do_try_state_accounts(
+			accounts: impl IntoIterator<Item = AccountData>,
+		) -> Result<(), sp_runtime::TryRuntimeError> {
+			let mut total: Balance = 0;
+			let mut count: u32 = 0;
+			for data in accounts.into_iter().filter(|data| *data != AccountData::default()) {
+				total = total
+					.checked_add(data.free)
+					.and_then(|t| t.checked_add(data.reserved))
+					.ok_or("total balance overflow")?;
+				count += 1;
+				ensure!(
+					data.free >= T::ExistentialDeposit::get() || !data.reserved.is_zero(),
+					"account below the existential deposit was not reaped"
+				);
+			}
+
+			ensure!(
+				total == TotalIssuance::<T>::get(),
+				"sum of balances does not match the total issuance"
+			);
+			ensure!(count == HoldersCount::<T>::get(), "number of accounts does not match");
+			Ok(())
+		}
+	}
+
+	#[cfg(test)]
+	pub(crate) mod tests {
+		use crate::currency::barrel::{self as barrel_currency, *};
+		use frame::{
+			deps::{
+				frame_support::{
+					dispatch::GetDispatchInfo,
+					traits::{OnKilledAccount, OnNewAccount, StorageInfoTrait},
+				},
+				sp_runtime::Perbill,
+			},
+			derive::{Decode, Encode, MaxEncodedLen, TypeInfo},
+			testing_prelude::*,
+			traits::Dispatchable,
+		};
+
+		construct_runtime!(
+			pub struct Runtime {
+				System: frame_system,
+				Currency: barrel_currency,
+			}
+		);
+
+		#[derive_impl(frame_system::config_preludes::TestDefaultConfig as frame_system::DefaultConfig)]
+		impl frame_system::Config for Runtime {
+			type Block = MockBlock<Runtime>;
+			type AccountData = barrel_currency::AccountData;
+		}
+
+		parameter_types! {
+			pub const ExistentialDeposit: Balance = 5;
+			pub const DustReceiver: AccountId = 42;
+			pub const InflationReceiver: AccountId = 43;
+			pub static YearlyInflation: Perbill = Perbill::zero();
+			pub static FlatTransferFee: Balance = 0;
+			pub static FeeBurn: Perbill = Perbill::from_percent(50);
+			pub const FeeCollector: AccountId = 44;
+		}
+
+		/// A call to one of the balance hooks of the barrel.
+		#[derive(Clone, Eq, PartialEq, Debug)]
+		pub enum Hook {
+			Transfer(AccountId, AccountId, Balance),
+			Changed(AccountId, AccountData),
+			Created(AccountId),
+			Killed(AccountId),
+		}
+
+		parameter_types! {
+			pub static HookCalls: Vec<Hook> = vec![];
+		}
+
+		/// Records all hook calls into [`HookCalls`].
+		pub struct RecordHooks;
+		impl OnTransfer<AccountId> for RecordHooks {
+			fn on_transfer(from: &AccountId, to: &AccountId, amount: Balance) {
+				HookCalls::mutate(|h| h.push(Hook::Transfer(*from, *to, amount)));
+			}
+		}
+		impl OnBalanceChanged<AccountId> for RecordHooks {
+			fn on_balance_changed(who: &AccountId, data: &AccountData) {
+				HookCalls::mutate(|h| h.push(Hook::Changed(*who, *data)));
+			}
+
+			fn weight() -> Weight {
+				Weight::zero()
+			}
+		}
+		impl OnNewAccount<AccountId> for RecordHooks {
+			fn on_new_account(who: &AccountId) {
+				HookCalls::mutate(|h| h.push(Hook::Created(*who)));
+			}
+		}
+		impl OnKilledAccount<AccountId> for RecordHooks {
+			fn on_killed_account(who: &AccountId) {
+				HookCalls::mutate(|h| h.push(Hook::Killed(*who)));
+			}
+		}
+
+		#[derive(Encode, Decode, MaxEncodedLen, TypeInfo, Clone, Copy, Eq, PartialEq, Debug)]
+		pub enum HoldReason {
+			ValidatorBond,
+			Delegation,
+			ScheduledTransfer,
+		}
+
+		impl From<barrel_currency::HoldReason> for HoldReason {
+			fn from(reason: barrel_currency::HoldReason) -> Self {
+				match reason {
+					barrel_currency::HoldReason::ScheduledTransfer => HoldReason::ScheduledTransfer,
+				}
+			}
+		}
+
+		impl barrel_currency::Config for Runtime {
+			type RuntimeEvent = RuntimeEvent;
+			type MintOrigin = frame_system::EnsureRoot<AccountId>;
+			type FreezeOrigin = frame_system::EnsureRoot<AccountId>;
+			type ExistentialDeposit = ExistentialDeposit;
+			type DustRemoval = DustTo<Runtime, DustReceiver>;
+			type RuntimeHoldReason = HoldReason;
+			type MaxVestingSchedules = ConstU32<2>;
+			type MaxTransfers = ConstU32<4>;
+			type MaxScheduledPerBlock = ConstU32<3>;
+			type MaxAgendaBlocks = ConstU32<2>;
+			type MaxReleasesPerBlock = ConstU32<2>;
+			type WeightInfo = ();
+			type AccountStore = System;
+			type EraDuration = ConstU64<10>;
+			type BlocksPerYear = ConstU64<100>;
+			type YearlyInflation = YearlyInflation;
+			type OnInflation = InflationTo<Runtime, InflationReceiver>;
+			type TransferFee = FlatFee<FlatTransferFee>;
+			type FeeBurn = FeeBurn;
+			type FeeCollector = FeeCollector;
+			type OnTransfer = (RecordHooks, ());
+			type OnBalanceChanged = RecordHooks;
+			type OnAccountCreated = RecordHooks;
+			type OnAccountKilled = RecordHooks;
+		}
+
+		type AccountId = <Runtime as frame_system::Config>::AccountId;
+
+		const ALICE: <Runtime as frame_system::Config>::AccountId = 1;
+		const BOB: <Runtime as frame_system::Config>::AccountId = 2;
+		const EVE: <Runtime as frame_system::Config>::AccountId = 3;
+
+		#[allow(unused)]
+		pub(crate) fn test_state_new() -> TestState {
+			let system = frame_system::GenesisConfig::default();
+			let currency = barrel_currency::GenesisConfig {
+				balances: vec![(ALICE, 100), (BOB, 100)],
+				..Default::default()
+			};
+			let runtime_genesis = RuntimeGenesisConfig { system, currency };
+
+			TestState::new(runtime_genesis.build_storage().unwrap())
+		}
+
+		struct ExtBuilder {
+			balances: Vec<(<Runtime as frame_system::Config>::AccountId, Balance)>,
+			vesting: Vec<(AccountId, Balance, Balance, BlockNumberFor<Runtime>)>,
+		}
+
+		impl Default for ExtBuilder {
+			fn default() -> Self {
+				Self { balances: vec![(ALICE, 100), (BOB, 100)], vesting: vec![] }
+			}
+		}
+
+		impl ExtBuilder {
+			fn add_balance(
+				mut self,
+				who: <Runtime as frame_system::Config>::AccountId,
+				amount: Balance,
+			) -> Self {
+				self.balances.push((who, amount));
+				self
+			}
+
+			fn add_vesting(
+				mut self,
+				who: AccountId,
+				locked: Balance,
+				per_block: Balance,
+				starting_block: BlockNumberFor<Runtime>,
+			) -> Self {
+				self.vesting.push((who, locked, per_block, starting_block));
+				self
+			}
+
+			fn build_and_execute(self, test: impl FnOnce() -> ()) {
+				let system = frame_system::GenesisConfig::default();
+				let currency = barrel_currency::GenesisConfig {
+					balances: self.balances,
+					vesting: self.vesting,
+				};
+				let runtime_genesis = RuntimeGenesisConfig { system, currency };
+
+				let mut ext = TestState::new(runtime_genesis.build_storage().unwrap());
+				// events are not deposited in block 0.
+				ext.execute_with(|| frame_system::Barrel::<Runtime>::set_block_number(1));
+				ext.execute_with(test);
+
+				ext.execute_with(|| {
+					Barrel::<Runtime>::do_try_state().unwrap();
+				})
+			}
+		}
+
+		/// The free balance of `who`, or `None` if the account does not exist.
+		fn free_of(who: &AccountId) -> Option<Balance> {
+			Barrel::<Runtime>::account(who).map(|data| data.free)
+		}
+
+		fn currency_events() -> Vec<Event<Runtime>> {
+			frame_system::Barrel::<Runtime>::events()
+				.into_iter()
+				.filter_map(|r| match r.event {
+					RuntimeEvent::Currency(e) => Some(e),
+					_ => None,
+				})
+				.collect()
+		}
+
+		#[test]
+		fn initial_state_works() {
+			ExtBuilder::default().build_and_execute(|| {
+				assert_eq!(free_of(&ALICE), Some(100));
+				assert_eq!(free_of(&BOB), Some(100));
+				assert_eq!(free_of(&EVE), None);
+				assert_eq!(TotalIssuance::<Runtime>::get(), 200);
+			});
+		}
+
+		#[test]
+		fn ext_builder_works() {
+			ExtBuilder::default().add_balance(EVE, 42).build_and_execute(|| {
+				assert_eq!(free_of(&EVE), Some(42));
+				assert_eq!(TotalIssuance::<Runtime>::get(), 242);
+			})
+		}
+
+		#[test]
+		#[should_panic]
+		fn duplicate_genesis_fails() {
+			ExtBuilder::default()
+				.add_balance(EVE, 42)
+				.add_balance(EVE, 43)
+				.build_and_execute(|| {
+					assert_eq!(free_of(&EVE), None);
+					assert_eq!(TotalIssuance::<Runtime>::get(), 242);
+				})
+		}
+
+		#[test]
+		fn test_mint() {
+			ExtBuilder::default().build_and_execute(|| {
+				// given the initial state, when:
+				assert_ok!(Barrel::<Runtime>::mint(RuntimeOrigin::root(), BOB, 100));
+
+				// then:
+				assert_eq!(free_of(&BOB), Some(200));
+				assert_eq!(TotalIssuance::<Runtime>::get(), 300);
+
+				// given:
+				assert_ok!(Barrel::<Runtime>::mint(RuntimeOrigin::root(), EVE, 100));
+
+				// then:
+				assert_eq!(free_of(&EVE), Some(100));
+				assert_eq!(TotalIssuance::<Runtime>::get(), 400);
+				assert_eq!(
+					currency_events(),
+					vec![
+						Event::Minted { to: BOB, amount: 100 },
+						Event::Endowed { who: EVE, free: 100 },
+						Event::Minted { to: EVE, amount: 100 }
+					]
+				);
+			});
+		}
+
+		#[test]
+		fn mint_requires_mint_origin() {
+			ExtBuilder::default().build_and_execute(|| {
+				// given the initial state, when:
+				assert_noop!(
+					Barrel::<Runtime>::mint(RuntimeOrigin::signed(ALICE), BOB, 100),
+					DispatchError::BadOrigin
+				);
+
+				// then nothing has changed.
+				assert_eq!(free_of(&BOB), Some(100));
+				assert_eq!(TotalIssuance::<Runtime>::get(), 200);
+			});
+		}
+
+		#[test]
+		fn burn_works() {
+			ExtBuilder::default().build_and_execute(|| {
+				// given the initial state, when:
+				assert_ok!(Barrel::<Runtime>::burn(RuntimeOrigin::signed(ALICE), 40));
+
+				// then:
+				assert_eq!(free_of(&ALICE), Some(60));
+				assert_eq!(TotalIssuance::<Runtime>::get(), 160);
+				assert_eq!(currency_events(), vec![Event::Burned { who: ALICE, amount: 40 }]);
+
+				// when burning more than what is left:
+				assert_noop!(
+					Barrel::<Runtime>::burn(RuntimeOrigin::signed(ALICE), 61),
+					Error::<Runtime>::InsufficientBalance
+				);
+
+				// or from a non-existent account:
+				assert_noop!(
+					Barrel::<Runtime>::burn(RuntimeOrigin::signed(EVE), 1),
+					Error::<Runtime>::NonExistentAccount
+				);
+			});
+		}
+
+		#[test]
+		fn transfer_works() {
+			ExtBuilder::default().build_and_execute(|| {
+				// given the the initial state, when:
+				assert_ok!(Barrel::<Runtime>::transfer(RuntimeOrigin::signed(ALICE), BOB, 50));
+
+				// then:
+				assert_eq!(free_of(&ALICE), Some(50));
+				assert_eq!(free_of(&BOB), Some(150));
+				assert_eq!(TotalIssuance::<Runtime>::get(), 200);
+
+				// when:
+				assert_ok!(Barrel::<Runtime>::transfer(RuntimeOrigin::signed(BOB), ALICE, 50));
+
+				// then:
+				assert_eq!(free_of(&ALICE), Some(100));
+				assert_eq!(free_of(&BOB), Some(100));
+				assert_eq!(TotalIssuance::<Runtime>::get(), 200);
+				assert_eq!(
+					currency_events(),
+					vec![
+						Event::Transferred { from: ALICE, to: BOB, amount: 50 },
+						Event::Transferred { from: BOB, to: ALICE, amount: 50 }
+					]
+				);
+			});
+		}
+
+		#[test]
+		fn transfer_from_non_existent_fails() {
+			ExtBuilder::default().build_and_execute(|| {
+				// given the the initial state, when:
+				assert_noop!(
+					Barrel::<Runtime>::transfer(RuntimeOrigin::signed(EVE), ALICE, 10),
+					Error::<Runtime>::NonExistentAccount
+				);
+
+				// then nothing has changed.
+				assert_eq!(free_of(&ALICE), Some(100));
+				assert_eq!(free_of(&BOB), Some(100));
+				assert_eq!(free_of(&EVE), None);
+				assert_eq!(TotalIssuance::<Runtime>::get(), 200);
+				assert!(currency_events().is_empty());
+			});
+		}
+
+		#[test]
+		fn transfer_more_than_balance_fails() {
+			ExtBuilder::default().build_and_execute(|| {
+				// given the the initial state, when:
+				assert_noop!(
+					Barrel::<Runtime>::transfer(RuntimeOrigin::signed(ALICE), BOB, 101),
+					Error::<Runtime>::InsufficientBalance
+				);
+
+				// then nothing has changed.
+				assert_eq!(free_of(&ALICE), Some(100));
+				assert_eq!(free_of(&BOB), Some(100));
+				assert!(currency_events().is_empty());
+			});
+		}
+
+		#[test]
+		fn reserve_unreserve_works() {
+			ExtBuilder::default().build_and_execute(|| {
+				// given the initial state, when:
+				assert_ok!(Barrel::<Runtime>::reserve(HoldReason::ValidatorBond, &ALICE, 30));
+				assert_ok!(Barrel::<Runtime>::reserve(HoldReason::Delegation, &ALICE, 20));
+
+				// then:
+				assert_eq!(Barrel::<Runtime>::free_balance(&ALICE), 50);
+				assert_eq!(
+					Barrel::<Runtime>::balance_on_hold(HoldReason::ValidatorBond, &ALICE),
+					30
+				);
+				assert_eq!(Barrel::<Runtime>::reserved_balance(&ALICE), 50);
+				assert_eq!(TotalIssuance::<Runtime>::get(), 200);
+
+				// when:
+				assert_ok!(Barrel::<Runtime>::unreserve(HoldReason::ValidatorBond, &ALICE, 30));
+
+				// then:
+				assert_eq!(Barrel::<Runtime>::free_balance(&ALICE), 80);
+				assert_eq!(
+					Barrel::<Runtime>::balance_on_hold(HoldReason::ValidatorBond, &ALICE),
+					0
+				);
+				assert_eq!(Barrel::<Runtime>::reserved_balance(&ALICE), 20);
+				assert_eq!(
+					currency_events(),
+					vec![
+						Event::Reserved {
+							who: ALICE,
+							reason: HoldReason::ValidatorBond,
+							amount: 30
+						},
+						Event::Reserved { who: ALICE, reason: HoldReason::Delegation, amount: 20 },
+						Event::Unreserved {
+							who: ALICE,
+							reason: HoldReason::ValidatorBond,
+							amount: 30
+						},
+					]
+				);
+
+				// cannot reserve more than what is free, or unreserve more than what is held.
+				assert_noop!(
+					Barrel::<Runtime>::reserve(HoldReason::ValidatorBond, &ALICE, 81),
+					Error::<Runtime>::InsufficientBalance
+				);
+				assert_noop!(
+					Barrel::<Runtime>::unreserve(HoldReason::Delegation, &ALICE, 21),
+					Error::<Runtime>::InsufficientHold
+				);
+			});
+		}
+
+		#[test]
+		fn cannot_transfer_held_funds() {
+			ExtBuilder::default().build_and_execute(|| {
+				// given:
+				assert_ok!(Barrel::<Runtime>::reserve(HoldReason::ValidatorBond, &ALICE, 60));
+
+				// when:
+				assert_noop!(
+					Barrel::<Runtime>::transfer(RuntimeOrigin::signed(ALICE), BOB, 41),
+					Error::<Runtime>::InsufficientBalance
+				);
+
+				// then only the free part can be transferred.
+				assert_ok!(Barrel::<Runtime>::transfer(RuntimeOrigin::signed(ALICE), BOB, 40));
+				assert_eq!(Barrel::<Runtime>::free_balance(&ALICE), 0);
+				assert_eq!(Barrel::<Runtime>::reserved_balance(&ALICE), 60);
+			});
+		}
+
+		#[test]
+		fn slash_reserved_works() {
+			ExtBuilder::default().build_and_execute(|| {
+				// given:
+				assert_ok!(Barrel::<Runtime>::reserve(HoldReason::ValidatorBond, &ALICE, 60));
+
+				// when:
+				assert_eq!(
+					Barrel::<Runtime>::slash_reserved(HoldReason::ValidatorBond, &ALICE, 20),
+					20
+				);
+
+				// then:
+				assert_eq!(
+					Barrel::<Runtime>::balance_on_hold(HoldReason::ValidatorBond, &ALICE),
+					40
+				);
+				assert_eq!(TotalIssuance::<Runtime>::get(), 180);
+
+				// when slashing more than what is held, only what is held is slashed.
+				assert_eq!(
+					Barrel::<Runtime>::slash_reserved(HoldReason::ValidatorBond, &ALICE, 50),
+					40
+				);
+
+				// then:
+				assert_eq!(
+					Barrel::<Runtime>::balance_on_hold(HoldReason::ValidatorBond, &ALICE),
+					0
+				);
+				assert_eq!(Barrel::<Runtime>::free_balance(&ALICE), 40);
+				assert_eq!(TotalIssuance::<Runtime>::get(), 140);
+			});
+		}
+
+		#[test]
+		fn repatriate_reserved_works() {
+			ExtBuilder::default().build_and_execute(|| {
+				// given:
+				assert_ok!(Barrel::<Runtime>::reserve(HoldReason::Delegation, &ALICE, 60));
+
+				// when:
+				assert_ok!(Barrel::<Runtime>::repatriate_reserved(
+					HoldReason::Delegation,
+					&ALICE,
+					&EVE,
+					25
+				));
+
+				// then:
+				assert_eq!(Barrel::<Runtime>::balance_on_hold(HoldReason::Delegation, &ALICE), 35);
+				assert_eq!(free_of(&EVE), Some(25));
+				assert_eq!(TotalIssuance::<Runtime>::get(), 200);
+				assert_eq!(
+					currency_events().last(),
+					Some(&Event::ReserveRepatriated {
+						from: ALICE,
+						to: EVE,
+						reason: HoldReason::Delegation,
+						amount: 25
+					})
+				);
+
+				// cannot repatriate more than what is held.
+				assert_noop!(
+					Barrel::<Runtime>::repatriate_reserved(
+						HoldReason::Delegation,
+						&ALICE,
+						&EVE,
+						36
+					),
+					Error::<Runtime>::InsufficientHold
+				);
+			});
+		}
+
+		#[test]
+		fn cannot_create_account_below_existential_deposit() {
+			ExtBuilder::default().build_and_execute(|| {
+				// given the initial state, when:
+				assert_noop!(
+					Barrel::<Runtime>::mint(RuntimeOrigin::root(), EVE, 4),
+					Error::<Runtime>::ExistentialDeposit
+				);
+				assert_noop!(
+					Barrel::<Runtime>::transfer(RuntimeOrigin::signed(ALICE), EVE, 4),
+					Error::<Runtime>::ExistentialDeposit
+				);
+
+				// then an existing account can receive any amount.
+				assert_ok!(Barrel::<Runtime>::transfer(RuntimeOrigin::signed(ALICE), BOB, 1));
+				assert_eq!(free_of(&BOB), Some(101));
+			});
+		}
+
+		#[test]
+		fn transfer_reaps_sender_and_burns_dust() {
+			ExtBuilder::default().build_and_execute(|| {
+				// given the initial state, when:
+				assert_ok!(Barrel::<Runtime>::transfer(RuntimeOrigin::signed(ALICE), BOB, 97));
+
+				// then alice is reaped, and since the dust receiver does not exist, dust is burned.
+				assert_eq!(free_of(&ALICE), None);
+				assert_eq!(free_of(&BOB), Some(197));
+				assert_eq!(free_of(&DustReceiver::get()), None);
+				assert_eq!(TotalIssuance::<Runtime>::get(), 197);
+				assert_eq!(
+					currency_events(),
+					vec![
+						Event::Transferred { from: ALICE, to: BOB, amount: 97 },
+						Event::Reaped { who: ALICE, dust: 3 }
+					]
+				);
+			});
+		}
+
+		#[test]
+		fn dust_goes_to_dust_receiver() {
+			ExtBuilder::default()
+				.add_balance(DustReceiver::get(), 10)
+				.build_and_execute(|| {
+					// given the initial state, when:
+					assert_ok!(Barrel::<Runtime>::burn(RuntimeOrigin::signed(ALICE), 98));
+
+					// then alice is reaped, and the dust is received.
+					assert_eq!(free_of(&ALICE), None);
+					assert_eq!(free_of(&DustReceiver::get()), Some(12));
+					assert_eq!(TotalIssuance::<Runtime>::get(), 112);
+				});
+		}
+
+		#[test]
+		fn held_funds_keep_account_alive() {
+			ExtBuilder::default().build_and_execute(|| {
+				// given:
+				assert_ok!(Barrel::<Runtime>::reserve(HoldReason::ValidatorBond, &ALICE, 98));
+
+				// when:
+				assert_ok!(Barrel::<Runtime>::transfer(RuntimeOrigin::signed(ALICE), BOB, 2));
+
+				// then alice is not reaped, as it has funds on hold.
+				assert_eq!(free_of(&ALICE), Some(0));
+
+				// when the hold is slashed entirely:
+				assert_eq!(
+					Barrel::<Runtime>::slash_reserved(HoldReason::ValidatorBond, &ALICE, 98),
+					98
+				);
+
+				// then alice is reaped.
+				assert_eq!(free_of(&ALICE), None);
+				assert_eq!(TotalIssuance::<Runtime>::get(), 102);
+			});
+		}
+
+		#[test]
+		fn transfer_keep_alive_works() {
+			ExtBuilder::default().build_and_execute(|| {
+				// given the initial state, when:
+				assert_noop!(
+					Barrel::<Runtime>::transfer_keep_alive(RuntimeOrigin::signed(ALICE), BOB, 96),
+					Error::<Runtime>::KeepAlive
+				);
+
+				// then:
+				assert_ok!(Barrel::<Runtime>::transfer_keep_alive(
+					RuntimeOrigin::signed(ALICE),
+					BOB,
+					95
+				));
+				assert_eq!(free_of(&ALICE), Some(5));
+				assert_eq!(free_of(&BOB), Some(195));
+			});
+		}
+
+		#[test]
+		fn transfer_all_works() {
+			ExtBuilder::default().build_and_execute(|| {
+				// given the initial state, when:
+				assert_ok!(Barrel::<Runtime>::transfer_all(
+					RuntimeOrigin::signed(ALICE),
+					EVE,
+					true
+				));
+
+				// then:
+				assert_eq!(free_of(&ALICE), Some(5));
+				assert_eq!(free_of(&EVE), Some(95));
+
+				// when:
+				assert_ok!(Barrel::<Runtime>::transfer_all(RuntimeOrigin::signed(BOB), EVE, false));
+
+				// then:
+				assert_eq!(free_of(&BOB), None);
+				assert_eq!(free_of(&EVE), Some(195));
+				assert_eq!(TotalIssuance::<Runtime>::get(), 200);
+			});
+		}
+
+		#[test]
+		fn fungible_inspect_and_mutate_works() {
+			use frame::traits::{
+				fungible::{Inspect, Mutate},
+				tokens::{Fortitude::Polite, Precision::Exact, Preservation},
+			};
+			type Currency = Barrel<Runtime>;
+
+			ExtBuilder::default().build_and_execute(|| {
+				// given the initial state, then:
+				assert_eq!(<Currency as Inspect<_>>::total_issuance(), 200);
+				assert_eq!(<Currency as Inspect<_>>::minimum_balance(), 5);
+				assert_eq!(<Currency as Inspect<_>>::balance(&ALICE), 100);
+				assert_eq!(
+					<Currency as Inspect<_>>::reducible_balance(
+						&ALICE,
+						Preservation::Preserve,
+						Polite
+					),
+					95
+				);
+
+				// when:
+				assert_ok!(<Currency as Mutate<_>>::mint_into(&EVE, 50));
+				assert_ok!(<Currency as Mutate<_>>::transfer(
+					&EVE,
+					&BOB,
+					10,
+					Preservation::Expendable
+				));
+
+				// then:
+				assert_eq!(free_of(&EVE), Some(40));
+				assert_eq!(free_of(&BOB), Some(110));
+				assert_eq!(TotalIssuance::<Runtime>::get(), 250);
+
+				// when:
+				assert_ok!(<Currency as Mutate<_>>::burn_from(&EVE, 40, Exact, Polite));
+
+				// then:
+				assert_eq!(free_of(&EVE), None);
+				assert_eq!(TotalIssuance::<Runtime>::get(), 210);
+				assert_eq!(
+					currency_events(),
+					vec![
+						Event::Endowed { who: EVE, free: 50 },
+						Event::Minted { to: EVE, amount: 50 },
+						Event::Transferred { from: EVE, to: BOB, amount: 10 },
+						Event::Reaped { who: EVE, dust: 0 },
+						Event::Burned { who: EVE, amount: 40 },
+					]
+				);
+			});
+		}
+
+		#[test]
+		fn fungible_hold_works() {
+			use frame::traits::{
+				fungible::{InspectHold, MutateHold},
+				tokens::{Fortitude::Polite, Precision::Exact},
+			};
+			type Currency = Barrel<Runtime>;
+
+			ExtBuilder::default().build_and_execute(|| {
+				// given the initial state, then the existential deposit cannot be held.
+				assert!(
+					<Currency as MutateHold<_>>::hold(&HoldReason::Delegation, &ALICE, 96).is_err()
+				);
+
+				// when:
+				assert_ok!(<Currency as MutateHold<_>>::hold(&HoldReason::Delegation, &ALICE, 60));
+
+				// then:
+				assert_eq!(
+					<Currency as InspectHold<_>>::balance_on_hold(&HoldReason::Delegation, &ALICE),
+					60
+				);
+				assert_eq!(<Currency as InspectHold<_>>::total_balance_on_hold(&ALICE), 60);
+				assert_eq!(free_of(&ALICE), Some(40));
+
+				// when:
+				assert_ok!(<Currency as MutateHold<_>>::release(
+					&HoldReason::Delegation,
+					&ALICE,
+					20,
+					Exact
+				));
+				assert_ok!(<Currency as MutateHold<_>>::burn_held(
+					&HoldReason::Delegation,
+					&ALICE,
+					40,
+					Exact,
+					Polite
+				));
+
+				// then:
+				assert_eq!(<Currency as InspectHold<_>>::total_balance_on_hold(&ALICE), 0);
+				assert_eq!(free_of(&ALICE), Some(60));
+				assert_eq!(TotalIssuance::<Runtime>::get(), 160);
+			});
+		}
+
+		#[test]
+		fn mint_overflow_fails() {
+			ExtBuilder::default().build_and_execute(|| {
+				// given the initial state, when:
+				assert_noop!(
+					Barrel::<Runtime>::mint(RuntimeOrigin::root(), EVE, Balance::MAX),
+					ArithmeticError::Overflow
+				);
+
+				// then nothing has changed.
+				assert_eq!(free_of(&EVE), None);
+				assert_eq!(TotalIssuance::<Runtime>::get(), 200);
+			});
+		}
+
+		#[test]
+		fn transfer_to_self_is_noop() {
+			ExtBuilder::default().build_and_execute(|| {
+				// given the initial state, when:
+				assert_ok!(Barrel::<Runtime>::transfer(RuntimeOrigin::signed(ALICE), ALICE, 100));
+
+				// then:
+				assert_eq!(free_of(&ALICE), Some(100));
+				assert_eq!(TotalIssuance::<Runtime>::get(), 200);
+
+				// but it still must be affordable.
+				assert_noop!(
+					Barrel::<Runtime>::transfer(RuntimeOrigin::signed(ALICE), ALICE, 101),
+					Error::<Runtime>::InsufficientBalance
+				);
+			});
+		}
+
+		#[test]
+		fn transfer_to_self_respects_vesting() {
+			ExtBuilder::default().add_vesting(ALICE, 50, 10, 10).build_and_execute(|| {
+				// given 50 of alice's funds are locked, when, then she cannot send them to herself.
+				assert_noop!(
+					Barrel::<Runtime>::transfer(RuntimeOrigin::signed(ALICE), ALICE, 51),
+					Error::<Runtime>::VestingLocked
+				);
+
+				// but the rest she can, without any events.
+				assert_ok!(Barrel::<Runtime>::transfer(RuntimeOrigin::signed(ALICE), ALICE, 50));
+				assert!(currency_events().is_empty());
+			});
+		}
+
+		#[test]
+		fn try_state_detects_broken_issuance() {
+			ExtBuilder::default().build_and_execute(|| {
+				assert_ok!(Barrel::<Runtime>::do_try_state());
+
+				// when the issuance is tampered with:
+				TotalIssuance::<Runtime>::put(201);
+
+				// then:
+				assert!(Barrel::<Runtime>::do_try_state().is_err());
+
+				// restore, so that the final check of `build_and_execute` passes.
+				TotalIssuance::<Runtime>::put(200);
+			});
+		}
+
+		#[test]
+		fn genesis_vesting_locks_funds() {
+			ExtBuilder::default().add_vesting(ALICE, 50, 10, 10).build_and_execute(|| {
+				// given the initial state, 50 of alice's funds are locked.
+				assert_eq!(Barrel::<Runtime>::vesting_locked(&ALICE), 50);
+				assert_eq!(Barrel::<Runtime>::reducible_balance(&ALICE, false), 50);
+
+				// when, then alice cannot transfer, burn or reserve the locked part.
+				assert_noop!(
+					Barrel::<Runtime>::transfer(RuntimeOrigin::signed(ALICE), BOB, 51),
+					Error::<Runtime>::VestingLocked
+				);
+				assert_noop!(
+					Barrel::<Runtime>::burn(RuntimeOrigin::signed(ALICE), 51),
+					Error::<Runtime>::VestingLocked
+				);
+				assert_noop!(
+					Barrel::<Runtime>::reserve(HoldReason::ValidatorBond, &ALICE, 51),
+					Error::<Runtime>::VestingLocked
+				);
+				assert_ok!(Barrel::<Runtime>::transfer(RuntimeOrigin::signed(ALICE), BOB, 50));
+
+				// when 2 blocks after the start of the schedule:
+				frame_system::Barrel::<Runtime>::set_block_number(12);
+
+				// then:
+				assert_eq!(Barrel::<Runtime>::vesting_locked(&ALICE), 30);
+				assert_ok!(Barrel::<Runtime>::transfer(RuntimeOrigin::signed(ALICE), BOB, 20));
+				assert_noop!(
+					Barrel::<Runtime>::transfer(RuntimeOrigin::signed(ALICE), BOB, 1),
+					Error::<Runtime>::VestingLocked
+				);
+			});
+		}
+
+		#[test]
+		fn vested_transfer_works() {
+			ExtBuilder::default().build_and_execute(|| {
+				// given the initial state, when:
+				let schedule = VestingSchedule { locked: 40, per_block: 20, starting_block: 5 };
+				assert_ok!(Barrel::<Runtime>::vested_transfer(
+					RuntimeOrigin::signed(ALICE),
+					EVE,
+					schedule
+				));
+
+				// then:
+				assert_eq!(free_of(&EVE), Some(40));
+				assert_eq!(Vesting::<Runtime>::get(&EVE).into_inner(), vec![schedule]);
+				assert_eq!(Barrel::<Runtime>::vesting_locked(&EVE), 40);
+				assert_eq!(
+					currency_events().last(),
+					Some(&Event::VestingScheduleAdded { who: EVE, schedule })
+				);
+
+				// cannot add more than `MaxVestingSchedules`.
+				assert_ok!(Barrel::<Runtime>::vested_transfer(
+					RuntimeOrigin::signed(ALICE),
+					EVE,
+					schedule
+				));
+				assert_noop!(
+					Barrel::<Runtime>::vested_transfer(RuntimeOrigin::signed(ALICE), EVE, schedule),
+					Error::<Runtime>::TooManySchedules
+				);
+
+				// and schedules must unlock something.
+				assert_noop!(
+					Barrel::<Runtime>::vested_transfer(
+						RuntimeOrigin::signed(ALICE),
+						EVE,
+						VestingSchedule { locked: 40, per_block: 0, starting_block: 5 }
+					),
+					Error::<Runtime>::InvalidSchedule
+				);
+			});
+		}
+
+		#[test]
+		fn vest_removes_completed_schedules() {
+			ExtBuilder::default().add_vesting(ALICE, 50, 10, 10).build_and_execute(|| {
+				// given the initial state, when vesting before the end of the schedule:
+				frame_system::Barrel::<Runtime>::set_block_number(14);
+				assert_ok!(Barrel::<Runtime>::vest(RuntimeOrigin::signed(ALICE)));
+
+				// then nothing has changed.
+				assert_eq!(Vesting::<Runtime>::get(&ALICE).len(), 1);
+
+				// when vesting at the end of the schedule:
+				frame_system::Barrel::<Runtime>::set_block_number(15);
+				assert_ok!(Barrel::<Runtime>::vest(RuntimeOrigin::signed(ALICE)));
+
+				// then:
+				assert!(!Vesting::<Runtime>::contains_key(&ALICE));
+				assert_eq!(currency_events(), vec![Event::VestingCompleted { who: ALICE }]);
+			});
+		}
+
+		#[test]
+		fn approve_and_change_allowance_works() {
+			ExtBuilder::default().build_and_execute(|| {
+				// given the initial state, when:
+				assert_ok!(Barrel::<Runtime>::approve(RuntimeOrigin::signed(ALICE), BOB, 30));
+				assert_ok!(Barrel::<Runtime>::increase_allowance(
+					RuntimeOrigin::signed(ALICE),
+					BOB,
+					20
+				));
+				assert_ok!(Barrel::<Runtime>::decrease_allowance(
+					RuntimeOrigin::signed(ALICE),
+					BOB,
+					10
+				));
+
+				// then:
+				assert_eq!(Allowances::<Runtime>::get(ALICE, BOB), 40);
+				assert_eq!(
+					currency_events(),
+					vec![
+						Event::Approved { owner: ALICE, spender: BOB, amount: 30 },
+						Event::Approved { owner: ALICE, spender: BOB, amount: 50 },
+						Event::Approved { owner: ALICE, spender: BOB, amount: 40 },
+					]
+				);
+
+				// cannot decrease below zero.
+				assert_noop!(
+					Barrel::<Runtime>::decrease_allowance(RuntimeOrigin::signed(ALICE), BOB, 41),
+					Error::<Runtime>::InsufficientAllowance
+				);
+
+				// when approving zero, then the allowance is removed.
+				assert_ok!(Barrel::<Runtime>::approve(RuntimeOrigin::signed(ALICE), BOB, 0));
+				assert!(!Allowances::<Runtime>::contains_key(ALICE, BOB));
+			});
+		}
+
+		#[test]
+		fn transfer_from_works() {
+			ExtBuilder::default().build_and_execute(|| {
+				// given bob can spend 30 of alice's funds.
+				assert_ok!(Barrel::<Runtime>::approve(RuntimeOrigin::signed(ALICE), BOB, 30));
+
+				// when:
+				assert_ok!(Barrel::<Runtime>::transfer_from(
+					RuntimeOrigin::signed(BOB),
+					ALICE,
+					EVE,
+					20
+				));
+
+				// then:
+				assert_eq!(free_of(&ALICE), Some(80));
+				assert_eq!(free_of(&EVE), Some(20));
+				assert_eq!(Allowances::<Runtime>::get(ALICE, BOB), 10);
+				assert_eq!(
+					currency_events().last(),
+					Some(&Event::AllowanceSpent { owner: ALICE, spender: BOB, amount: 20 })
+				);
+
+				// cannot spend more than the allowance, or spend without one.
+				assert_noop!(
+					Barrel::<Runtime>::transfer_from(RuntimeOrigin::signed(BOB), ALICE, EVE, 11),
+					Error::<Runtime>::InsufficientAllowance
+				);
+				assert_noop!(
+					Barrel::<Runtime>::transfer_from(RuntimeOrigin::signed(EVE), ALICE, EVE, 1),
+					Error::<Runtime>::InsufficientAllowance
+				);
+
+				// when the rest of the allowance is spent, then it is removed.
+				assert_ok!(Barrel::<Runtime>::transfer_from(
+					RuntimeOrigin::signed(BOB),
+					ALICE,
+					BOB,
+					10
+				));
+				assert!(!Allowances::<Runtime>::contains_key(ALICE, BOB));
+			});
+		}
+
+		#[test]
+		fn transfer_from_respects_balance() {
+			ExtBuilder::default().build_and_execute(|| {
+				// given bob can spend more than alice has.
+				assert_ok!(Barrel::<Runtime>::approve(RuntimeOrigin::signed(ALICE), BOB, 500));
+
+				// when, then:
+				assert_noop!(
+					Barrel::<Runtime>::transfer_from(RuntimeOrigin::signed(BOB), ALICE, EVE, 101),
+					Error::<Runtime>::InsufficientBalance
+				);
+			});
+		}
+
+		#[test]
+		fn transfer_from_spends_the_fee_from_the_allowance() {
+			ExtBuilder::default().build_and_execute(|| {
+				// given a fee of 2, and bob can spend 30 of alice's funds.
+				FlatTransferFee::set(2);
+				assert_ok!(Barrel::<Runtime>::approve(RuntimeOrigin::signed(ALICE), BOB, 30));
+
+				// when, then bob cannot move the whole allowance, since alice also pays the fee.
+				assert_noop!(
+					Barrel::<Runtime>::transfer_from(RuntimeOrigin::signed(BOB), ALICE, EVE, 30),
+					Error::<Runtime>::InsufficientAllowance
+				);
+
+				// when:
+				assert_ok!(Barrel::<Runtime>::transfer_from(
+					RuntimeOrigin::signed(BOB),
+					ALICE,
+					EVE,
+					28
+				));
+
+				// then alice paid no more than she approved.
+				assert_eq!(free_of(&ALICE), Some(70));
+				assert_eq!(free_of(&EVE), Some(28));
+				assert!(!Allowances::<Runtime>::contains_key(ALICE, BOB));
+				assert_eq!(
+					currency_events().last(),
+					Some(&Event::AllowanceSpent { owner: ALICE, spender: BOB, amount: 30 })
+				);
+			});
+		}
+
+		#[test]
+		fn transfer_many_works() {
+			ExtBuilder::default().build_and_execute(|| {
+				// given the initial state, when:
+				let transfers = vec![(BOB, 10), (EVE, 20), (EVE, 5)];
+				assert_ok!(Barrel::<Runtime>::transfer_many(
+					RuntimeOrigin::signed(ALICE),
+					transfers.try_into().unwrap()
+				));
+
+				// then:
+				assert_eq!(free_of(&ALICE), Some(65));
+				assert_eq!(free_of(&BOB), Some(110));
+				assert_eq!(free_of(&EVE), Some(25));
+				assert_eq!(
+					currency_events(),
+					vec![
+						Event::Transferred { from: ALICE, to: BOB, amount: 10 },
+						Event::Endowed { who: EVE, free: 20 },
+						Event::Transferred { from: ALICE, to: EVE, amount: 20 },
+						Event::Transferred { from: ALICE, to: EVE, amount: 5 },
+					]
+				);
+			});
+		}
+
+		#[test]
+		fn transfer_many_is_all_or_nothing() {
+			ExtBuilder::default().build_and_execute(|| {
+				// given the initial state, when the last leg cannot be afforded:
+				let transfers = vec![(BOB, 50), (EVE, 60)];
+				let call = RuntimeCall::Currency(Call::transfer_many {
+					transfers: transfers.try_into().unwrap(),
+				});
+
+				// then none of the legs are executed.
+				assert!(call.dispatch(RuntimeOrigin::signed(ALICE)).is_err());
+				assert_eq!(free_of(&ALICE), Some(100));
+				assert_eq!(free_of(&BOB), Some(100));
+				assert_eq!(free_of(&EVE), None);
+				assert!(currency_events().is_empty());
+			});
+		}
+
+		#[test]
+		fn transfer_many_weight_is_proportional() {
+			let transfers = |n: u64| {
+				let call = Call::<Runtime>::transfer_many {
+					transfers: (0..n).map(|i| (i, 1)).collect::<Vec<_>>().try_into().unwrap(),
+				};
+				call.get_dispatch_info().weight
+			};
+
+			let per_transfer = transfers(1).saturating_sub(transfers(0));
+			assert!(!per_transfer.is_zero());
+			assert_eq!(transfers(4).saturating_sub(transfers(0)), per_transfer.saturating_mul(4));
+		}
+
+		#[test]
+		fn holders_count_works() {
+			ExtBuilder::default().build_and_execute(|| {
+				// given the initial state, then:
+				assert_eq!(Barrel::<Runtime>::holders_count(), 2);
+
+				// when eve is endowed and alice is reaped:
+				assert_ok!(Barrel::<Runtime>::transfer(RuntimeOrigin::signed(BOB), EVE, 10));
+				assert_ok!(Barrel::<Runtime>::transfer(RuntimeOrigin::signed(ALICE), BOB, 100));
+
+				// then:
+				assert_eq!(Barrel::<Runtime>::holders_count(), 2);
+				assert!(free_of(&ALICE).is_none());
+			});
+		}
+
+		#[test]
+		fn freeze_and_thaw_work() {
+			ExtBuilder::default().build_and_execute(|| {
+				// given the initial state, when:
+				assert_noop!(
+					Barrel::<Runtime>::freeze_account(RuntimeOrigin::signed(BOB), ALICE),
+					DispatchError::BadOrigin
+				);
+				assert_noop!(
+					Barrel::<Runtime>::thaw_account(RuntimeOrigin::root(), ALICE),
+					Error::<Runtime>::NotFrozen
+				);
+				assert_ok!(Barrel::<Runtime>::freeze_account(RuntimeOrigin::root(), ALICE));
+
+				// then:
+				assert!(Barrel::<Runtime>::is_frozen(&ALICE));
+				assert_noop!(
+					Barrel::<Runtime>::freeze_account(RuntimeOrigin::root(), ALICE),
+					Error::<Runtime>::AlreadyFrozen
+				);
+
+				// when:
+				assert_ok!(Barrel::<Runtime>::thaw_account(RuntimeOrigin::root(), ALICE));
+
+				// then:
+				assert!(!Barrel::<Runtime>::is_frozen(&ALICE));
+				assert_eq!(
+					currency_events(),
+					vec![Event::AccountFrozen { who: ALICE }, Event::AccountThawed { who: ALICE }]
+				);
+			});
+		}
+
+		#[test]
+		fn frozen_account_cannot_move_funds() {
+			use frame::traits::{
+				fungible::{Inspect, MutateHold},
+				tokens::{Fortitude::Polite, Preservation},
+			};
+			type Currency = Barrel<Runtime>;
+
+			ExtBuilder::default().build_and_execute(|| {
+				// given:
+				assert_ok!(Barrel::<Runtime>::approve(RuntimeOrigin::signed(ALICE), EVE, 50));
+				assert_ok!(Barrel::<Runtime>::freeze_account(RuntimeOrigin::root(), ALICE));
+
+				// when, then alice cannot send, burn or hold any funds.
+				assert_noop!(
+					Barrel::<Runtime>::transfer(RuntimeOrigin::signed(ALICE), BOB, 10),
+					Error::<Runtime>::AccountFrozen
+				);
+				assert_noop!(
+					Barrel::<Runtime>::transfer(RuntimeOrigin::signed(ALICE), ALICE, 10),
+					Error::<Runtime>::AccountFrozen
+				);
+				assert_noop!(
+					Barrel::<Runtime>::transfer_from(RuntimeOrigin::signed(EVE), ALICE, BOB, 10),
+					Error::<Runtime>::AccountFrozen
+				);
+				assert_noop!(
+					Barrel::<Runtime>::burn(RuntimeOrigin::signed(ALICE), 10),
+					Error::<Runtime>::AccountFrozen
+				);
+				assert_noop!(
+					Barrel::<Runtime>::reserve(HoldReason::ValidatorBond, &ALICE, 10),
+					Error::<Runtime>::AccountFrozen
+				);
+				assert_eq!(
+					<Currency as Inspect<_>>::reducible_balance(
+						&ALICE,
+						Preservation::Expendable,
+						Polite
+					),
+					0
+				);
+				assert!(
+					<Currency as MutateHold<_>>::hold(&HoldReason::Delegation, &ALICE, 10).is_err()
+				);
+
+				// but it can still receive funds.
+				assert_ok!(Barrel::<Runtime>::transfer(RuntimeOrigin::signed(BOB), ALICE, 10));
+				assert_eq!(free_of(&ALICE), Some(110));
+
+				// when thawed, then alice can send funds again.
+				assert_ok!(Barrel::<Runtime>::thaw_account(RuntimeOrigin::root(), ALICE));
+				assert_ok!(Barrel::<Runtime>::transfer(RuntimeOrigin::signed(ALICE), BOB, 10));
+			});
+		}
+
+		#[test]
+		fn inflation_is_minted_every_era() {
+			ExtBuilder::default().build_and_execute(|| {
+				// given a yearly inflation of 10%, over 10 eras of 10 blocks.
+				YearlyInflation::set(Perbill::from_percent(10));
+				assert_eq!(Barrel::<Runtime>::era_inflation(200), 2);
+				assert_eq!(Barrel::<Runtime>::projected_issuance(2), Some(204));
+				assert_eq!(Barrel::<Runtime>::projected_issuance(MAX_PROJECTED_ERAS + 1), None);
+
+				// when, then nothing is minted within an era.
+				assert_eq!(
+					Barrel::<Runtime>::on_initialize(9),
+					<() as crate::currency::WeightInfo>::release_scheduled_transfers(0, 0)
+				);
+				assert_eq!(TotalIssuance::<Runtime>::get(), 200);
+
+				// when, then 2 is minted at the end of the first era, which is less than the
+				// existential deposit, so the receiver cannot be created.
+				Barrel::<Runtime>::on_initialize(10);
+				assert_eq!(TotalIssuance::<Runtime>::get(), 200);
+				assert_eq!(free_of(&InflationReceiver::get()), None);
+
+				// when the issuance grows:
+				assert_ok!(Barrel::<Runtime>::mint(RuntimeOrigin::root(), EVE, 300));
+				Barrel::<Runtime>::on_initialize(20);
+
+				// then:
+				assert_eq!(TotalIssuance::<Runtime>::get(), 505);
+				assert_eq!(free_of(&InflationReceiver::get()), Some(5));
+				assert_eq!(Barrel::<Runtime>::projected_issuance(1), Some(510));
+				assert_eq!(
+					currency_events(),
+					vec![
+						Event::Endowed { who: EVE, free: 300 },
+						Event::Minted { to: EVE, amount: 300 },
+						Event::Endowed { who: InflationReceiver::get(), free: 5 },
+						Event::Inflated { amount: 5 },
+					]
+				);
+			});
+		}
+
+		#[test]
+		fn transfer_fee_is_split_between_burning_and_collector() {
+			ExtBuilder::default().build_and_execute(|| {
+				// given a fee of 10, half of which is burned.
+				FlatTransferFee::set(10);
+
+				// when, then alice cannot afford the fee on top of her whole balance.
+				assert_noop!(
+					Barrel::<Runtime>::transfer(RuntimeOrigin::signed(ALICE), BOB, 95),
+					Error::<Runtime>::InsufficientBalance
+				);
+
+				// when:
+				assert_ok!(Barrel::<Runtime>::transfer(RuntimeOrigin::signed(ALICE), BOB, 50));
+
+				// then:
+				assert_eq!(free_of(&ALICE), Some(40));
+				assert_eq!(free_of(&BOB), Some(150));
+				assert_eq!(free_of(&FeeCollector::get()), Some(5));
+				assert_eq!(TotalIssuance::<Runtime>::get(), 195);
+				assert_eq!(
+					currency_events(),
+					vec![
+						Event::Transferred { from: ALICE, to: BOB, amount: 50 },
+						Event::Endowed { who: FeeCollector::get(), free: 5 },
+						Event::TransferFeePaid { who: ALICE, burned: 5, collected: 5 },
+					]
+				);
+
+				// when alice transfers all of her balance, then she also pays the fee.
+				assert_ok!(Barrel::<Runtime>::transfer_all(
+					RuntimeOrigin::signed(ALICE),
+					BOB,
+					false
+				));
+				assert_eq!(free_of(&ALICE), None);
+				assert_eq!(free_of(&BOB), Some(180));
+				assert_eq!(free_of(&FeeCollector::get()), Some(10));
+				assert_eq!(TotalIssuance::<Runtime>::get(), 190);
+			});
+		}
+
+		#[test]
+		fn transfer_fee_is_burned_if_collector_cannot_receive_it() {
+			ExtBuilder::default().build_and_execute(|| {
+				// given a fee of 4, of which 2 would go to the collector, below the existential
+				// deposit.
+				FlatTransferFee::set(4);
+
+				// when:
+				assert_ok!(Barrel::<Runtime>::transfer(RuntimeOrigin::signed(ALICE), BOB, 50));
+
+				// then:
+				assert_eq!(free_of(&ALICE), Some(46));
+				assert_eq!(free_of(&FeeCollector::get()), None);
+				assert_eq!(TotalIssuance::<Runtime>::get(), 196);
+				assert_eq!(
+					currency_events().last(),
+					Some(&Event::TransferFeePaid { who: ALICE, burned: 4, collected: 0 })
+				);
+			});
+		}
+
+		#[test]
+		fn fee_policies_work() {
+			parameter_types! {
+				pub const Rate: Perbill = Perbill::from_percent(1);
+				pub Tiers: Vec<(Balance, Perbill)> =
+					vec![(100, Perbill::from_percent(1)), (1000, Perbill::from_percent(2))];
+			}
+
+			assert_eq!(<() as TransferFee>::fee(1000), 0);
+			assert_eq!(FlatFee::<ConstU128<3>>::fee(1000), 3);
+			assert_eq!(PercentageFee::<Rate>::fee(1000), 10);
+			assert_eq!(PercentageFee::<Rate>::fee(50), 1);
+			assert_eq!(TieredFee::<Tiers>::fee(99), 0);
+			assert_eq!(TieredFee::<Tiers>::fee(500), 5);
+			assert_eq!(TieredFee::<Tiers>::fee(1000), 20);
+		}
+
+		#[test]
+		fn balance_hooks_are_called() {
+			ExtBuilder::default().build_and_execute(|| {
+				// given the hooks called at genesis are ignored.
+				HookCalls::take();
+
+				// when:
+				assert_ok!(Barrel::<Runtime>::mint(RuntimeOrigin::root(), EVE, 50));
+
+				// then:
+				let eve = |free| AccountData { free, reserved: 0 };
+				assert_eq!(
+					HookCalls::take(),
+					vec![Hook::Created(EVE), Hook::Changed(EVE, eve(50))]
+				);
+
+				// when:
+				assert_ok!(Barrel::<Runtime>::reserve(HoldReason::Delegation, &EVE, 20));
+
+				// then:
+				assert_eq!(
+					HookCalls::take(),
+					vec![Hook::Changed(EVE, AccountData { free: 30, reserved: 20 })]
+				);
+
+				// when eve is slashed and transfers everything away, then she is reaped.
+				assert_eq!(Barrel::<Runtime>::slash_reserved(HoldReason::Delegation, &EVE, 20), 20);
+				assert_ok!(Barrel::<Runtime>::transfer(RuntimeOrigin::signed(EVE), BOB, 30));
+				assert_eq!(
+					HookCalls::take(),
+					vec![
+						Hook::Changed(EVE, eve(30)),
+						Hook::Changed(EVE, eve(0)),
+						Hook::Killed(EVE),
+						Hook::Changed(BOB, AccountData { free: 130, reserved: 0 }),
+						Hook::Transfer(EVE, BOB, 30),
+					]
+				);
+
+				// when the transfer leaves dust behind, then she is reaped afterwards.
+				assert_ok!(Barrel::<Runtime>::transfer(RuntimeOrigin::signed(ALICE), EVE, 10));
+				HookCalls::take();
+				assert_ok!(Barrel::<Runtime>::transfer(RuntimeOrigin::signed(EVE), BOB, 8));
+				assert_eq!(
+					HookCalls::take(),
+					vec![
+						Hook::Changed(EVE, eve(2)),
+						Hook::Changed(BOB, AccountData { free: 138, reserved: 0 }),
+						Hook::Transfer(EVE, BOB, 8),
+						Hook::Changed(EVE, eve(0)),
+						Hook::Killed(EVE),
+					]
+				);
+			});
+		}
+
+		#[test]
+		fn scheduled_transfer_is_released_at_its_block() {
+			ExtBuilder::default().build_and_execute(|| {
+				// given a fee of 2, which is charged when the transfer is scheduled.
+				FlatTransferFee::set(2);
+
+				// when:
+				assert_ok!(Barrel::<Runtime>::schedule_transfer(
+					RuntimeOrigin::signed(ALICE),
+					EVE,
+					30,
+					3
+				));
+
+				// then the funds are held in escrow.
+				assert_eq!(
+					Barrel::<Runtime>::account(&ALICE),
+					Some(AccountData { free: 68, reserved: 30 })
+				);
+				assert_eq!(
+					ScheduledTransfers::<Runtime>::get(0),
+					Some(ScheduledTransfer { from: ALICE, to: EVE, amount: 30, at: 3 })
+				);
+				assert_eq!(TransferAgenda::<Runtime>::get(3), vec![0]);
+				assert_eq!(AgendaBlocks::<Runtime>::get(), vec![3]);
+
+				// when, then nothing is released before block 3.
+				Barrel::<Runtime>::on_initialize(2);
+				assert_eq!(free_of(&EVE), None);
+
+				// when:
+				Barrel::<Runtime>::on_initialize(3);
+
+				// then:
+				assert_eq!(free_of(&EVE), Some(30));
+				assert_eq!(
+					Barrel::<Runtime>::account(&ALICE),
+					Some(AccountData { free: 68, reserved: 0 })
+				);
+				assert_eq!(ScheduledTransfers::<Runtime>::get(0), None);
+				assert!(!TransferAgenda::<Runtime>::contains_key(3));
+				assert!(AgendaBlocks::<Runtime>::get().is_empty());
+				assert_eq!(
+					currency_events(),
+					vec![
+						Event::Reserved {
+							who: ALICE,
+							reason: HoldReason::ScheduledTransfer,
+							amount: 30
+						},
+						Event::TransferFeePaid { who: ALICE, burned: 2, collected: 0 },
+						Event::TransferScheduled { id: 0, from: ALICE, to: EVE, amount: 30, at: 3 },
+						Event::Endowed { who: EVE, free: 30 },
+						Event::ReserveRepatriated {
+							from: ALICE,
+							to: EVE,
+							reason: HoldReason::ScheduledTransfer,
+							amount: 30
+						},
+						Event::ScheduledTransferReleased { id: 0 },
+					]
+				);
+			});
+		}
+
+		#[test]
+		fn scheduled_transfer_can_be_cancelled_before_its_block() {
+			ExtBuilder::default().build_and_execute(|| {
+				// when, then a transfer cannot be scheduled for the current block, or of nothing.
+				assert_noop!(
+					Barrel::<Runtime>::schedule_transfer(RuntimeOrigin::signed(ALICE), EVE, 30, 1),
+					Error::<Runtime>::ReleaseNotInFuture
+				);
+				assert_noop!(
+					Barrel::<Runtime>::schedule_transfer(RuntimeOrigin::signed(ALICE), EVE, 0, 3),
+					Error::<Runtime>::ZeroAmount
+				);
+
+				// given two transfers due at block 3.
+				assert_ok!(Barrel::<Runtime>::schedule_transfer(
+					RuntimeOrigin::signed(ALICE),
+					EVE,
+					30,
+					3
+				));
+				assert_ok!(Barrel::<Runtime>::schedule_transfer(
+					RuntimeOrigin::signed(ALICE),
+					BOB,
+					20,
+					3
+				));
+
+				// when, then only the sender can cancel them.
+				assert_noop!(
+					Barrel::<Runtime>::cancel_scheduled_transfer(RuntimeOrigin::signed(BOB), 0),
+					Error::<Runtime>::NotTransferSender
+				);
+				assert_noop!(
+					Barrel::<Runtime>::cancel_scheduled_transfer(RuntimeOrigin::signed(ALICE), 2),
+					Error::<Runtime>::UnknownScheduledTransfer
+				);
+
+				// when:
+				frame_system::Barrel::<Runtime>::set_block_number(2);
+				assert_ok!(Barrel::<Runtime>::cancel_scheduled_transfer(
+					RuntimeOrigin::signed(ALICE),
+					0
+				));
+
+				// then:
+				assert_eq!(
+					Barrel::<Runtime>::account(&ALICE),
+					Some(AccountData { free: 80, reserved: 20 })
+				);
+				assert_eq!(ScheduledTransfers::<Runtime>::get(0), None);
+				assert_eq!(TransferAgenda::<Runtime>::get(3), vec![1]);
+				assert_eq!(AgendaBlocks::<Runtime>::get(), vec![3]);
+				System::assert_last_event(Event::ScheduledTransferCancelled { id: 0 }.into());
+
+				// when, then it is too late to cancel at the release block.
+				frame_system::Barrel::<Runtime>::set_block_number(3);
+				assert_noop!(
+					Barrel::<Runtime>::cancel_scheduled_transfer(RuntimeOrigin::signed(ALICE), 1),
+					Error::<Runtime>::TooLateToCancel
+				);
+				Barrel::<Runtime>::on_initialize(3);
+				assert_eq!(free_of(&EVE), None);
+				assert_eq!(free_of(&BOB), Some(120));
+			});
+		}
+
+		#[test]
+		fn scheduled_transfers_are_released_in_bounded_batches() {
+			ExtBuilder::default().build_and_execute(|| {
+				// given 3 transfers due at block 2, the last of which cannot create its recipient,
+				// and 1 due at block 3.
+				let schedule = |to, amount, at| {
+					Barrel::<Runtime>::schedule_transfer(
+						RuntimeOrigin::signed(ALICE),
+						to,
+						amount,
+						at,
+					)
+				};
+				assert_ok!(schedule(EVE, 10, 2));
+				assert_ok!(schedule(EVE, 10, 2));
+				assert_ok!(schedule(7, 3, 2));
+				assert_ok!(schedule(BOB, 10, 3));
+
+				// when, then the agenda of a block, and the number of blocks, are bounded.
+				assert_noop!(schedule(BOB, 10, 2), Error::<Runtime>::AgendaFull);
+				assert_noop!(schedule(BOB, 10, 4), Error::<Runtime>::TooManyAgendaBlocks);
+				assert_eq!(AgendaBlocks::<Runtime>::get(), vec![2, 3]);
+
+				// when only 2 transfers can be released per block:
+				Barrel::<Runtime>::on_initialize(2);
+
+				// then the third one is carried over.
+				assert_eq!(free_of(&EVE), Some(20));
+				assert_eq!(TransferAgenda::<Runtime>::get(2), vec![2]);
+				assert_eq!(AgendaBlocks::<Runtime>::get(), vec![2, 3]);
+
+				// when:
+				frame_system::Barrel::<Runtime>::reset_events();
+				Barrel::<Runtime>::on_initialize(3);
+
+				// then the carried over transfer is released first, and fails.
+				assert_eq!(free_of(&7), None);
+				assert_eq!(free_of(&BOB), Some(110));
+				assert_eq!(
+					Barrel::<Runtime>::account(&ALICE),
+					Some(AccountData { free: 70, reserved: 0 })
+				);
+				assert!(AgendaBlocks::<Runtime>::get().is_empty());
+				assert_eq!(
+					currency_events(),
+					vec![
+						Event::Unreserved {
+							who: ALICE,
+							reason: HoldReason::ScheduledTransfer,
+							amount: 3
+						},
+						Event::ScheduledTransferFailed {
+							id: 2,
+							error: Error::<Runtime>::ExistentialDeposit.into()
+						},
+						Event::ReserveRepatriated {
+							from: ALICE,
+							to: BOB,
+							reason: HoldReason::ScheduledTransfer,
+							amount: 10
+						},
+						Event::ScheduledTransferReleased { id: 3 },
+					]
+				);
+			});
+		}
+
+		#[test]
+		fn accounts_are_stored_in_system() {
+			ExtBuilder::default().build_and_execute(|| {
+				// given the initial state, then:
+				assert_eq!(
+					frame_system::Account::<Runtime>::get(&ALICE).data,
+					AccountData { free: 100, reserved: 0 }
+				);
+				assert_eq!(System::providers(&ALICE), 1);
+				assert!(!System::account_exists(&EVE));
+
+				// when eve is endowed, and alice puts some funds on hold:
+				assert_ok!(Barrel::<Runtime>::transfer(RuntimeOrigin::signed(BOB), EVE, 10));
+				assert_ok!(Barrel::<Runtime>::reserve(HoldReason::ValidatorBond, &ALICE, 30));
+
+				// then:
+				assert_eq!(System::providers(&EVE), 1);
+				assert_eq!(
+					frame_system::Account::<Runtime>::get(&ALICE).data,
+					AccountData { free: 70, reserved: 30 }
+				);
+
+				// when eve is reaped:
+				assert_ok!(Barrel::<Runtime>::transfer(RuntimeOrigin::signed(EVE), BOB, 10));
+
+				// then its account is removed from system.
+				assert!(!System::account_exists(&EVE));
+				assert_eq!(System::providers(&EVE), 0);
+				assert_eq!(Barrel::<Runtime>::holders_count(), 2);
+			});
+		}
+
+		#[test]
+		fn storage_is_bounded() {
+			for info in Barrel::<Runtime>::storage_info() {
+				assert!(info.max_size.is_some(), "{:?} is unbounded", info.storage_name);
+			}
 		}
 	}
 }
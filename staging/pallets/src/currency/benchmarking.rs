@@ -0,0 +1,340 @@
+//! Benchmarks for the currency barrel.
+
+use super::*;
+use frame::{
+	prelude::*,
+	traits::{fungible::Mutate, Zero},
+};
+use frame_benchmarking::v2::*;
+use frame_system::RawOrigin;
+
+/// A balance that is comfortably above the existential deposit.
+fn large_balance<T: Config>() -> Balance {
+	T::ExistentialDeposit::get().max(1).saturating_mul(1_000)
+}
+
+/// `amount` plus the transfer fee that is charged for transferring it.
+fn with_fee<T: Config>(amount: Balance) -> Balance {
+	amount.saturating_add(T::TransferFee::fee(amount))
+}
+
+/// Fill the agenda of block `at` with transfers, leaving room for `room` more.
+fn fill_agenda<T: Config>(at: BlockNumberFor<T>, room: u32) {
+	let count = T::MaxScheduledPerBlock::get().saturating_sub(room);
+	let ids = (0..count).map(|i| ScheduledTransferId::MAX - i).collect::<Vec<_>>();
+	TransferAgenda::<T>::insert(at, BoundedVec::try_from(ids).unwrap());
+}
+
+/// Fill [`AgendaBlocks`] with blocks after `at`, so that it has room for `room` more blocks.
+fn fill_agenda_blocks<T: Config>(at: BlockNumberFor<T>, room: u32) {
+	let count = T::MaxAgendaBlocks::get().saturating_sub(room);
+	let blocks = (1..=count).map(|i| at.saturating_add(i.into())).collect::<Vec<_>>();
+	AgendaBlocks::<T>::put(BoundedVec::try_from(blocks).unwrap());
+}
+
+/// Create an account with `amount` of free balance.
+fn funded<T: Config>(name: &'static str, index: u32, amount: Balance) -> T::AccountId {
+	let who = account(name, index, 0);
+	<Barrel<T> as Mutate<_>>::mint_into(&who, amount).unwrap();
+	who
+}
+
+#[benchmarks]
+mod benchmarks {
+	use super::*;
+
+	#[benchmark]
+	fn mint() -> Result<(), BenchmarkError> {
+		let origin =
+			T::MintOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let to: T::AccountId = account("to", 0, 0);
+		let amount = large_balance::<T>();
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, to.clone(), amount);
+
+		assert_eq!(Barrel::<T>::free_balance(&to), amount);
+		Ok(())
+	}
+
+	// worst case: the recipient is created, and the sender is reaped.
+	#[benchmark]
+	fn transfer() {
+		let amount = large_balance::<T>();
+		let from = funded::<T>("from", 0, with_fee::<T>(amount));
+		let to: T::AccountId = account("to", 0, 0);
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(from.clone()), to.clone(), amount);
+
+		assert!(Barrel::<T>::account(&from).is_none());
+		assert_eq!(Barrel::<T>::free_balance(&to), amount);
+	}
+
+	#[benchmark]
+	fn transfer_keep_alive() {
+		let balance = large_balance::<T>();
+		let amount = balance - T::ExistentialDeposit::get();
+		let from = funded::<T>("from", 0, balance + T::TransferFee::fee(amount));
+		let to: T::AccountId = account("to", 0, 0);
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(from.clone()), to.clone(), amount);
+
+		assert_eq!(Barrel::<T>::free_balance(&from), T::ExistentialDeposit::get());
+		assert_eq!(Barrel::<T>::free_balance(&to), amount);
+	}
+
+	#[benchmark]
+	fn transfer_all() {
+		let amount = large_balance::<T>();
+		let from = funded::<T>("from", 0, amount);
+		let to: T::AccountId = account("to", 0, 0);
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(from.clone()), to.clone(), false);
+
+		// the fee on the transferred amount can be less than the fee on the whole balance, which
+		// leaves a little behind.
+		assert!(Barrel::<T>::free_balance(&from) <= T::TransferFee::fee(amount));
+		assert_eq!(Barrel::<T>::free_balance(&to), amount - T::TransferFee::fee(amount));
+	}
+
+	#[benchmark]
+	fn burn() {
+		let balance = large_balance::<T>();
+		let who = funded::<T>("who", 0, balance);
+		let amount = balance - T::ExistentialDeposit::get();
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(who.clone()), amount);
+
+		assert_eq!(Barrel::<T>::free_balance(&who), T::ExistentialDeposit::get());
+	}
+
+	// worst case: all recipients are created.
+	#[benchmark]
+	fn transfer_many(n: Linear<1, { T::MaxTransfers::get() }>) {
+		let amount = large_balance::<T>();
+		let from = funded::<T>(
+			"from",
+			0,
+			with_fee::<T>(amount).saturating_mul(n.into()).saturating_add(amount),
+		);
+		let transfers = (0..n)
+			.map(|i| (account("to", i, 0), amount))
+			.collect::<Vec<(T::AccountId, Balance)>>();
+		let transfers: BoundedVec<_, T::MaxTransfers> = transfers.try_into().unwrap();
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(from.clone()), transfers);
+
+		assert_eq!(Barrel::<T>::free_balance(&from), amount);
+	}
+
+	// worst case: the recipient already has all but one of its vesting schedules.
+	#[benchmark]
+	fn vested_transfer() {
+		let amount = large_balance::<T>();
+		let from = funded::<T>("from", 0, with_fee::<T>(amount).saturating_mul(2));
+		let to = funded::<T>("to", 0, amount);
+		let existing = VestingSchedule { locked: 1, per_block: 1, starting_block: Zero::zero() };
+		let existing = vec![existing; T::MaxVestingSchedules::get().saturating_sub(1) as usize];
+		Vesting::<T>::insert(&to, BoundedVec::try_from(existing).unwrap());
+		let schedule =
+			VestingSchedule { locked: amount, per_block: 1, starting_block: Zero::zero() };
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(from), to.clone(), schedule);
+
+		assert_eq!(Vesting::<T>::get(&to).len() as u32, T::MaxVestingSchedules::get());
+	}
+
+	// worst case: the caller has the maximum number of schedules, all of which have completed.
+	#[benchmark]
+	fn vest() {
+		let amount = large_balance::<T>();
+		let who = funded::<T>("who", 0, amount);
+		let schedule =
+			VestingSchedule { locked: amount, per_block: amount, starting_block: Zero::zero() };
+		let schedules = vec![schedule; T::MaxVestingSchedules::get() as usize];
+		Vesting::<T>::insert(&who, BoundedVec::try_from(schedules).unwrap());
+		frame_system::Barrel::<T>::set_block_number(10u32.into());
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(who.clone()));
+
+		assert!(!Vesting::<T>::contains_key(&who));
+	}
+
+	#[benchmark]
+	fn approve() {
+		let owner: T::AccountId = whitelisted_caller();
+		let spender: T::AccountId = account("spender", 0, 0);
+		let amount = large_balance::<T>();
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(owner.clone()), spender.clone(), amount);
+
+		assert_eq!(Allowances::<T>::get(&owner, &spender), amount);
+	}
+
+	#[benchmark]
+	fn increase_allowance() {
+		let owner: T::AccountId = whitelisted_caller();
+		let spender: T::AccountId = account("spender", 0, 0);
+		let amount = large_balance::<T>();
+		Allowances::<T>::insert(&owner, &spender, amount);
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(owner.clone()), spender.clone(), amount);
+
+		assert_eq!(Allowances::<T>::get(&owner, &spender), amount.saturating_mul(2));
+	}
+
+	#[benchmark]
+	fn decrease_allowance() {
+		let owner: T::AccountId = whitelisted_caller();
+		let spender: T::AccountId = account("spender", 0, 0);
+		let amount = large_balance::<T>();
+		Allowances::<T>::insert(&owner, &spender, amount.saturating_mul(2));
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(owner.clone()), spender.clone(), amount);
+
+		assert_eq!(Allowances::<T>::get(&owner, &spender), amount);
+	}
+
+	// worst case: the recipient is created, and the allowance is removed.
+	#[benchmark]
+	fn transfer_from() {
+		let amount = large_balance::<T>();
+		let owner = funded::<T>("owner", 0, with_fee::<T>(amount).saturating_mul(2));
+		let spender: T::AccountId = whitelisted_caller();
+		let to: T::AccountId = account("to", 0, 0);
+		Allowances::<T>::insert(&owner, &spender, with_fee::<T>(amount));
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(spender.clone()), owner.clone(), to.clone(), amount);
+
+		assert!(!Allowances::<T>::contains_key(&owner, &spender));
+		assert_eq!(Barrel::<T>::free_balance(&to), amount);
+	}
+
+	#[benchmark]
+	fn freeze_account() -> Result<(), BenchmarkError> {
+		let origin =
+			T::FreezeOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let who: T::AccountId = account("who", 0, 0);
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, who.clone());
+
+		assert!(Barrel::<T>::is_frozen(&who));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn thaw_account() -> Result<(), BenchmarkError> {
+		let origin =
+			T::FreezeOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let who: T::AccountId = account("who", 0, 0);
+		FrozenAccounts::<T>::insert(&who, ());
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, who.clone());
+
+		assert!(!Barrel::<T>::is_frozen(&who));
+		Ok(())
+	}
+
+	// worst case: the beneficiary of the inflation is created.
+	#[benchmark]
+	fn mint_inflation() {
+		funded::<T>("who", 0, large_balance::<T>());
+
+		#[block]
+		{
+			Barrel::<T>::mint_inflation();
+		}
+	}
+
+	// worst case: the agenda of the release block has room for a single transfer, and the
+	// release block goes first in the agenda blocks, which have room for one more.
+	#[benchmark]
+	fn schedule_transfer() {
+		let amount = large_balance::<T>();
+		let from = funded::<T>("from", 0, with_fee::<T>(amount).saturating_add(amount));
+		let to: T::AccountId = account("to", 0, 0);
+		let at: BlockNumberFor<T> = 10u32.into();
+		fill_agenda::<T>(at, 1);
+		fill_agenda_blocks::<T>(at, 1);
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(from.clone()), to, amount, at);
+
+		assert_eq!(
+			Barrel::<T>::balance_on_hold(HoldReason::ScheduledTransfer.into(), &from),
+			amount
+		);
+	}
+
+	// worst case: the transfer is the only one of its block, which is removed from the full
+	// agenda blocks.
+	#[benchmark]
+	fn cancel_scheduled_transfer() {
+		let amount = large_balance::<T>();
+		let from = funded::<T>("from", 0, with_fee::<T>(amount).saturating_add(amount));
+		let to: T::AccountId = account("to", 0, 0);
+		let at: BlockNumberFor<T> = 10u32.into();
+		fill_agenda_blocks::<T>(at, 1);
+		let id = NextScheduledTransferId::<T>::get();
+		Barrel::<T>::schedule_transfer(RawOrigin::Signed(from.clone()).into(), to, amount, at)
+			.unwrap();
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(from.clone()), id);
+
+		assert!(!ScheduledTransfers::<T>::contains_key(id));
+		assert!(!AgendaBlocks::<T>::get().contains(&at));
+		assert!(Barrel::<T>::balance_on_hold(HoldReason::ScheduledTransfer.into(), &from).is_zero());
+	}
+
+	// the agendas of `b` blocks are looked at, and `t` transfers are released, all of which are
+	// due in the last one. The agendas of the others are left empty, which costs as much to look
+	// at, apart from the transfers. This assumes that `MaxScheduledPerBlock` is at least
+	// `MaxReleasesPerBlock`, and `MaxAgendaBlocks` at least `MaxReleasesPerBlock`.
+	//
+	// worst case: all recipients are created.
+	#[benchmark]
+	fn release_scheduled_transfers(
+		b: Linear<1, { T::MaxReleasesPerBlock::get() }>,
+		t: Linear<0, { T::MaxReleasesPerBlock::get() }>,
+	) {
+		let amount = large_balance::<T>();
+		let now: BlockNumberFor<T> = b.into();
+		for i in 0..t {
+			let from = funded::<T>("from", i, amount.saturating_mul(2));
+			Barrel::<T>::reserve(HoldReason::ScheduledTransfer.into(), &from, amount).unwrap();
+			let to: T::AccountId = account("to", i, 0);
+			ScheduledTransfers::<T>::insert(i, ScheduledTransfer { from, to, amount, at: now });
+			TransferAgenda::<T>::try_append(now, i).unwrap();
+		}
+		let blocks = (1..=b).map(BlockNumberFor::<T>::from).collect::<Vec<_>>();
+		AgendaBlocks::<T>::put(BoundedVec::try_from(blocks).unwrap());
+
+		#[block]
+		{
+			Barrel::<T>::release_scheduled_transfers(now);
+		}
+
+		assert_eq!(ScheduledTransfers::<T>::iter().count(), 0);
+		assert!(AgendaBlocks::<T>::get().is_empty());
+	}
+
+	impl_benchmark_test_suite!(
+		Barrel,
+		crate::currency::barrel::tests::test_state_new(),
+		crate::currency::barrel::tests::Runtime
+	);
+}
@@ -0,0 +1,207 @@
+//! Implementation of the `fungible` traits of FRAME for the currency barrel.
+//!
+//! This allows other barrels to be generic over their currency, and use either this barrel or
+//! `barrel_balances` interchangeably.
+
+use super::barrel::*;
+use frame::{
+	deps::{frame_support::storage::with_storage_layer, sp_runtime::ArithmeticError},
+	prelude::*,
+	traits::{
+		fungible,
+		tokens::{DepositConsequence, Fortitude, Preservation, Provenance, WithdrawConsequence},
+		Zero,
+	},
+};
+
+impl<T: Config> fungible::Inspect<T::AccountId> for Barrel<T> {
+	type Balance = Balance;
+
+	fn total_issuance() -> Balance {
+		TotalIssuance::<T>::get()
+	}
+
+	fn minimum_balance() -> Balance {
+		T::ExistentialDeposit::get()
+	}
+
+	fn total_balance(who: &T::AccountId) -> Balance {
+		Self::free_balance(who).saturating_add(Self::reserved_balance(who))
+	}
+
+	fn balance(who: &T::AccountId) -> Balance {
+		Self::free_balance(who)
+	}
+
+	/// Vesting locks are respected even with [`Fortitude::Force`], as they only apply to free
+	/// funds and would otherwise be bypassed by putting the funds on hold.
+	fn reducible_balance(
+		who: &T::AccountId,
+		preservation: Preservation,
+		_force: Fortitude,
+	) -> Balance {
+		Self::reducible_balance(who, preservation != Preservation::Expendable)
+	}
+
+	fn can_deposit(
+		who: &T::AccountId,
+		amount: Balance,
+		provenance: Provenance,
+	) -> DepositConsequence {
+		if amount.is_zero() {
+			return DepositConsequence::Success
+		}
+
+		if provenance == Provenance::Minted &&
+			TotalIssuance::<T>::get().checked_add(amount).is_none()
+		{
+			return DepositConsequence::Overflow
+		}
+
+		match Self::account(who).map(|data| data.free) {
+			Some(free) if free.checked_add(amount).is_none() => DepositConsequence::Overflow,
+			Some(_) => DepositConsequence::Success,
+			None if amount < T::ExistentialDeposit::get() => DepositConsequence::BelowMinimum,
+			None => DepositConsequence::Success,
+		}
+	}
+
+	fn can_withdraw(who: &T::AccountId, amount: Balance) -> WithdrawConsequence<Balance> {
+		if amount.is_zero() {
+			return WithdrawConsequence::Success
+		}
+
+		if TotalIssuance::<T>::get() < amount {
+			return WithdrawConsequence::Underflow
+		}
+
+		if Self::is_frozen(who) {
+			return WithdrawConsequence::Frozen
+		}
+
+		let data = match Self::account(who) {
+			Some(data) => data,
+			None => return WithdrawConsequence::BalanceLow,
+		};
+		let remaining = match data.free.checked_sub(amount) {
+			Some(remaining) => remaining,
+			None => return WithdrawConsequence::BalanceLow,
+		};
+
+		if remaining < Self::vesting_locked(who) {
+			WithdrawConsequence::Frozen
+		} else if remaining < T::ExistentialDeposit::get() && data.reserved.is_zero() {
+			WithdrawConsequence::ReducedToZero(remaining)
+		} else {
+			WithdrawConsequence::Success
+		}
+	}
+}
+
+impl<T: Config> fungible::Unbalanced<T::AccountId> for Barrel<T> {
+	fn handle_dust(dust: fungible::Dust<T::AccountId, Self>) {
+		TotalIssuance::<T>::mutate(|t| *t = t.saturating_sub(dust.0));
+	}
+
+	/// Set the free balance of `who` to `amount`.
+	///
+	/// If this leaves the account below the existential deposit, it is reaped right away and its
+	/// dust is handled by [`Config::DustRemoval`], so no dust is ever returned.
+	fn write_balance(
+		who: &T::AccountId,
+		amount: Balance,
+	) -> Result<Option<Balance>, DispatchError> {
+		if Self::account(who).is_some() {
+			Self::try_mutate_account(who, |data| {
+				data.free = amount;
+				Ok(())
+			})?;
+			Self::reap_if_dust(who);
+		} else if !amount.is_zero() {
+			Self::credit(who, amount)?;
+		}
+
+		Ok(None)
+	}
+
+	fn set_total_issuance(amount: Balance) {
+		TotalIssuance::<T>::put(amount);
+	}
+}
+
+impl<T: Config> fungible::Mutate<T::AccountId> for Barrel<T> {
+	fn done_mint_into(who: &T::AccountId, amount: Balance) {
+		Self::deposit_event(Event::Minted { to: who.clone(), amount });
+	}
+
+	fn done_burn_from(who: &T::AccountId, amount: Balance) {
+		Self::deposit_event(Event::Burned { who: who.clone(), amount });
+	}
+
+	fn done_transfer(source: &T::AccountId, dest: &T::AccountId, amount: Balance) {
+		Self::deposit_event(Event::Transferred { from: source.clone(), to: dest.clone(), amount });
+		T::OnTransfer::on_transfer(source, dest, amount);
+	}
+}
+
+impl<T: Config> fungible::InspectHold<T::AccountId> for Barrel<T> {
+	type Reason = T::RuntimeHoldReason;
+
+	fn total_balance_on_hold(who: &T::AccountId) -> Balance {
+		Self::reserved_balance(who)
+	}
+
+	fn reducible_total_balance_on_hold(who: &T::AccountId, _force: Fortitude) -> Balance {
+		Self::reserved_balance(who)
+	}
+
+	fn balance_on_hold(reason: &Self::Reason, who: &T::AccountId) -> Balance {
+		Self::balance_on_hold(*reason, who)
+	}
+
+	fn hold_available(_reason: &Self::Reason, who: &T::AccountId) -> bool {
+		Self::account(who).is_some() && !Self::is_frozen(who)
+	}
+}
+
+impl<T: Config> fungible::UnbalancedHold<T::AccountId> for Barrel<T> {
+	fn set_balance_on_hold(
+		reason: &Self::Reason,
+		who: &T::AccountId,
+		amount: Balance,
+	) -> DispatchResult {
+		ensure!(Self::account(who).is_some(), Error::<T>::NonExistentAccount);
+		let previous = Holds::<T>::get(who, reason);
+
+		// `Holds` is updated first, so that it is up to date for `OnBalanceChanged`.
+		with_storage_layer(|| {
+			if amount.is_zero() {
+				Holds::<T>::remove(who, reason);
+			} else {
+				Holds::<T>::insert(who, reason, amount);
+			}
+			Self::try_mutate_account(who, |data| {
+				data.reserved = data
+					.reserved
+					.saturating_sub(previous)
+					.checked_add(amount)
+					.ok_or(ArithmeticError::Overflow)?;
+				Ok(())
+			})
+		})
+	}
+}
+
+impl<T: Config> fungible::MutateHold<T::AccountId> for Barrel<T> {
+	fn done_hold(reason: &Self::Reason, who: &T::AccountId, amount: Balance) {
+		Self::deposit_event(Event::Reserved { who: who.clone(), reason: *reason, amount });
+	}
+
+	fn done_release(reason: &Self::Reason, who: &T::AccountId, amount: Balance) {
+		Self::deposit_event(Event::Unreserved { who: who.clone(), reason: *reason, amount });
+	}
+
+	fn done_burn_held(reason: &Self::Reason, who: &T::AccountId, amount: Balance) {
+		Self::deposit_event(Event::Slashed { who: who.clone(), reason: *reason, amount });
+	}
+}
@@ -0,0 +1,208 @@
+//! Storage migrations for the currency barrel.
+
+use super::barrel::*;
+use frame::{
+	deps::frame_support::{
+		storage_alias,
+		traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
+	},
+	prelude::*,
+	traits::StoredMap,
+};
+#[cfg(feature = "try-runtime")]
+use frame::{
+	deps::sp_runtime::TryRuntimeError,
+	derive::{Decode, Encode},
+};
+
+/// Version 1: the free balance of each account moves from the standalone `Balances` map of
+/// version 0 into the [`AccountData`] kept in [`Config::AccountStore`].
+pub mod v1 {
+	use super::*;
+
+	mod v0 {
+		use super::*;
+
+		/// The free balance of each account, which existed as long as it had an entry here.
+		#[storage_alias]
+		pub type Balances<T: Config> = StorageMap<
+			Barrel<T>,
+			Blake2_128Concat,
+			<T as frame_system::Config>::AccountId,
+			Balance,
+		>;
+	}
+
+	/// The number of accounts that [`MigrateToAccountData`] is sized for.
+	///
+	/// All accounts are migrated in a single block. Each of them takes about 7 reads and 4
+	/// writes, so that this many take about 0.6 seconds with the database weights of RocksDB.
+	/// Chains with more accounts must migrate them over several blocks instead.
+	pub const MAX_ACCOUNTS: u32 = 1_000;
+
+	/// Move all balances into [`Config::AccountStore`], and initialize [`HoldersCount`].
+	///
+	/// The funds held by each account are summed up into [`AccountData::reserved`], while
+	/// [`Holds`] itself is left untouched. Accounts without a v0 balance are migrated as well, if
+	/// they have funds on hold, vesting schedules or a freeze. Each migrated account gets a
+	/// provider reference in `frame_system`, like any account created by this barrel.
+	///
+	/// There should be at most [`MAX_ACCOUNTS`] of them.
+	pub struct MigrateToAccountData<T>(PhantomData<T>);
+
+	impl<T: Config> MigrateToAccountData<T> {
+		/// All accounts with a v0 balance, holds, vesting schedules or a freeze, each once, and
+		/// the number of keys read to find them.
+		fn accounts() -> (Vec<T::AccountId>, u64) {
+			let mut accounts = v0::Balances::<T>::iter_keys()
+				.chain(Holds::<T>::iter_keys().map(|(who, _)| who))
+				.chain(Vesting::<T>::iter_keys())
+				.chain(FrozenAccounts::<T>::iter_keys())
+				.collect::<Vec<_>>();
+			let keys = accounts.len() as u64;
+			accounts.sort();
+			accounts.dedup();
+			(accounts, keys)
+		}
+
+		/// The sum of the funds that `who` has on hold, and the number of holds read.
+		fn reserved(who: &T::AccountId) -> (Balance, u64) {
+			Holds::<T>::iter_prefix_values(who)
+				.fold((0, 0), |(total, holds), x| (total.saturating_add(x), holds + 1))
+		}
+	}
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToAccountData<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if Barrel::<T>::on_chain_storage_version() != 0 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			let (accounts, keys) = Self::accounts();
+			let migrated = accounts.len() as u64;
+			let mut holds: u64 = 0;
+			for who in accounts {
+				let free = v0::Balances::<T>::take(&who).unwrap_or_default();
+				let (reserved, held) = Self::reserved(&who);
+				holds += held;
+				// an existing account can only fail to be stored if `AccountStore` refuses to
+				// provide for it, in which case there is nothing better to do than to skip it. An
+				// account without any funds is not stored at all.
+				let _ = Barrel::<T>::try_mutate_account(&who, |data| {
+					*data = AccountData { free, reserved };
+					Ok(())
+				});
+			}
+
+			StorageVersion::new(1).put::<Barrel<T>>();
+			// all keys of the four maps are read. Then, per account: the old entry, its holds and
+			// the end of them, its account data, its providers and `HoldersCount` are read, and
+			// the old entry, its account data, its providers and `HoldersCount` are written.
+			T::DbWeight::get()
+				.reads_writes(keys + holds + migrated * 5 + 1, migrated * 4 + 1)
+				.saturating_add(Barrel::<T>::balance_hooks_weight(migrated as u32))
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+			ensure!(Barrel::<T>::on_chain_storage_version() == 0, "can only upgrade from v0");
+			let (accounts, _) = Self::accounts();
+			ensure!(
+				accounts.len() <= MAX_ACCOUNTS as usize,
+				"too many accounts to migrate in a single block"
+			);
+			let accounts = accounts
+				.into_iter()
+				.map(|who| {
+					let free = v0::Balances::<T>::get(&who).unwrap_or_default();
+					let (reserved, _) = Self::reserved(&who);
+					(who, AccountData { free, reserved })
+				})
+				.collect::<Vec<_>>();
+			let issuance = TotalIssuance::<T>::get();
+			Ok((accounts, issuance).encode())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(state: Vec<u8>) -> Result<(), TryRuntimeError> {
+			let (accounts, issuance): (Vec<(T::AccountId, AccountData)>, Balance) =
+				Decode::decode(&mut &state[..])
+					.map_err(|_| "cannot decode the pre-upgrade state")?;
+
+			ensure!(Barrel::<T>::on_chain_storage_version() == 1, "storage version not updated");
+			ensure!(v0::Balances::<T>::iter().next().is_none(), "old balances not removed");
+			ensure!(TotalIssuance::<T>::get() == issuance, "total issuance changed");
+			let holders =
+				accounts.iter().filter(|(_, data)| *data != AccountData::default()).count();
+			ensure!(HoldersCount::<T>::get() == holders as u32, "number of accounts changed");
+			for (who, data) in accounts {
+				ensure!(T::AccountStore::get(&who) == data, "account data changed");
+			}
+			Barrel::<T>::do_try_state()
+		}
+	}
+
+	#[cfg(test)]
+	mod tests {
+		use super::*;
+		use crate::currency::barrel::tests::{HoldReason, Runtime};
+		use frame::testing_prelude::*;
+
+		#[test]
+		fn migrate_to_account_data_works() {
+			TestState::new(Default::default()).execute_with(|| {
+				// given a v0 state where 1 and 2 are endowed, 3 only has funds on hold with a zero
+				// balance, 4 only has funds on hold without any balance, and 5 is frozen without
+				// any funds.
+				StorageVersion::new(0).put::<Barrel<Runtime>>();
+				v0::Balances::<Runtime>::insert(1, 100);
+				v0::Balances::<Runtime>::insert(2, 50);
+				v0::Balances::<Runtime>::insert(3, 0);
+				Holds::<Runtime>::insert(2, HoldReason::ValidatorBond, 20);
+				Holds::<Runtime>::insert(3, HoldReason::ValidatorBond, 30);
+				Holds::<Runtime>::insert(4, HoldReason::ValidatorBond, 40);
+				FrozenAccounts::<Runtime>::insert(5, ());
+				TotalIssuance::<Runtime>::put(240);
+				#[cfg(feature = "try-runtime")]
+				let state = MigrateToAccountData::<Runtime>::pre_upgrade().unwrap();
+
+				// when:
+				MigrateToAccountData::<Runtime>::on_runtime_upgrade();
+
+				// then:
+				#[cfg(feature = "try-runtime")]
+				MigrateToAccountData::<Runtime>::post_upgrade(state).unwrap();
+				assert_eq!(Barrel::<Runtime>::on_chain_storage_version(), 1);
+				assert_eq!(v0::Balances::<Runtime>::iter().count(), 0);
+				assert_eq!(
+					Barrel::<Runtime>::account(&1),
+					Some(AccountData { free: 100, reserved: 0 })
+				);
+				assert_eq!(
+					Barrel::<Runtime>::account(&2),
+					Some(AccountData { free: 50, reserved: 20 })
+				);
+				assert_eq!(
+					Barrel::<Runtime>::account(&3),
+					Some(AccountData { free: 0, reserved: 30 })
+				);
+				assert_eq!(
+					Barrel::<Runtime>::account(&4),
+					Some(AccountData { free: 0, reserved: 40 })
+				);
+				assert_eq!(Barrel::<Runtime>::account(&5), None);
+				assert_eq!(Barrel::<Runtime>::holders_count(), 4);
+				for who in 1..=4 {
+					assert_eq!(frame_system::Barrel::<Runtime>::providers(&who), 1);
+				}
+				assert_eq!(frame_system::Barrel::<Runtime>::providers(&5), 0);
+				Barrel::<Runtime>::do_try_state().unwrap();
+
+				// and running it again is a no-op.
+				v0::Balances::<Runtime>::insert(6, 100);
+				MigrateToAccountData::<Runtime>::on_runtime_upgrade();
+				assert_eq!(Barrel::<Runtime>::account(&6), None);
+			});
+		}
+	}
+}
@@ -0,0 +1,27 @@
+//! Runtime API of the currency barrel.
+//!
+//! This allows clients to read balances without knowing the storage layout of the barrel.
+
+use super::Balance;
+use parity_scale_codec::Codec;
+
+frame::runtime::apis::decl_runtime_apis! {
+	/// Read access to the balances and issuance of the currency barrel.
+	pub trait CurrencyApi<AccountId> where AccountId: Codec {
+		/// The free balance of `who`.
+		fn balance_of(who: AccountId) -> Balance;
+
+		/// The total issuance of the currency.
+		fn total_issuance() -> Balance;
+
+		/// The number of accounts that exist in the currency.
+		fn holders_count() -> u32;
+
+		/// The total issuance after `eras` more eras of inflation, assuming that all of it is
+		/// minted.
+		///
+		/// Returns `None` if `eras` is more than [`super::MAX_PROJECTED_ERAS`].
+		#[api_version(2)]
+		fn projected_issuance(eras: u32) -> Option<Balance>;
+	}
+}
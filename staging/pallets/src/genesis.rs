@@ -0,0 +1,355 @@
+//! Validation of the genesis configuration of the tutorial barrels, as a whole.
+//!
+//! The genesis of each barrel is built on its own, and panics on the first problem that it runs
+//! into. The staking genesis even relies on the currency genesis having been built before it, to
+//! fund its stakers. [`validate`] looks at both of them together, before anything is built, and
+//! reports every problem at once.
+
+use crate::{currency, staking};
+use core::fmt;
+use frame::{prelude::*, traits::Zero};
+
+/// A problem with the genesis configuration of a single account.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Problem<AccountId> {
+	/// The account at fault.
+	pub who: AccountId,
+	/// What is wrong with it.
+	pub reason: Reason<AccountId>,
+}
+
+/// What can be wrong with the genesis configuration of an account.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum Reason<AccountId> {
+	/// The account is endowed more than once.
+	DuplicateBalance,
+	/// The account is endowed with less than the existential deposit.
+	BelowExistentialDeposit,
+	/// A vesting schedule of the account would never unlock its funds.
+	InvalidVestingSchedule,
+	/// A vesting schedule of the account locks more than its free balance.
+	VestingNotFunded,
+	/// The account has more vesting schedules than the currency barrel allows.
+	TooManyVestingSchedules,
+	/// The account is registered as a validator more than once.
+	DuplicateValidator,
+	/// The account is registered as a validator without any own stake.
+	NoOwnStake,
+	/// The account is registered as a validator after the staking barrel reached its maximum
+	/// number of validators.
+	TooManyValidators,
+	/// The account delegates to `target` more than once.
+	DuplicateDelegation { target: AccountId },
+	/// The account delegates nothing to `target`.
+	NoDelegatedStake { target: AccountId },
+	/// The validator delegates to itself.
+	SelfDelegation,
+	/// The account delegates to more validators than the staking barrel allows.
+	TooManyDelegations,
+	/// The account delegates after the staking barrel reached its maximum number of delegators.
+	TooManyDelegators,
+	/// The account delegates to `target`, which is not registered as a validator.
+	NotRegistered { target: AccountId },
+	/// The account puts more at stake than it can, while staying alive and respecting its
+	/// vesting schedules.
+	InsufficientFunds { stake: currency::Balance, stakeable: currency::Balance },
+}
+
+impl<AccountId: fmt::Display> fmt::Display for Problem<AccountId> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{}: {}", self.who, self.reason)
+	}
+}
+
+impl<AccountId: fmt::Display> fmt::Display for Reason<AccountId> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Reason::DuplicateBalance => write!(f, "endowed more than once"),
+			Reason::BelowExistentialDeposit =>
+				write!(f, "endowed with less than the existential deposit"),
+			Reason::InvalidVestingSchedule => write!(f, "vesting schedule never unlocks its funds"),
+			Reason::VestingNotFunded => write!(f, "vesting schedule locks more than the balance"),
+			Reason::TooManyVestingSchedules => write!(f, "too many vesting schedules"),
+			Reason::DuplicateValidator => write!(f, "registered as a validator more than once"),
+			Reason::NoOwnStake => write!(f, "registered as a validator without any stake"),
+			Reason::TooManyValidators => write!(f, "registered beyond the maximum of validators"),
+			Reason::DuplicateDelegation { target } =>
+				write!(f, "delegates to {} more than once", target),
+			Reason::NoDelegatedStake { target } => write!(f, "delegates nothing to {}", target),
+			Reason::SelfDelegation => write!(f, "delegates to itself"),
+			Reason::TooManyDelegations => write!(f, "delegates to too many validators"),
+			Reason::TooManyDelegators => write!(f, "delegates beyond the maximum of delegators"),
+			Reason::NotRegistered { target } =>
+				write!(f, "delegates to {}, which is not a validator", target),
+			Reason::InsufficientFunds { stake, stakeable } =>
+				write!(f, "stakes {}, but only {} can be staked", stake, stakeable),
+		}
+	}
+}
+
+/// Check that the genesis of the currency and staking barrels can be built.
+///
+/// This is the case if no account is endowed, registered as a validator or delegating to the same
+/// validator more than once, every validator has some own stake, every delegation has some stake
+/// and targets another, registered validator, the maximum numbers of validators, delegators and
+/// delegations per delegator are respected, and every staker is funded by the currency genesis.
+/// Otherwise, all problems are returned, in the order of the configuration.
+pub fn validate<T>(
+	currency: &currency::GenesisConfig<T>,
+	staking: &staking::GenesisConfig<T>,
+) -> Result<(), Vec<Problem<T::AccountId>>>
+where
+	T: currency::Config + staking::Config<Currency = currency::Barrel<T>>,
+{
+	let mut problems = Vec::new();
+	let mut report =
+		|who: &T::AccountId, reason| problems.push(Problem { who: who.clone(), reason });
+
+	let mut endowed: Vec<(&T::AccountId, currency::Balance)> = Vec::new();
+	for (who, amount) in &currency.balances {
+		if endowed.iter().any(|(w, _)| *w == who) {
+			report(who, Reason::DuplicateBalance);
+			continue
+		}
+		if *amount < T::ExistentialDeposit::get() {
+			report(who, Reason::BelowExistentialDeposit);
+		}
+		endowed.push((who, *amount));
+	}
+	let free_of = |who: &T::AccountId| {
+		endowed.iter().find(|(w, _)| *w == who).map_or(0, |(_, amount)| *amount)
+	};
+
+	// vesting schedules are added one by one, and none of them has unlocked anything at genesis.
+	let mut vesting: Vec<(&T::AccountId, u32, currency::Balance)> = Vec::new();
+	for (who, locked, per_block, starting_block) in &currency.vesting {
+		let schedule = currency::VestingSchedule {
+			locked: *locked,
+			per_block: *per_block,
+			starting_block: *starting_block,
+		};
+		if !schedule.is_valid() {
+			report(who, Reason::InvalidVestingSchedule);
+		}
+		if free_of(who) < *locked {
+			report(who, Reason::VestingNotFunded);
+		}
+		match vesting.iter_mut().find(|(w, _, _)| *w == who) {
+			Some((_, count, total)) => {
+				*count += 1;
+				*total = total.saturating_add(*locked);
+				if *count == T::MaxVestingSchedules::get() + 1 {
+					report(who, Reason::TooManyVestingSchedules);
+				}
+			},
+			None => vesting.push((who, 1, *locked)),
+		}
+	}
+
+	// all validators are registered before any delegation is made.
+	let mut validators: Vec<&T::AccountId> = Vec::new();
+	let mut stakes: Vec<(&T::AccountId, currency::Balance)> = Vec::new();
+	for (who, stake) in &staking.validators {
+		if validators.contains(&who) {
+			report(who, Reason::DuplicateValidator);
+			continue
+		}
+		if stake.is_zero() {
+			report(who, Reason::NoOwnStake);
+			continue
+		}
+		if validators.len() as u32 == T::MaxValidators::get() {
+			report(who, Reason::TooManyValidators);
+			continue
+		}
+		validators.push(who);
+		add_to(&mut stakes, who, *stake);
+	}
+
+	let mut delegators: Vec<&T::AccountId> = Vec::new();
+	let mut delegations: Vec<(&T::AccountId, &T::AccountId)> = Vec::new();
+	for (who, target, stake) in &staking.delegators {
+		if delegations.contains(&(who, target)) {
+			report(who, Reason::DuplicateDelegation { target: target.clone() });
+			continue
+		}
+		if stake.is_zero() {
+			report(who, Reason::NoDelegatedStake { target: target.clone() });
+			continue
+		}
+		if who == target {
+			report(who, Reason::SelfDelegation);
+			continue
+		}
+		if !validators.contains(&target) {
+			report(who, Reason::NotRegistered { target: target.clone() });
+			continue
+		}
+		let count = delegations.iter().filter(|(w, _)| *w == who).count() as u32;
+		if count == T::MaxDelegations::get() {
+			report(who, Reason::TooManyDelegations);
+			continue
+		}
+		if count == 0 && delegators.len() as u32 == T::MaxDelegators::get() {
+			report(who, Reason::TooManyDelegators);
+			continue
+		}
+		if count == 0 {
+			delegators.push(who);
+		}
+		delegations.push((who, target));
+		add_to(&mut stakes, who, *stake);
+	}
+
+	for (who, stake) in stakes {
+		let locked = vesting.iter().find(|(w, _, _)| *w == who).map_or(0, |(_, _, l)| *l);
+		let stakeable = free_of(who).saturating_sub(locked.max(T::ExistentialDeposit::get()));
+		if stake > stakeable {
+			report(who, Reason::InsufficientFunds { stake, stakeable });
+		}
+	}
+
+	if problems.is_empty() {
+		Ok(())
+	} else {
+		Err(problems)
+	}
+}
+
+/// Add `amount` to the total of `who` in `totals`.
+fn add_to<'a, AccountId: PartialEq>(
+	totals: &mut Vec<(&'a AccountId, currency::Balance)>,
+	who: &'a AccountId,
+	amount: currency::Balance,
+) {
+	match totals.iter_mut().find(|(w, _)| *w == who) {
+		Some((_, total)) => *total = total.saturating_add(amount),
+		None => totals.push((who, amount)),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::staking::barrel::tests::with_currency::{
+		genesis, MaxDelegators, MaxValidators, Runtime, RuntimeGenesisConfig,
+	};
+	use frame::testing_prelude::*;
+
+	/// Validate `config`, and assert that it is valid if and only if it can be built.
+	fn validate_and_build(config: RuntimeGenesisConfig) -> Result<(), Vec<Problem<u64>>> {
+		let result = validate::<Runtime>(&config.currency, &config.staking);
+		let built =
+			std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| config.build_storage()))
+				.map_or(false, |storage| storage.is_ok());
+		assert_eq!(result.is_ok(), built);
+		result
+	}
+
+	#[test]
+	fn valid_genesis_is_accepted_and_builds() {
+		// given 1 and 2 are validators, 3 delegates to both of them, and 2 stakes all it can next
+		// to its vesting schedule.
+		let mut config = genesis(
+			vec![(1, 20), (2, 30), (3, 15)],
+			vec![(1, 10), (2, 10)],
+			vec![(3, 1, 7), (3, 2, 7)],
+		);
+		config.currency.vesting = vec![(2, 20, 1, 0)];
+
+		// when, then:
+		assert_eq!(validate::<Runtime>(&config.currency, &config.staking), Ok(()));
+		assert!(config.build_storage().is_ok());
+	}
+
+	#[test]
+	fn every_problem_is_reported() {
+		// given:
+		let mut config = genesis(
+			vec![(1, 20), (1, 20), (2, 0), (3, 10), (4, 50)],
+			vec![(1, 10), (1, 5), (3, 10), (6, 0)],
+			vec![(4, 1, 10), (4, 1, 10), (5, 2, 10)],
+		);
+		config.currency.vesting = vec![(4, 60, 1, 0), (4, 10, 0, 0)];
+
+		// when:
+		let problems = validate::<Runtime>(&config.currency, &config.staking).unwrap_err();
+
+		// then:
+		let problem = |who, reason| Problem { who, reason };
+		assert_eq!(
+			problems,
+			vec![
+				problem(1, Reason::DuplicateBalance),
+				problem(2, Reason::BelowExistentialDeposit),
+				problem(4, Reason::VestingNotFunded),
+				problem(4, Reason::InvalidVestingSchedule),
+				problem(4, Reason::TooManyVestingSchedules),
+				problem(1, Reason::DuplicateValidator),
+				problem(6, Reason::NoOwnStake),
+				problem(4, Reason::DuplicateDelegation { target: 1 }),
+				problem(5, Reason::NotRegistered { target: 2 }),
+				problem(3, Reason::InsufficientFunds { stake: 10, stakeable: 9 }),
+				problem(4, Reason::InsufficientFunds { stake: 10, stakeable: 0 }),
+			]
+		);
+		assert_eq!(problems[9].to_string(), "3: stakes 10, but only 9 can be staked".to_string());
+	}
+
+	#[test]
+	fn too_many_validators_are_reported() {
+		// given at most 2 validators.
+		MaxValidators::set(2);
+		let config = |validators| genesis(vec![(1, 20), (2, 20), (3, 20)], validators, vec![]);
+
+		// when, then:
+		assert_eq!(validate_and_build(config(vec![(1, 10), (2, 10)])), Ok(()));
+		assert_eq!(
+			validate_and_build(config(vec![(1, 10), (2, 10), (3, 10)])),
+			Err(vec![Problem { who: 3, reason: Reason::TooManyValidators }])
+		);
+	}
+
+	#[test]
+	fn too_many_delegators_are_reported() {
+		// given at most 1 delegator, and 1 and 2 are validators.
+		MaxDelegators::set(1);
+		let config = |delegators| {
+			genesis(vec![(1, 20), (2, 20), (3, 20), (4, 20)], vec![(1, 10), (2, 10)], delegators)
+		};
+
+		// when, then 3 can delegate to both, but 4 cannot delegate at all.
+		assert_eq!(validate_and_build(config(vec![(3, 1, 5), (3, 2, 5)])), Ok(()));
+		assert_eq!(
+			validate_and_build(config(vec![(3, 1, 5), (4, 1, 5), (3, 2, 5)])),
+			Err(vec![Problem { who: 4, reason: Reason::TooManyDelegators }])
+		);
+	}
+
+	#[test]
+	fn zero_delegations_are_reported() {
+		// given:
+		let config = |stake| genesis(vec![(1, 20), (3, 20)], vec![(1, 10)], vec![(3, 1, stake)]);
+
+		// when, then:
+		assert_eq!(validate_and_build(config(5)), Ok(()));
+		assert_eq!(
+			validate_and_build(config(0)),
+			Err(vec![Problem { who: 3, reason: Reason::NoDelegatedStake { target: 1 } }])
+		);
+	}
+
+	#[test]
+	fn self_delegations_are_reported() {
+		// given 1 and 2 are validators with some more funds.
+		let config =
+			|target| genesis(vec![(1, 30), (2, 30)], vec![(1, 10), (2, 10)], vec![(1, target, 5)]);
+
+		// when, then 1 can delegate to 2, but not to itself.
+		assert_eq!(validate_and_build(config(2)), Ok(()));
+		assert_eq!(
+			validate_and_build(config(1)),
+			Err(vec![Problem { who: 1, reason: Reason::SelfDelegation }])
+		);
+	}
+}
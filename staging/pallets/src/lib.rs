@@ -1,4 +1,6 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
+pub mod assets;
 pub mod currency;
+pub mod genesis;
 pub mod staking;
@@ -1,42 +1,485 @@
 pub use barrel::*;
+pub use weights::WeightInfo;
 
-#[frame::barrel(dev_mode)]
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+pub mod migrations;
+pub mod weights;
+
+#[frame::barrel]
 pub mod barrel {
-	use crate::currency::barrel::{self as barrel_currency, Balance};
+	use super::WeightInfo;
+	use crate::currency;
 	use frame::{
-		derive::{Decode, DefaultNoBound, Encode, TypeInfo},
+		deps::sp_runtime::Perbill,
+		derive::{Decode, DefaultNoBound, Encode, MaxEncodedLen, TypeInfo},
 		prelude::*,
+		traits::{
+			fungible::{Inspect, InspectHold, Mutate, MutateHold},
+			tokens::{Fortitude, Precision, Preservation, Restriction},
+			Saturating, Zero,
+		},
 	};
 
+	pub type BalanceOf<T> = <<T as Config>::Currency as Inspect<AccountIdOf<T>>>::Balance;
+	type AccountIdOf<T> = <T as frame_system::Config>::AccountId;
+
+	/// The index of an era.
+	pub type EraIndex = u32;
+
+	/// Something that handles the funds slashed from stakers.
+	pub trait OnSlash<T: Config> {
+		/// Slash `amount` held by `who` for `reason`.
+		///
+		/// Returns the part of `amount` that was actually taken from `who`.
+		fn on_slash(who: &T::AccountId, reason: HoldReason, amount: BalanceOf<T>) -> BalanceOf<T>;
+	}
+
+	/// Burn all slashed funds.
+	impl<T: Config> OnSlash<T> for () {
+		fn on_slash(who: &T::AccountId, reason: HoldReason, amount: BalanceOf<T>) -> BalanceOf<T> {
+			T::Currency::burn_held(
+				&reason.into(),
+				who,
+				amount,
+				Precision::BestEffort,
+				Fortitude::Force,
+			)
+			.unwrap_or_else(|_| Zero::zero())
+		}
+	}
+
+	/// An [`OnSlash`] implementation that moves all slashed funds into the account returned by
+	/// `A`, such as a treasury or the reward pot.
+	///
+	/// If the funds cannot be moved into `A`, they are burned.
+	pub struct SlashTo<A>(PhantomData<A>);
+	impl<T: Config, A: Get<T::AccountId>> OnSlash<T> for SlashTo<A> {
+		fn on_slash(who: &T::AccountId, reason: HoldReason, amount: BalanceOf<T>) -> BalanceOf<T> {
+			T::Currency::transfer_on_hold(
+				&reason.into(),
+				who,
+				&A::get(),
+				amount,
+				Precision::BestEffort,
+				Restriction::Free,
+				Fortitude::Force,
+			)
+			.unwrap_or_else(|_| <() as OnSlash<T>>::on_slash(who, reason, amount))
+		}
+	}
+
+	/// The in-code storage version.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(2);
+
 	#[barrel::config]
-	pub trait Config: frame_system::Config + barrel_currency::Config {
+	pub trait Config: frame_system::Config {
+		/// The overarching event type.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+		/// The currency in which stake is held.
+		///
+		/// This can be the tutorial `currency` barrel, `barrel_balances`, a single asset of the
+		/// `assets` barrel through `fungible::ItemOf`, or any other type that implements the
+		/// `fungible` traits.
+		type Currency: Mutate<Self::AccountId>
+			+ MutateHold<Self::AccountId, Reason = Self::RuntimeHoldReason>;
+
+		/// The overarching hold reason.
+		type RuntimeHoldReason: From<HoldReason>;
+
+		/// The maximum number of validators that are selected at the start of each era.
+		#[barrel::constant]
 		type ValidatorCount: Get<u32>;
 		type EraDuration: Get<BlockNumberFor<Self>>;
+
+		/// The number of eras that unbonded funds remain held for, before they can be withdrawn.
+		///
+		/// It must be longer than [`Config::SlashDeferDuration`], so that funds unbonded right
+		/// after an offence are still held when its slash is applied.
+		#[barrel::constant]
+		type BondingDuration: Get<EraIndex>;
+
+		/// The maximum number of [`UnbondingChunk`]s of an account, each of which can be withdrawn
+		/// in a different era.
+		#[barrel::constant]
+		type MaxUnbondingChunks: Get<u32>;
+
+		/// The maximum number of validators that an account can delegate to at once.
+		#[barrel::constant]
+		type MaxDelegations: Get<u32>;
+
+		/// The maximum number of accounts in [`Validators`], including those that unregistered
+		/// but are still backed by delegations.
+		///
+		/// All of them are looked at when the validators of an era are selected.
+		#[barrel::constant]
+		type MaxValidators: Get<u32>;
+
+		/// The maximum number of delegators.
+		///
+		/// All of their delegations, at most [`Config::MaxDelegations`] each, are looked at when
+		/// the validators of an era are selected.
+		#[barrel::constant]
+		type MaxDelegators: Get<u32>;
+
+		/// The account from which rewards are paid.
+		///
+		/// When an era ends, whatever this account holds beyond the unclaimed rewards of earlier
+		/// eras becomes the reward of that era. It can be funded by the inflation of the tutorial
+		/// currency, through [`currency::InflationTo`].
+		type RewardPot: Get<Self::AccountId>;
+
+		/// The number of eras, after an era ends, during which its rewards can be claimed.
+		///
+		/// Rewards that are not claimed by then go back to the reward pot.
+		#[barrel::constant]
+		type HistoryDepth: Get<EraIndex>;
+
+		/// The maximum number of delegators of a validator that are paid by a single
+		/// [`Barrel::payout_era`].
+		#[barrel::constant]
+		type MaxExposurePageSize: Get<u32>;
+
+		/// The maximum amount by which a validator can change its commission, once per era.
+		#[barrel::constant]
+		type MaxCommissionChange: Get<Perbill>;
+
+		/// The number of eras after an offence at which its slash is applied, during which
+		/// [`Config::AdminOrigin`] can still cancel it.
+		///
+		/// It must be at least 1, at most [`Config::HistoryDepth`], so that the stake behind the
+		/// offender is still known when the slash is applied, and shorter than
+		/// [`Config::BondingDuration`].
+		#[barrel::constant]
+		type SlashDeferDuration: Get<EraIndex>;
+
+		/// The origin that is allowed to cancel deferred slashes, via
+		/// [`Barrel::cancel_deferred_slash`].
+		type AdminOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+		/// What happens to the funds slashed from stakers.
+		type OnSlash: OnSlash<Self>;
+
+		/// Weight information for the calls and hooks of this barrel.
+		type WeightInfo: WeightInfo;
 	}
 
 	#[barrel::barrel]
+	#[barrel::storage_version(STORAGE_VERSION)]
 	pub struct Barrel<T>(_);
 
-	#[derive(Encode, Decode, TypeInfo, Eq, PartialEq, Clone, Debug)]
-	pub struct ValidatorStake {
+	/// The reasons for which this barrel holds funds.
+	#[barrel::composite_enum]
+	pub enum HoldReason {
+		/// Funds bonded by a validator as their own stake.
+		ValidatorBond,
+		/// Funds delegated to a validator.
+		Delegation,
+		/// Funds that are being unbonded, until they can be withdrawn.
+		Unbonding,
+	}
+
+	#[derive(Encode, Decode, MaxEncodedLen, TypeInfo, Eq, PartialEq, Clone, Debug)]
+	pub struct ValidatorStake<Balance> {
 		pub(crate) own: Balance,
 		pub(crate) delegated: Balance,
 	}
 
+	/// The commission of a validator.
+	#[derive(Encode, Decode, MaxEncodedLen, TypeInfo, Eq, PartialEq, Clone, Debug)]
+	pub struct ValidatorCommission {
+		/// The part of the rewards of the validator that it keeps, before the rest is shared with
+		/// its delegators.
+		pub rate: Perbill,
+		/// The era in which it was last changed.
+		pub changed_in: EraIndex,
+	}
+
+	/// The stake behind a validator in an era, as it was when the era started.
+	///
+	/// The stake of its delegators is kept in [`ErasStakersPaged`], in `page_count` pages.
+	#[derive(Encode, Decode, MaxEncodedLen, TypeInfo, Eq, PartialEq, Clone, Debug)]
+	pub struct ExposureOverview<Balance> {
+		/// The total stake of the validator and its delegators.
+		pub total: Balance,
+		/// The own stake of the validator.
+		pub own: Balance,
+		/// The commission of the validator.
+		pub commission: Perbill,
+		/// The number of pages of delegators, at least one.
+		pub page_count: u32,
+	}
+
+	/// The rewards of an era.
+	#[derive(Encode, Decode, MaxEncodedLen, TypeInfo, Eq, PartialEq, Clone, Debug)]
+	pub struct EraReward<Balance> {
+		/// The total stake of all validators that were selected for the era.
+		pub total_stake: Balance,
+		/// The reward shared by all of them, which is only known once the era ends.
+		pub reward: Balance,
+		/// The part of `reward` that was paid so far.
+		pub paid: Balance,
+	}
+
+	/// The maps of the exposures of an era, in the order in which they are removed once the era
+	/// expires.
+	#[derive(Encode, Decode, MaxEncodedLen, TypeInfo, Eq, PartialEq, Clone, Copy, Debug)]
+	pub enum ExposureMap {
+		/// [`ErasStakersOverview`].
+		Overview,
+		/// [`ErasStakersPaged`].
+		Paged,
+		/// [`ClaimedPages`].
+		ClaimedPages,
+	}
+
+	/// How far the exposures of expired eras were removed.
+	#[derive(Encode, Decode, MaxEncodedLen, TypeInfo, Eq, PartialEq, Clone, Debug)]
+	pub struct CleanupCursor {
+		/// The oldest era whose exposures are not all removed yet.
+		pub era: EraIndex,
+		/// The map of `era` whose entries are removed next.
+		pub map: ExposureMap,
+		/// Where the removal of the entries of `map` continues, if it was started.
+		pub cursor: Option<BoundedVec<u8, ConstU32<256>>>,
+	}
+
+	/// Funds that are being unbonded, and can be withdrawn from era `era` on.
+	#[derive(Encode, Decode, MaxEncodedLen, TypeInfo, Eq, PartialEq, Clone, Debug)]
+	pub struct UnbondingChunk<Balance> {
+		/// The amount that is being unbonded.
+		pub amount: Balance,
+		/// The first era in which it can be withdrawn.
+		pub era: EraIndex,
+	}
+
+	/// The validators, with their stake.
+	///
+	/// A validator that unregistered while still backed by delegations is kept here without any
+	/// own stake, until all of them are undelegated. It cannot be elected or delegated to, unless
+	/// it registers again.
+	///
+	/// There are at most [`Config::MaxValidators`] of them.
+	#[barrel::storage]
+	pub type Validators<T: Config> =
+		CountedStorageMap<_, Blake2_128Concat, T::AccountId, ValidatorStake<BalanceOf<T>>>;
+
+	/// The total stake of each delegator, across all of its [`Delegations`].
+	///
+	/// There are at most [`Config::MaxDelegators`] of them.
+	#[barrel::storage]
+	pub type Delegators<T: Config> =
+		CountedStorageMap<_, Blake2_128Concat, T::AccountId, BalanceOf<T>>;
+
+	/// The stake that each delegator delegates to each validator, keyed by delegator first.
+	///
+	/// A delegator has at most [`Config::MaxDelegations`] entries.
+	#[barrel::storage]
+	pub type Delegations<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		T::AccountId,
+		BalanceOf<T>,
+	>;
+
+	/// The validators selected at the start of the current era, at most [`Config::ValidatorCount`].
+	///
+	/// Validators that unregister during the era are removed right away.
+	#[barrel::storage]
+	pub type ActiveValidators<T: Config> =
+		StorageValue<_, BoundedVec<T::AccountId, T::ValidatorCount>, ValueQuery>;
+
+	/// The index of the current era, incremented each time validators are selected.
+	#[barrel::storage]
+	pub type CurrentEra<T: Config> = StorageValue<_, EraIndex, ValueQuery>;
+
+	/// The commission of each validator that ever set one.
+	#[barrel::storage]
+	pub type Commissions<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, ValidatorCommission>;
+
+	/// The rewards of each era that are kept, from the start of the era until
+	/// [`Config::HistoryDepth`] eras after it ended.
+	#[barrel::storage]
+	pub type ErasRewards<T: Config> =
+		StorageMap<_, Twox64Concat, EraIndex, EraReward<BalanceOf<T>>>;
+
+	/// The stake behind each validator selected for an era, kept as long as [`ErasRewards`].
+	#[barrel::storage]
+	pub type ErasStakersOverview<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		EraIndex,
+		Blake2_128Concat,
+		T::AccountId,
+		ExposureOverview<BalanceOf<T>>,
+	>;
+
+	/// The stake of the delegators behind each validator selected for an era, in pages of at most
+	/// [`Config::MaxExposurePageSize`].
+	#[barrel::storage]
+	pub type ErasStakersPaged<T: Config> = StorageNMap<
+		_,
+		(
+			NMapKey<Twox64Concat, EraIndex>,
+			NMapKey<Blake2_128Concat, T::AccountId>,
+			NMapKey<Twox64Concat, u32>,
+		),
+		BoundedVec<(T::AccountId, BalanceOf<T>), T::MaxExposurePageSize>,
+	>;
+
+	/// The number of pages of each validator in [`ErasStakersPaged`] that were paid out so far.
+	#[barrel::storage]
+	pub type ClaimedPages<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		EraIndex,
+		Blake2_128Concat,
+		T::AccountId,
+		u32,
+		ValueQuery,
+	>;
+
+	/// The removal of the exposures of expired eras that is still in progress, if any.
+	///
+	/// At most [`Config::MaxExposurePageSize`] entries are removed per block, so that it can span
+	/// several blocks. All eras from [`CleanupCursor::era`] to the last expired one are left.
 	#[barrel::storage]
-	pub type Validators<T: Config> = StorageMap<_, _, T::AccountId, ValidatorStake>;
+	pub type ExposureCleanup<T: Config> = StorageValue<_, CleanupCursor>;
 
+	/// The total of the rewards of all eras in [`ErasRewards`] that were not paid yet.
+	///
+	/// This is kept in the reward pot for them, and not part of the reward of the next era.
 	#[barrel::storage]
-	pub type Delegators<T: Config> = StorageMap<_, _, T::AccountId, Balance>;
+	pub type UnclaimedRewards<T: Config> = StorageValue<_, BalanceOf<T>, ValueQuery>;
 
+	/// The slashes that are not applied yet, keyed by the era of the offence and the offender,
+	/// with the fraction of the stake behind the offender that is slashed.
+	///
+	/// They are applied as era `era + SlashDeferDuration` starts, unless cancelled before.
 	#[barrel::storage]
-	pub type ActiveValidators<T: Config> = StorageValue<_, Vec<T::AccountId>, ValueQuery>;
+	pub type UnappliedSlashes<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, EraIndex, Blake2_128Concat, T::AccountId, Perbill>;
+
+	/// The validators that committed an offence, with the era in which they last did.
+	///
+	/// They are not selected, and cannot be delegated to, until they register again.
+	#[barrel::storage]
+	pub type Chilled<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, EraIndex>;
+
+	/// The funds of each account that are being unbonded, in the order in which they can be
+	/// withdrawn.
+	#[barrel::storage]
+	pub type Unbonding<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		BoundedVec<UnbondingChunk<BalanceOf<T>>, T::MaxUnbondingChunks>,
+		ValueQuery,
+	>;
+
+	#[barrel::event]
+	#[barrel::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// `who` registered as a validator, with `amount` of own stake.
+		ValidatorRegistered { who: T::AccountId, amount: BalanceOf<T> },
+		/// `who` unregistered as a validator.
+		ValidatorUnregistered { who: T::AccountId },
+		/// `who` delegated `amount` to the validator `to`.
+		Delegated { who: T::AccountId, to: T::AccountId, amount: BalanceOf<T> },
+		/// `who` undelegated `amount` from the validator `from`.
+		Undelegated { who: T::AccountId, from: T::AccountId, amount: BalanceOf<T> },
+		/// `who` moved `amount` of its delegation from the validator `from` to the validator `to`.
+		Redelegated {
+			who: T::AccountId,
+			from: T::AccountId,
+			to: T::AccountId,
+			amount: BalanceOf<T>,
+		},
+		/// `amount` of the stake of `who` started unbonding, and can be withdrawn from `era` on.
+		Unbonded { who: T::AccountId, amount: BalanceOf<T>, era: EraIndex },
+		/// `who` withdrew `amount` of unbonded funds.
+		Withdrawn { who: T::AccountId, amount: BalanceOf<T> },
+		/// `validators` were selected for era `era`.
+		NewValidatorSet { era: EraIndex, validators: Vec<T::AccountId> },
+		/// Era `era` ended, and its validators and delegators can claim `reward` between them.
+		EraPaid { era: EraIndex, reward: BalanceOf<T> },
+		/// `who` was paid `amount` as its reward for era `era`.
+		Rewarded { era: EraIndex, who: T::AccountId, amount: BalanceOf<T> },
+		/// The validator `who` changed its commission to `commission`.
+		CommissionSet { who: T::AccountId, commission: Perbill },
+		/// The validator `validator` committed an offence in era `era`, for which `fraction` of
+		/// the stake behind it is slashed.
+		OffenceReported { validator: T::AccountId, era: EraIndex, fraction: Perbill },
+		/// The slash of `validator` for its offence in era `era` was cancelled.
+		SlashCancelled { validator: T::AccountId, era: EraIndex },
+		/// `amount` of the stake of `who` was slashed, for an offence of `validator`.
+		Slashed { who: T::AccountId, validator: T::AccountId, amount: BalanceOf<T> },
+	}
+
+	#[barrel::error]
+	pub enum Error<T> {
+		/// The account is already a registered validator.
+		AlreadyRegistered,
+		/// A validator must register with some own stake.
+		NoOwnStake,
+		/// The account cannot put this much at stake, while staying alive.
+		InsufficientFunds,
+		/// The account already delegates to this validator.
+		AlreadyDelegating,
+		/// The account is not a registered validator.
+		NotRegistered,
+		/// The account does not delegate to this validator.
+		NotDelegator,
+		/// The account does not delegate this much to this validator.
+		InsufficientStake,
+		/// Some stake must be delegated.
+		NoStake,
+		/// The account already delegates to the maximum number of validators.
+		TooManyDelegations,
+		/// The maximum number of validators is already registered.
+		TooManyValidators,
+		/// The maximum number of delegators already delegate.
+		TooManyDelegators,
+		/// A delegation cannot be moved to the validator that it is delegated to.
+		SameValidator,
+		/// A validator cannot delegate to itself.
+		SelfDelegation,
+		/// A delegation cannot be moved away from a validator with a slash that is not applied
+		/// yet.
+		PendingSlash,
+		/// The account has no unbonded funds that can be withdrawn yet.
+		NothingToWithdraw,
+		/// The account already has the maximum number of unbonding chunks.
+		TooManyUnbondingChunks,
+		/// The era has not ended yet.
+		EraNotEnded,
+		/// The rewards of the era expired.
+		EraExpired,
+		/// The validator was not selected for the era.
+		NotExposed,
+		/// All of the rewards of the validator for the era were already paid.
+		AlreadyPaid,
+		/// The commission was already changed in this era.
+		CommissionAlreadyChanged,
+		/// The commission cannot change by more than [`Config::MaxCommissionChange`] at once.
+		CommissionChangeTooLarge,
+		/// The validator was chilled for an offence.
+		ValidatorChilled,
+		/// There is no slash of the validator for the era that is not applied yet.
+		NoUnappliedSlash,
+	}
 
 	#[derive(DefaultNoBound)]
 	#[barrel::genesis_config]
 	pub struct GenesisConfig<T: Config> {
-		validators: Vec<(T::AccountId, Balance)>,
-		delegators: Vec<(T::AccountId, T::AccountId, Balance)>,
+		/// The validators to register, with their own stake.
+		pub validators: Vec<(T::AccountId, BalanceOf<T>)>,
+		/// The delegations to make, as `(delegator, validator, stake)`.
+		pub delegators: Vec<(T::AccountId, T::AccountId, BalanceOf<T>)>,
 	}
 
 	// TODO:
@@ -62,139 +505,2249 @@ pub mod barrel {
 
 	#[barrel::call]
 	impl<T: Config> Barrel<T> {
-		pub fn register(origin: OriginFor<T>, amount: Balance) -> DispatchResult {
+		#[barrel::call_index(0)]
+		#[barrel::weight(T::WeightInfo::register())]
+		pub fn register(origin: OriginFor<T>, amount: BalanceOf<T>) -> DispatchResult {
 			let who = ensure_signed(origin)?;
 
-			ensure!(!Validators::<T>::contains_key(&who), "AlreadyRegistered");
+			ensure!(!amount.is_zero(), Error::<T>::NoOwnStake);
+			ensure!(!Self::is_registered(&who), Error::<T>::AlreadyRegistered);
+			ensure!(Self::stakeable_balance(&who) >= amount, Error::<T>::InsufficientFunds);
 			ensure!(
-				barrel_currency::Balances::<T>::get(&who).map_or(false, |b| b >= amount),
-				"InsufficientFunds"
+				Validators::<T>::contains_key(&who) ||
+					Validators::<T>::count() < T::MaxValidators::get(),
+				Error::<T>::TooManyValidators
 			);
 
-			Validators::<T>::insert(&who, ValidatorStake { own: amount, delegated: 0 });
+			T::Currency::hold(&HoldReason::ValidatorBond.into(), &who, amount)?;
+			// a validator that registers again keeps the delegations that still back it, and is
+			// no longer chilled.
+			Validators::<T>::mutate(&who, |maybe_stake| {
+				let delegated = maybe_stake.as_ref().map_or(Zero::zero(), |stake| stake.delegated);
+				*maybe_stake = Some(ValidatorStake { own: amount, delegated });
+			});
+			Chilled::<T>::remove(&who);
 
+			Self::deposit_event(Event::ValidatorRegistered { who, amount });
 			Ok(())
 		}
 
-		pub fn delegate(origin: OriginFor<T>, to: T::AccountId, amount: Balance) -> DispatchResult {
+		#[barrel::call_index(1)]
+		#[barrel::weight(T::WeightInfo::delegate())]
+		pub fn delegate(
+			origin: OriginFor<T>,
+			to: T::AccountId,
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
 			let who = ensure_signed(origin)?;
 
-			ensure!(!Delegators::<T>::contains_key(&who), "AlreadyDelegator");
+			ensure!(!Delegations::<T>::contains_key(&who, &to), Error::<T>::AlreadyDelegating);
+			Self::ensure_can_delegate_to_new(&who)?;
+			Self::add_delegation(who, to, amount)
+		}
+
+		/// Stop being a validator, and start unbonding all of the own stake.
+		///
+		/// The validator is removed from [`ActiveValidators`] right away. The stake can be
+		/// withdrawn via [`Barrel::withdraw_unbonded`] after [`Config::BondingDuration`] eras.
+		#[barrel::call_index(2)]
+		#[barrel::weight(T::WeightInfo::unregister())]
+		pub fn unregister(origin: OriginFor<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let stake = Validators::<T>::get(&who)
+				.filter(|stake| !stake.own.is_zero())
+				.ok_or(Error::<T>::NotRegistered)?;
+
+			if stake.delegated.is_zero() {
+				Validators::<T>::remove(&who);
+			} else {
+				Validators::<T>::insert(
+					&who,
+					ValidatorStake { own: Zero::zero(), delegated: stake.delegated },
+				);
+			}
+			ActiveValidators::<T>::mutate(|active| active.retain(|v| *v != who));
+			Commissions::<T>::remove(&who);
+
+			Self::deposit_event(Event::ValidatorUnregistered { who: who.clone() });
+			Self::unbond(&who, HoldReason::ValidatorBond, stake.own)
+		}
+
+		/// Start unbonding `amount` of the caller's delegation to `from`.
+		///
+		/// The delegation is removed once all of it is undelegated, and the caller stops being a
+		/// delegator once all of its delegations are. The stake can be withdrawn via
+		/// [`Barrel::withdraw_unbonded`] after [`Config::BondingDuration`] eras.
+		#[barrel::call_index(3)]
+		#[barrel::weight(T::WeightInfo::undelegate())]
+		pub fn undelegate(
+			origin: OriginFor<T>,
+			from: T::AccountId,
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			Self::remove_delegation(&who, &from, amount)?;
+			Self::reduce_delegated(&from, amount);
+			Self::reduce_total_delegated(&who, amount);
+
+			Self::deposit_event(Event::Undelegated { who: who.clone(), from, amount });
+			Self::unbond(&who, HoldReason::Delegation, amount)
+		}
+
+		/// Withdraw all of the caller's unbonded funds that can be withdrawn in the current era.
+		#[barrel::call_index(4)]
+		#[barrel::weight(T::WeightInfo::withdraw_unbonded())]
+		pub fn withdraw_unbonded(origin: OriginFor<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let current_era = CurrentEra::<T>::get();
+
+			let mut chunks = Unbonding::<T>::get(&who);
+			let withdrawn = chunks
+				.iter()
+				.filter(|chunk| chunk.era <= current_era)
+				.fold(Zero::zero(), |acc: BalanceOf<T>, chunk| acc.saturating_add(chunk.amount));
+			ensure!(!withdrawn.is_zero(), Error::<T>::NothingToWithdraw);
+
+			chunks.retain(|chunk| chunk.era > current_era);
+			if chunks.is_empty() {
+				Unbonding::<T>::remove(&who);
+			} else {
+				Unbonding::<T>::insert(&who, chunks);
+			}
+			T::Currency::release(
+				&HoldReason::Unbonding.into(),
+				&who,
+				withdrawn,
+				Precision::BestEffort,
+			)?;
+
+			Self::deposit_event(Event::Withdrawn { who, amount: withdrawn });
+			Ok(())
+		}
+
+		/// Delegate `amount` more to `to`, which the caller already delegates to.
+		#[barrel::call_index(5)]
+		#[barrel::weight(T::WeightInfo::delegate_more())]
+		pub fn delegate_more(
+			origin: OriginFor<T>,
+			to: T::AccountId,
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			ensure!(Delegations::<T>::contains_key(&who, &to), Error::<T>::NotDelegator);
+			Self::add_delegation(who, to, amount)
+		}
+
+		/// Move `amount` of the caller's delegation from `from` to `to`.
+		///
+		/// The stake remains held, and backs `to` right away, without being unbonded. It cannot be
+		/// moved while a slash of `from` is pending, since it would then escape the slash.
+		#[barrel::call_index(6)]
+		#[barrel::weight(
+			T::WeightInfo::redelegate().saturating_add(
+				T::DbWeight::get().reads(T::SlashDeferDuration::get().saturating_add(1).into())
+			)
+		)]
+		pub fn redelegate(
+			origin: OriginFor<T>,
+			from: T::AccountId,
+			to: T::AccountId,
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			ensure!(from != to, Error::<T>::SameValidator);
+			ensure!(who != to, Error::<T>::SelfDelegation);
+			ensure!(!amount.is_zero(), Error::<T>::NoStake);
+			ensure!(Self::is_registered(&to), Error::<T>::NotRegistered);
+			ensure!(!Chilled::<T>::contains_key(&to), Error::<T>::ValidatorChilled);
+			ensure!(!Self::has_pending_slash(&from), Error::<T>::PendingSlash);
+
+			// the delegation to `from` is reduced first, so that all of it can be moved to a new
+			// validator even if the caller is at the maximum.
+			Self::remove_delegation(&who, &from, amount)?;
+			if !Delegations::<T>::contains_key(&who, &to) {
+				Self::ensure_can_delegate_to_new(&who)?;
+			}
+			Self::reduce_delegated(&from, amount);
+			Delegations::<T>::mutate(&who, &to, |maybe_amount| {
+				*maybe_amount = Some(maybe_amount.unwrap_or_else(Zero::zero).saturating_add(amount))
+			});
+			Validators::<T>::mutate(&to, |maybe_stake| {
+				maybe_stake
+					.as_mut()
+					.map(|stake| stake.delegated = stake.delegated.saturating_add(amount))
+			});
+
+			Self::deposit_event(Event::Redelegated { who, from, to, amount });
+			Ok(())
+		}
+
+		/// Set the commission of the calling validator, which applies from the next era on.
+		///
+		/// The commission can change at most once per era, and by at most
+		/// [`Config::MaxCommissionChange`].
+		#[barrel::call_index(7)]
+		#[barrel::weight(T::WeightInfo::set_commission())]
+		pub fn set_commission(origin: OriginFor<T>, commission: Perbill) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Self::is_registered(&who), Error::<T>::NotRegistered);
+
+			let era = CurrentEra::<T>::get();
+			let current = match Commissions::<T>::get(&who) {
+				Some(current) => {
+					ensure!(current.changed_in < era, Error::<T>::CommissionAlreadyChanged);
+					current.rate
+				},
+				None => Perbill::zero(),
+			};
+			let change = commission.max(current).saturating_sub(commission.min(current));
+			ensure!(change <= T::MaxCommissionChange::get(), Error::<T>::CommissionChangeTooLarge);
+
+			Commissions::<T>::insert(
+				&who,
+				ValidatorCommission { rate: commission, changed_in: era },
+			);
+			Self::deposit_event(Event::CommissionSet { who, commission });
+			Ok(())
+		}
+
+		/// Pay the next page of the rewards of `validator` for `era`, from [`Config::RewardPot`].
+		///
+		/// The first page pays the validator its commission and the reward of its own stake, along
+		/// with the first [`Config::MaxExposurePageSize`] of its delegators. Each further page
+		/// pays as many delegators more. Each delegator is paid pro rata to its stake, after the
+		/// commission. Anyone can call this, for any era that ended at most
+		/// [`Config::HistoryDepth`] eras ago.
+		#[barrel::call_index(8)]
+		#[barrel::weight(T::WeightInfo::payout_era(T::MaxExposurePageSize::get()))]
+		pub fn payout_era(
+			origin: OriginFor<T>,
+			validator: T::AccountId,
+			era: EraIndex,
+		) -> DispatchResult {
+			ensure_signed(origin)?;
+			let current_era = CurrentEra::<T>::get();
+			ensure!(era < current_era, Error::<T>::EraNotEnded);
+			ensure!(current_era - era <= T::HistoryDepth::get(), Error::<T>::EraExpired);
+
+			let exposure =
+				ErasStakersOverview::<T>::get(era, &validator).ok_or(Error::<T>::NotExposed)?;
+			let mut era_reward = ErasRewards::<T>::get(era).ok_or(Error::<T>::NotExposed)?;
+			let page = ClaimedPages::<T>::get(era, &validator);
+			ensure!(page < exposure.page_count, Error::<T>::AlreadyPaid);
+			ClaimedPages::<T>::insert(era, &validator, page + 1);
+
+			let validator_reward = Perbill::from_rational(exposure.total, era_reward.total_stake)
+				.mul_floor(era_reward.reward);
+			let commission = exposure.commission.mul_floor(validator_reward);
+			let shared = validator_reward.saturating_sub(commission);
+			let share = |stake| Perbill::from_rational(stake, exposure.total).mul_floor(shared);
+
+			let mut payouts = Vec::new();
+			if page.is_zero() {
+				payouts.push((validator.clone(), commission.saturating_add(share(exposure.own))));
+			}
+			payouts.extend(
+				ErasStakersPaged::<T>::get((era, &validator, page))
+					.unwrap_or_default()
+					.into_iter()
+					.map(|(who, stake)| (who, share(stake))),
+			);
+
+			let pot = T::RewardPot::get();
+			for (who, amount) in payouts {
+				if amount.is_zero() {
+					continue
+				}
+				// a payout that cannot be made, because the pot was drained or `who` cannot receive
+				// it, stays in the pot until the era expires.
+				if T::Currency::transfer(&pot, &who, amount, Preservation::Preserve).is_ok() {
+					era_reward.paid = era_reward.paid.saturating_add(amount);
+					UnclaimedRewards::<T>::mutate(|unclaimed| {
+						*unclaimed = unclaimed.saturating_sub(amount)
+					});
+					Self::deposit_event(Event::Rewarded { era, who, amount });
+				}
+			}
+			ErasRewards::<T>::insert(era, era_reward);
+
+			Ok(())
+		}
+
+		/// Report an offence of `validator` in the current era, for which `fraction` of the stake
+		/// behind it is slashed.
+		///
+		/// Only root can report offences for now. See [`Barrel::on_offence`].
+		#[barrel::call_index(9)]
+		#[barrel::weight(T::WeightInfo::report_offence())]
+		pub fn report_offence(
+			origin: OriginFor<T>,
+			validator: T::AccountId,
+			fraction: Perbill,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			Self::on_offence(validator, fraction)
+		}
+
+		/// Cancel the slash of `validator` for its offence in era `era`, before it is applied.
+		///
+		/// The validator stays chilled.
+		#[barrel::call_index(10)]
+		#[barrel::weight(T::WeightInfo::cancel_deferred_slash())]
+		pub fn cancel_deferred_slash(
+			origin: OriginFor<T>,
+			era: EraIndex,
+			validator: T::AccountId,
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			UnappliedSlashes::<T>::take(era, &validator).ok_or(Error::<T>::NoUnappliedSlash)?;
+
+			Self::deposit_event(Event::SlashCancelled { validator, era });
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Barrel<T> {
+		/// The balance of `who` that can be put at stake, while keeping the account alive.
+		fn stakeable_balance(who: &T::AccountId) -> BalanceOf<T> {
+			T::Currency::reducible_balance(who, Preservation::Protect, Fortitude::Polite)
+		}
+
+		/// Whether `who` is a registered validator, with some own stake.
+		pub fn is_registered(who: &T::AccountId) -> bool {
+			Validators::<T>::get(who).map_or(false, |stake| !stake.own.is_zero())
+		}
+
+		/// The validators that `who` delegates to, with the stake delegated to each of them.
+		pub fn delegations_of(who: &T::AccountId) -> Vec<(T::AccountId, BalanceOf<T>)> {
+			Delegations::<T>::iter_prefix(who).collect()
+		}
+
+		/// The total stake that `who` delegates, across all of its delegations.
+		pub fn total_delegated(who: &T::AccountId) -> BalanceOf<T> {
+			Delegators::<T>::get(who).unwrap_or_else(Zero::zero)
+		}
+
+		/// Record an offence of `validator` in the current era. `fraction` of the stake behind it
+		/// in that era is slashed once [`Config::SlashDeferDuration`] eras passed, unless the
+		/// slash is cancelled before.
+		///
+		/// The validator is removed from [`ActiveValidators`] right away, and chilled. Offences of
+		/// a validator in the same era do not add up: only the largest fraction is slashed.
+		pub fn on_offence(validator: T::AccountId, fraction: Perbill) -> DispatchResult {
+			let era = CurrentEra::<T>::get();
 			ensure!(
-				barrel_currency::Balances::<T>::get(&who).map_or(false, |b| b >= amount),
-				"InsufficientFunds"
+				ErasStakersOverview::<T>::contains_key(era, &validator),
+				Error::<T>::NotExposed
 			);
 
-			// TODO: we can basically remove this because we have transactional.
-			ensure!(Validators::<T>::contains_key(&to), "NotRegistered");
+			UnappliedSlashes::<T>::mutate(era, &validator, |maybe_fraction| {
+				*maybe_fraction = Some(maybe_fraction.map_or(fraction, |f| f.max(fraction)))
+			});
+			ActiveValidators::<T>::mutate(|active| active.retain(|v| *v != validator));
+			Chilled::<T>::insert(&validator, era);
+
+			Self::deposit_event(Event::OffenceReported { validator, era, fraction });
+			Ok(())
+		}
 
-			Delegators::<T>::insert(&who, amount);
+		/// Whether `validator` has a slash that is not applied yet, in any era whose slashes are
+		/// still deferred.
+		fn has_pending_slash(validator: &T::AccountId) -> bool {
+			let current_era = CurrentEra::<T>::get();
+			(current_era.saturating_sub(T::SlashDeferDuration::get())..=current_era)
+				.any(|era| UnappliedSlashes::<T>::contains_key(era, validator))
+		}
+
+		/// Ensure that `who` can delegate to one more validator.
+		fn ensure_can_delegate_to_new(who: &T::AccountId) -> DispatchResult {
+			let count = Delegations::<T>::iter_prefix(who).count() as u32;
+			ensure!(count < T::MaxDelegations::get(), Error::<T>::TooManyDelegations);
+			Ok(())
+		}
+
+		/// Hold `amount` more of `who`, and delegate it to `to`.
+		fn add_delegation(
+			who: T::AccountId,
+			to: T::AccountId,
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
+			ensure!(!amount.is_zero(), Error::<T>::NoStake);
+			ensure!(who != to, Error::<T>::SelfDelegation);
+			ensure!(Self::stakeable_balance(&who) >= amount, Error::<T>::InsufficientFunds);
+			ensure!(Self::is_registered(&to), Error::<T>::NotRegistered);
+			ensure!(!Chilled::<T>::contains_key(&to), Error::<T>::ValidatorChilled);
+			ensure!(
+				Delegators::<T>::contains_key(&who) ||
+					Delegators::<T>::count() < T::MaxDelegators::get(),
+				Error::<T>::TooManyDelegators
+			);
+
+			// the records are only increased once the funds are held, so that they never exceed
+			// them when `sync_stake` is called along the way.
+			T::Currency::hold(&HoldReason::Delegation.into(), &who, amount)?;
+			Delegators::<T>::mutate(&who, |maybe_total| {
+				*maybe_total = Some(maybe_total.unwrap_or_else(Zero::zero).saturating_add(amount))
+			});
+			Delegations::<T>::mutate(&who, &to, |maybe_amount| {
+				*maybe_amount = Some(maybe_amount.unwrap_or_else(Zero::zero).saturating_add(amount))
+			});
 			Validators::<T>::mutate(&to, |maybe_stake| {
-				maybe_stake.as_mut().map(|stake| stake.delegated += amount)
+				maybe_stake
+					.as_mut()
+					.map(|stake| stake.delegated = stake.delegated.saturating_add(amount))
+			});
+
+			Self::deposit_event(Event::Delegated { who, to, amount });
+			Ok(())
+		}
+
+		/// Reduce the delegation of `who` to `from` by `amount`, removing it if nothing is left.
+		///
+		/// Neither the total of `who` nor the stake of `from` are touched.
+		fn remove_delegation(
+			who: &T::AccountId,
+			from: &T::AccountId,
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
+			Delegations::<T>::try_mutate_exists(who, from, |maybe_amount| {
+				let delegated = maybe_amount.ok_or(Error::<T>::NotDelegator)?;
+				ensure!(amount <= delegated, Error::<T>::InsufficientStake);
+				*maybe_amount = Some(delegated - amount).filter(|left| !left.is_zero());
+				Ok(())
+			})
+		}
+
+		/// Record the stake behind each of the `validators` selected for `era`, along with their
+		/// commission.
+		///
+		/// Returns the number of delegations that were looked at.
+		fn snapshot_exposures(
+			era: EraIndex,
+			validators: Vec<(T::AccountId, ValidatorStake<BalanceOf<T>>)>,
+		) -> u32 {
+			if validators.is_empty() {
+				return 0
+			}
+
+			let mut delegators = validators.iter().map(|_| Vec::new()).collect::<Vec<_>>();
+			let mut count: u32 = 0;
+			for (delegator, validator, amount) in Delegations::<T>::iter() {
+				count += 1;
+				if let Some(i) = validators.iter().position(|(v, _)| *v == validator) {
+					delegators[i].push((delegator, amount));
+				}
+			}
+
+			let page_size = T::MaxExposurePageSize::get().max(1) as usize;
+			let mut total_stake: BalanceOf<T> = Zero::zero();
+			for ((validator, stake), delegators) in validators.into_iter().zip(delegators) {
+				let total = delegators
+					.iter()
+					.fold(stake.own, |acc, (_, amount)| acc.saturating_add(*amount));
+				total_stake = total_stake.saturating_add(total);
+
+				let mut page_count: u32 = 0;
+				for page in delegators.chunks(page_size) {
+					ErasStakersPaged::<T>::insert(
+						(era, &validator, page_count),
+						BoundedVec::truncate_from(page.to_vec()),
+					);
+					page_count += 1;
+				}
+				let commission =
+					Commissions::<T>::get(&validator).map_or(Perbill::zero(), |c| c.rate);
+				ErasStakersOverview::<T>::insert(
+					era,
+					&validator,
+					ExposureOverview {
+						total,
+						own: stake.own,
+						commission,
+						page_count: page_count.max(1),
+					},
+				);
+			}
+			ErasRewards::<T>::insert(
+				era,
+				EraReward { total_stake, reward: Zero::zero(), paid: Zero::zero() },
+			);
+			count
+		}
+
+		/// Fix the reward of `era`, which just ended, to what the reward pot holds beyond the
+		/// unclaimed rewards of earlier eras.
+		fn end_era(era: EraIndex) {
+			ErasRewards::<T>::mutate(era, |maybe_reward| {
+				if let Some(era_reward) = maybe_reward {
+					let pot = T::Currency::reducible_balance(
+						&T::RewardPot::get(),
+						Preservation::Preserve,
+						Fortitude::Polite,
+					);
+					let unclaimed = UnclaimedRewards::<T>::get();
+					era_reward.reward = pot.saturating_sub(unclaimed);
+					if !era_reward.reward.is_zero() {
+						UnclaimedRewards::<T>::put(unclaimed.saturating_add(era_reward.reward));
+						Self::deposit_event(Event::EraPaid { era, reward: era_reward.reward });
+					}
+				}
 			});
+		}
+
+		/// The last era that expired as `current_era` started, if any.
+		fn last_expired_era(current_era: EraIndex) -> Option<EraIndex> {
+			current_era.checked_sub(T::HistoryDepth::get().saturating_add(1))
+		}
+
+		/// Remove the rewards of the era that expires as `current_era` starts, and leave what was
+		/// not claimed of them in the reward pot, for the eras to come.
+		///
+		/// Its exposures are removed over the following blocks, by
+		/// [`Barrel::remove_expired_exposures`].
+		fn expire_rewards(current_era: EraIndex) {
+			let Some(expired) = Self::last_expired_era(current_era) else { return };
+
+			if let Some(era_reward) = ErasRewards::<T>::take(expired) {
+				let left = era_reward.reward.saturating_sub(era_reward.paid);
+				UnclaimedRewards::<T>::mutate(|unclaimed| {
+					*unclaimed = unclaimed.saturating_sub(left)
+				});
+			}
+			// a cleanup that is still in progress goes on to this era once it is done.
+			if !ExposureCleanup::<T>::exists() {
+				ExposureCleanup::<T>::put(CleanupCursor {
+					era: expired,
+					map: ExposureMap::Overview,
+					cursor: None,
+				});
+			}
+		}
+
+		/// Remove at most [`Config::MaxExposurePageSize`] entries of the exposures of expired
+		/// eras, continuing from [`ExposureCleanup`].
+		///
+		/// Returns the weight consumed.
+		pub(crate) fn remove_expired_exposures() -> Weight {
+			let Some(mut cleanup) = ExposureCleanup::<T>::get() else {
+				return T::WeightInfo::remove_expired_exposures(0)
+			};
+			let last = Self::last_expired_era(CurrentEra::<T>::get()).unwrap_or_default();
+			let limit = T::MaxExposurePageSize::get();
+			let mut removed: u32 = 0;
+
+			let done = loop {
+				let budget = limit.saturating_sub(removed);
+				if budget == 0 {
+					break false
+				}
+				let cursor = cleanup.cursor.take();
+				let cursor = cursor.as_ref().map(|cursor| &cursor[..]);
+				let result = match cleanup.map {
+					ExposureMap::Overview =>
+						ErasStakersOverview::<T>::clear_prefix(cleanup.era, budget, cursor),
+					ExposureMap::Paged =>
+						ErasStakersPaged::<T>::clear_prefix((cleanup.era,), budget, cursor),
+					ExposureMap::ClaimedPages =>
+						ClaimedPages::<T>::clear_prefix(cleanup.era, budget, cursor),
+				};
+				removed = removed.saturating_add(result.loops);
+				if let Some(cursor) = result.maybe_cursor {
+					// a cursor that does not fit only means that the removal starts over.
+					cleanup.cursor = BoundedVec::try_from(cursor).ok();
+					break false
+				}
+				match cleanup.map {
+					ExposureMap::Overview => cleanup.map = ExposureMap::Paged,
+					ExposureMap::Paged => cleanup.map = ExposureMap::ClaimedPages,
+					ExposureMap::ClaimedPages => {
+						if cleanup.era >= last {
+							break true
+						}
+						cleanup.era.saturating_inc();
+						cleanup.map = ExposureMap::Overview;
+					},
+				}
+			};
+
+			if done {
+				ExposureCleanup::<T>::kill();
+			} else {
+				ExposureCleanup::<T>::put(cleanup);
+			}
+			T::WeightInfo::remove_expired_exposures(removed)
+		}
+
+		/// Apply the slashes of the offences that are due as `current_era` starts.
+		///
+		/// Returns the weight of applying them.
+		fn apply_slashes(current_era: EraIndex) -> Weight {
+			let Some(era) = current_era.checked_sub(T::SlashDeferDuration::get()) else {
+				return Weight::zero()
+			};
+
+			UnappliedSlashes::<T>::drain_prefix(era).collect::<Vec<_>>().into_iter().fold(
+				Weight::zero(),
+				|weight, (validator, fraction)| {
+					let stakers = Self::apply_slash(era, &validator, fraction);
+					weight.saturating_add(T::WeightInfo::apply_slash(stakers))
+				},
+			)
+		}
+
+		/// Slash `fraction` of the stake of each staker that was behind `validator` in `era`.
+		///
+		/// The stake is taken from what the staker still has at stake behind `validator`, and
+		/// then from its unbonding funds, so that the slash cannot be escaped by unbonding after
+		/// the offence. Returns the number of stakers.
+		pub(crate) fn apply_slash(
+			era: EraIndex,
+			validator: &T::AccountId,
+			fraction: Perbill,
+		) -> u32 {
+			let Some(exposure) = ErasStakersOverview::<T>::get(era, validator) else { return 0 };
+
+			let mut count: u32 = 1;
+			Self::slash_staker(
+				validator,
+				validator,
+				HoldReason::ValidatorBond,
+				fraction.mul_floor(exposure.own),
+			);
+			for page in 0..exposure.page_count {
+				for (who, stake) in
+					ErasStakersPaged::<T>::get((era, validator, page)).unwrap_or_default()
+				{
+					count += 1;
+					Self::slash_staker(
+						&who,
+						validator,
+						HoldReason::Delegation,
+						fraction.mul_floor(stake),
+					);
+				}
+			}
+			count
+		}
+
+		/// Slash `amount` of the stake of `who` behind `validator`, which is held for `reason`,
+		/// and the rest from its unbonding funds.
+		fn slash_staker(
+			who: &T::AccountId,
+			validator: &T::AccountId,
+			reason: HoldReason,
+			amount: BalanceOf<T>,
+		) {
+			if amount.is_zero() {
+				return
+			}
+
+			// the records are reduced before the funds are slashed, so that `sync_stake` finds
+			// nothing more to reduce when it is called along the way.
+			let bonded = match reason {
+				HoldReason::ValidatorBond => Validators::<T>::mutate_exists(who, |maybe_stake| {
+					let Some(stake) = maybe_stake else { return Zero::zero() };
+					let cut = stake.own.min(amount);
+					stake.own -= cut;
+					if stake.own.is_zero() && stake.delegated.is_zero() {
+						*maybe_stake = None;
+					}
+					cut
+				}),
+				_ => {
+					let cut = Delegations::<T>::get(who, validator)
+						.unwrap_or_else(Zero::zero)
+						.min(amount);
+					let _ = Self::remove_delegation(who, validator, cut);
+					Self::reduce_delegated(validator, cut);
+					Self::reduce_total_delegated(who, cut);
+					cut
+				},
+			};
+			let unbonding = Self::reduce_unbonding(who, amount - bonded);
+
+			let slash = |reason, amount: BalanceOf<T>| {
+				if amount.is_zero() {
+					amount
+				} else {
+					T::OnSlash::on_slash(who, reason, amount)
+				}
+			};
+			let slashed =
+				slash(reason, bonded).saturating_add(slash(HoldReason::Unbonding, unbonding));
+			if !slashed.is_zero() {
+				Self::deposit_event(Event::Slashed {
+					who: who.clone(),
+					validator: validator.clone(),
+					amount: slashed,
+				});
+			}
+		}
 
+		/// Move `amount` held by `who` for `reason` into its unbonding queue, from which it can be
+		/// withdrawn after [`Config::BondingDuration`] eras.
+		///
+		/// The stake records of `who` must already be reduced by `amount`.
+		fn unbond(who: &T::AccountId, reason: HoldReason, amount: BalanceOf<T>) -> DispatchResult {
+			if amount.is_zero() {
+				return Ok(())
+			}
+
+			// the queue is only extended once the funds are held for unbonding, so that it never
+			// exceeds them when `sync_stake` is called along the way.
+			T::Currency::release(&reason.into(), who, amount, Precision::Exact)?;
+			T::Currency::hold(&HoldReason::Unbonding.into(), who, amount)?;
+
+			let era = CurrentEra::<T>::get().saturating_add(T::BondingDuration::get());
+			Unbonding::<T>::try_mutate(who, |chunks| -> DispatchResult {
+				match chunks.last_mut() {
+					Some(chunk) if chunk.era == era =>
+						chunk.amount = chunk.amount.saturating_add(amount),
+					_ => chunks
+						.try_push(UnbondingChunk { amount, era })
+						.map_err(|_| Error::<T>::TooManyUnbondingChunks)?,
+				}
+				Ok(())
+			})?;
+
+			Self::deposit_event(Event::Unbonded { who: who.clone(), amount, era });
 			Ok(())
 		}
+
+		/// Reduce the stake delegated to `validator` by `amount`.
+		///
+		/// A validator that unregistered is removed once it is no longer backed by any delegation.
+		fn reduce_delegated(validator: &T::AccountId, amount: BalanceOf<T>) {
+			Validators::<T>::mutate_exists(validator, |maybe_stake| {
+				if let Some(stake) = maybe_stake {
+					stake.delegated = stake.delegated.saturating_sub(amount);
+					if stake.own.is_zero() && stake.delegated.is_zero() {
+						*maybe_stake = None;
+					}
+				}
+			});
+		}
+
+		/// Reduce the total stake delegated by `who` by `amount`, removing it as a delegator if
+		/// nothing is left.
+		fn reduce_total_delegated(who: &T::AccountId, amount: BalanceOf<T>) {
+			Delegators::<T>::mutate_exists(who, |maybe_total| {
+				let total = maybe_total.unwrap_or_else(Zero::zero).saturating_sub(amount);
+				*maybe_total = Some(total).filter(|total| !total.is_zero());
+			});
+		}
+
+		/// Reduce the unbonding funds of `who` by up to `amount`, taken from the chunks that would
+		/// be withdrawn last.
+		///
+		/// Returns the amount that they were reduced by.
+		fn reduce_unbonding(who: &T::AccountId, amount: BalanceOf<T>) -> BalanceOf<T> {
+			if amount.is_zero() {
+				return amount
+			}
+
+			let mut chunks = Unbonding::<T>::get(who);
+			let mut excess = amount;
+			while let Some(chunk) = chunks.last_mut() {
+				let cut = chunk.amount.min(excess);
+				chunk.amount -= cut;
+				excess -= cut;
+				if chunk.amount.is_zero() {
+					chunks.pop();
+				}
+				if excess.is_zero() {
+					break
+				}
+			}
+			if chunks.is_empty() {
+				Unbonding::<T>::remove(who);
+			} else {
+				Unbonding::<T>::insert(who, chunks);
+			}
+			amount - excess
+		}
+
+		/// Reduce the stake recorded for `who` to what is actually held for it.
+		///
+		/// Held funds can be slashed or moved by other barrels, without staking knowing about
+		/// it. Delegated funds are taken from the delegations of `who` in storage order, and a
+		/// delegator with nothing left at stake is removed. Unbonding funds are taken from the
+		/// chunks that would be withdrawn last.
+		pub(crate) fn sync_stake(who: &T::AccountId) {
+			if let Some(mut stake) = Validators::<T>::get(who) {
+				let held = T::Currency::balance_on_hold(&HoldReason::ValidatorBond.into(), who);
+				if held < stake.own {
+					stake.own = held;
+					if held.is_zero() {
+						// as in `unregister`, a validator without its own stake stops validating.
+						ActiveValidators::<T>::mutate(|active| active.retain(|v| v != who));
+						Commissions::<T>::remove(who);
+					}
+					if stake.own.is_zero() && stake.delegated.is_zero() {
+						Validators::<T>::remove(who);
+					} else {
+						Validators::<T>::insert(who, stake);
+					}
+				}
+			}
+
+			if let Some(delegated) = Delegators::<T>::get(who) {
+				let held = T::Currency::balance_on_hold(&HoldReason::Delegation.into(), who);
+				if held < delegated {
+					let mut excess = delegated - held;
+					for (target, amount) in Self::delegations_of(who) {
+						let cut = amount.min(excess);
+						excess -= cut;
+						let _ = Self::remove_delegation(who, &target, cut);
+						Self::reduce_delegated(&target, cut);
+						if excess.is_zero() {
+							break
+						}
+					}
+					if held.is_zero() {
+						Delegators::<T>::remove(who);
+					} else {
+						Delegators::<T>::insert(who, held);
+					}
+				}
+			}
+
+			let unbonding = Unbonding::<T>::get(who)
+				.iter()
+				.fold(Zero::zero(), |acc: BalanceOf<T>, chunk| acc.saturating_add(chunk.amount));
+			let held = T::Currency::balance_on_hold(&HoldReason::Unbonding.into(), who);
+			if held < unbonding {
+				Self::reduce_unbonding(who, unbonding - held);
+			}
+		}
+	}
+
+	/// Keeps the stake records in sync with the tutorial currency, when it is the
+	/// [`Config::Currency`].
+	///
+	/// The removal of an account is reported as a change as well, so this needs no
+	/// `OnAccountKilled` counterpart.
+	impl<T: Config> currency::OnBalanceChanged<T::AccountId> for Barrel<T> {
+		fn on_balance_changed(who: &T::AccountId, _: &currency::AccountData) {
+			Self::sync_stake(who);
+		}
+
+		fn weight() -> Weight {
+			T::WeightInfo::sync_stake(T::MaxDelegations::get())
+		}
 	}
 
 	#[barrel::hooks]
 	impl<T: Config> Hooks<BlockNumberFor<T>> for Barrel<T> {
 		fn on_initialize(now: BlockNumberFor<T>) -> Weight {
-			use frame::traits::Zero;
-
 			if (now % T::EraDuration::get()).is_zero() && !now.is_zero() {
+				let ended = CurrentEra::<T>::get();
+				let era = ended.saturating_add(1);
+				CurrentEra::<T>::put(era);
+
+				// slashes are applied while the stake behind the offenders is still known, and
+				// before the validators of the new era are selected.
+				let slashes = Self::apply_slashes(era);
+				// the rewards that expire go back to the pot, before the reward of the era that
+				// ended is taken from it.
+				Self::expire_rewards(era);
+				Self::end_era(ended);
+
 				let mut all_validators = Validators::<T>::iter().collect::<Vec<_>>();
-				all_validators.sort_by_key(|(_, stake)| stake.own + stake.delegated);
+				let count = all_validators.len() as u32;
+				// validators that unregistered, but are still backed by delegations, are skipped,
+				// as are chilled validators.
+				all_validators.retain(|(who, stake)| {
+					!stake.own.is_zero() && !Chilled::<T>::contains_key(who)
+				});
+				all_validators.sort_by_key(|(_, stake)| stake.own.saturating_add(stake.delegated));
 				all_validators.reverse();
-				ActiveValidators::<T>::put(
-					all_validators
-						.into_iter()
-						.take(T::ValidatorCount::get() as usize)
-						.map(|(acc, _)| acc)
-						.collect::<Vec<_>>(),
+				all_validators.truncate(T::ValidatorCount::get() as usize);
+				let validators = BoundedVec::<_, T::ValidatorCount>::truncate_from(
+					all_validators.iter().map(|(acc, _)| acc.clone()).collect::<Vec<_>>(),
 				);
+				ActiveValidators::<T>::put(&validators);
+				let delegations = Self::snapshot_exposures(era, all_validators);
+
+				Self::deposit_event(Event::NewValidatorSet {
+					era,
+					validators: validators.into_inner(),
+				});
+				T::WeightInfo::select_validators(count, delegations)
+					.saturating_add(slashes)
+					.saturating_add(Self::remove_expired_exposures())
+			} else {
+				Self::remove_expired_exposures()
 			}
+		}
+
+		fn integrity_test() {
+			let defer = T::SlashDeferDuration::get();
+			assert!(
+				defer >= 1 && defer <= T::HistoryDepth::get(),
+				"SlashDeferDuration must be between 1 and HistoryDepth"
+			);
+			assert!(
+				defer < T::BondingDuration::get(),
+				"SlashDeferDuration must be shorter than BondingDuration"
+			);
 
-			Default::default()
+			// the validators of an era are selected in a block hook, which must fit in a block.
+			let selection = T::WeightInfo::select_validators(
+				T::MaxValidators::get(),
+				T::MaxDelegators::get().saturating_mul(T::MaxDelegations::get()),
+			);
+			assert!(
+				selection.ref_time() <= T::BlockWeights::get().max_block.ref_time(),
+				"the selection of validators with MaxValidators and MaxDelegators does not fit in a \
+				 block"
+			);
 		}
 	}
 
 	#[cfg(test)]
-	mod tests {
-		use crate::{
-			currency::barrel::{self as barrel_currency, Balance},
-			staking::barrel::{self as barrel_staking, *},
-		};
-		use frame::testing_prelude::*;
+	pub(crate) mod tests {
+		use crate::staking::barrel::{self as barrel_staking, *};
 		use barrel_staking::{ActiveValidators, ValidatorStake, Validators};
+		use frame::{
+			deps::frame_support::traits::StorageInfoTrait,
+			testing_prelude::*,
+			traits::{fungible::InspectHold, tokens::Preservation::Expendable},
+		};
 
-		type AccountId = <Runtime as frame_system::Config>::AccountId;
+		/// The staking tests, written once and executed against every mock runtime below.
+		///
+		/// Each mock runtime provides `Runtime`, `RuntimeOrigin`, `ValidatorCount`, `EraDuration`,
+		/// `ExistentialDeposit`, `RewardPot` and a `genesis(balances, validators, delegators)`
+		/// function.
+		macro_rules! staking_tests {
+			() => {
+				type AccountId = <Runtime as frame_system::Config>::AccountId;
+				type Balance = BalanceOf<Runtime>;
+				type StakeCurrency = <Runtime as barrel_staking::Config>::Currency;
 
-		construct_runtime!(
-			pub struct Runtime {
-				System: frame_system,
-				Currency: barrel_currency,
-				Staking: barrel_staking,
-			}
-		);
+				struct ExtBuilder {
+					validators: Vec<(AccountId, Balance)>,
+					delegators: Vec<(AccountId, AccountId, Balance)>,
+					balances: Vec<(AccountId, Balance)>,
+				}
 
-		#[derive_impl(frame_system::config_preludes::TestDefaultConfig as frame_system::DefaultConfig)]
-		impl frame_system::Config for Runtime {
-			type Block = MockBlock<Runtime>;
-		}
+				impl Default for ExtBuilder {
+					fn default() -> Self {
+						let instance = Self {
+							validators: Default::default(),
+							delegators: Default::default(),
+							balances: Default::default(),
+						};
+						instance.add_validator(1, 10).add_validator(2, 20).add_validator(3, 30)
+					}
+				}
 
-		// TODO: if we were to have private `struct` runtime, then these would also not need to be
-		// pub.
-		parameter_types! {
-			pub static ValidatorCount: u32 = 2;
-			pub const EraDuration: BlockNumberFor<Runtime> = 3;
-		}
+				impl ExtBuilder {
+					// Every staker is funded with the existential deposit on top of its stake, so
+					// that it can be put on hold while keeping the account alive.
+					fn add_validator(mut self, validator: AccountId, self_stake: Balance) -> Self {
+						self.balances.push((validator, self_stake + ExistentialDeposit::get()));
+						self.validators.push((validator, self_stake));
+						self
+					}
 
-		impl barrel_staking::Config for Runtime {
-			type ValidatorCount = ValidatorCount;
-			type EraDuration = EraDuration;
-		}
+					fn add_delegator(
+						mut self,
+						delegator: AccountId,
+						delegatee: AccountId,
+						stake: Balance,
+					) -> Self {
+						// a delegator that delegates to many validators is funded for all of them.
+						match self.balances.iter_mut().find(|(who, _)| *who == delegator) {
+							Some((_, balance)) => *balance += stake,
+							None =>
+								self.balances.push((delegator, stake + ExistentialDeposit::get())),
+						}
+						self.delegators.push((delegator, delegatee, stake));
+						self
+					}
+
+					fn build_and_execute(self, test: impl FnOnce() -> ()) {
+						let runtime_genesis =
+							genesis(self.balances, self.validators, self.delegators);
+						let mut ext = TestState::new(runtime_genesis.build_storage().unwrap());
+
+						// process block 0 to simulate a proper genesis. Not mandatory to be done
+						// this way. This sets the current block number (to be executed) to 1.
+						ext.execute_with(next_block);
+						ext.execute_with(test);
+					}
+				}
+
+				fn next_block() {
+					let now = frame_system::Barrel::<Runtime>::block_number();
+					barrel_staking::Barrel::<Runtime>::on_initialize(now);
+					frame_system::Barrel::<Runtime>::set_block_number(now + 1);
+				}
+
+				fn held(reason: HoldReason, who: AccountId) -> Balance {
+					<StakeCurrency as InspectHold<_>>::balance_on_hold(&reason.into(), &who)
+				}
+
+				/// The events deposited by staking since the last call.
+				fn staking_events() -> Vec<Event<Runtime>> {
+					let events = frame_system::Barrel::<Runtime>::events()
+						.into_iter()
+						.filter_map(|r| match r.event {
+							RuntimeEvent::Staking(e) => Some(e),
+							_ => None,
+						})
+						.collect();
+					frame_system::Barrel::<Runtime>::reset_events();
+					events
+				}
+
+				#[test]
+				fn storage_is_bounded() {
+					for info in barrel_staking::Barrel::<Runtime>::storage_info() {
+						assert!(info.max_size.is_some(), "{:?} is unbounded", info.storage_name);
+					}
+				}
+
+				#[test]
+				fn basic_setup_works() {
+					ExtBuilder::default().build_and_execute(|| {
+						assert_eq!(frame_system::Barrel::<Runtime>::block_number(), 1);
+						assert_eq!(
+							Validators::<Runtime>::get(1).unwrap(),
+							ValidatorStake { own: 10, delegated: 0 }
+						);
+						assert_eq!(
+							Validators::<Runtime>::get(2).unwrap(),
+							ValidatorStake { own: 20, delegated: 0 }
+						);
+						assert_eq!(
+							Validators::<Runtime>::get(3).unwrap(),
+							ValidatorStake { own: 30, delegated: 0 }
+						);
+						assert_eq!(Validators::<Runtime>::iter().count(), 3);
+						assert!(ActiveValidators::<Runtime>::get().is_empty());
+						// no events are deposited at genesis.
+						assert!(staking_events().is_empty());
+					})
+				}
+
+				#[test]
+				fn stake_is_held() {
+					ExtBuilder::default().add_delegator(42, 1, 30).build_and_execute(|| {
+						// given initial state, all of the stake of validators and delegators is
+						// held.
+						assert_eq!(held(HoldReason::ValidatorBond, 1), 10);
+						assert_eq!(StakeCurrency::balance(&1), ExistentialDeposit::get());
+						assert_eq!(held(HoldReason::Delegation, 42), 30);
+						assert_eq!(StakeCurrency::balance(&42), ExistentialDeposit::get());
+
+						// when, then neither can transfer their stake away.
+						assert!(
+							<StakeCurrency as Mutate<_>>::transfer(&1, &2, 10, Expendable).is_err()
+						);
+						assert!(<StakeCurrency as Mutate<_>>::transfer(&42, &2, 30, Expendable)
+							.is_err());
+						assert!(staking_events().is_empty());
+					})
+				}
+
+				#[test]
+				fn cannot_stake_more_than_free_balance() {
+					ExtBuilder::default().build_and_execute(|| {
+						// given initial state, 1 has all of its stake held, and receives some more.
+						assert_ok!(StakeCurrency::mint_into(&1, 5));
+
+						// when, then 1 can delegate what is free, but not more.
+						assert_noop!(
+							barrel_staking::Barrel::<Runtime>::delegate(
+								RuntimeOrigin::signed(1),
+								2,
+								6
+							),
+							Error::<Runtime>::InsufficientFunds
+						);
+						assert_ok!(barrel_staking::Barrel::<Runtime>::delegate(
+							RuntimeOrigin::signed(1),
+							2,
+							5
+						));
+						assert_eq!(
+							Validators::<Runtime>::get(2).unwrap(),
+							ValidatorStake { own: 20, delegated: 5 }
+						);
+						assert_eq!(held(HoldReason::Delegation, 1), 5);
+						assert_eq!(
+							staking_events(),
+							vec![Event::Delegated { who: 1, to: 2, amount: 5 }]
+						);
+					})
+				}
+
+				#[test]
+				fn delegates_to_many_validators() {
+					ExtBuilder::default()
+						.add_delegator(42, 1, 10)
+						.add_delegator(42, 2, 10)
+						.build_and_execute(|| {
+							// given 42 delegates to 1 and 2, and has some more funds.
+							let portfolio = || {
+								let mut delegations =
+									barrel_staking::Barrel::<Runtime>::delegations_of(&42);
+								delegations.sort();
+								delegations
+							};
+							assert_eq!(portfolio(), vec![(1, 10), (2, 10)]);
+							assert_eq!(barrel_staking::Barrel::<Runtime>::total_delegated(&42), 20);
+							assert_eq!(held(HoldReason::Delegation, 42), 20);
+							assert_ok!(StakeCurrency::mint_into(&42, 50));
+
+							// when, then it cannot delegate to 1 again, nor to a third validator.
+							assert_noop!(
+								barrel_staking::Barrel::<Runtime>::delegate(
+									RuntimeOrigin::signed(42),
+									1,
+									5
+								),
+								Error::<Runtime>::AlreadyDelegating
+							);
+							assert_noop!(
+								barrel_staking::Barrel::<Runtime>::delegate(
+									RuntimeOrigin::signed(42),
+									3,
+									5
+								),
+								Error::<Runtime>::TooManyDelegations
+							);
+
+							// when, then it can only delegate more to validators it delegates to.
+							assert_noop!(
+								barrel_staking::Barrel::<Runtime>::delegate_more(
+									RuntimeOrigin::signed(42),
+									3,
+									5
+								),
+								Error::<Runtime>::NotDelegator
+							);
+							assert_ok!(barrel_staking::Barrel::<Runtime>::delegate_more(
+								RuntimeOrigin::signed(42),
+								1,
+								5
+							));
+							assert_eq!(portfolio(), vec![(1, 15), (2, 10)]);
+							assert_eq!(
+								Validators::<Runtime>::get(1),
+								Some(ValidatorStake { own: 10, delegated: 15 })
+							);
+
+							// when, then it can move all of its delegation to 2 over to 3, and part
+							// of its delegation to 1.
+							assert_noop!(
+								barrel_staking::Barrel::<Runtime>::redelegate(
+									RuntimeOrigin::signed(42),
+									1,
+									1,
+									5
+								),
+								Error::<Runtime>::SameValidator
+							);
+							assert_noop!(
+								barrel_staking::Barrel::<Runtime>::redelegate(
+									RuntimeOrigin::signed(42),
+									2,
+									3,
+									11
+								),
+								Error::<Runtime>::InsufficientStake
+							);
+							assert_ok!(barrel_staking::Barrel::<Runtime>::redelegate(
+								RuntimeOrigin::signed(42),
+								2,
+								3,
+								10
+							));
+							assert_ok!(barrel_staking::Barrel::<Runtime>::redelegate(
+								RuntimeOrigin::signed(42),
+								1,
+								3,
+								5
+							));
+							assert_noop!(
+								barrel_staking::Barrel::<Runtime>::redelegate(
+									RuntimeOrigin::signed(42),
+									2,
+									1,
+									1
+								),
+								Error::<Runtime>::NotDelegator
+							);
+
+							// then its stake stays held, and only backs other validators.
+							assert_eq!(portfolio(), vec![(1, 10), (3, 15)]);
+							assert_eq!(barrel_staking::Barrel::<Runtime>::total_delegated(&42), 25);
+							assert_eq!(held(HoldReason::Delegation, 42), 25);
+							assert_eq!(
+								Validators::<Runtime>::get(1),
+								Some(ValidatorStake { own: 10, delegated: 10 })
+							);
+							assert_eq!(
+								Validators::<Runtime>::get(2),
+								Some(ValidatorStake { own: 20, delegated: 0 })
+							);
+							assert_eq!(
+								Validators::<Runtime>::get(3),
+								Some(ValidatorStake { own: 30, delegated: 15 })
+							);
+							assert_eq!(
+								staking_events(),
+								vec![
+									Event::Delegated { who: 42, to: 1, amount: 5 },
+									Event::Redelegated { who: 42, from: 2, to: 3, amount: 10 },
+									Event::Redelegated { who: 42, from: 1, to: 3, amount: 5 }
+								]
+							);
+						})
+				}
+
+				#[test]
+				fn validators_cannot_delegate_to_themselves() {
+					ExtBuilder::default().build_and_execute(|| {
+						// given 1 is a validator with some more funds.
+						assert_ok!(StakeCurrency::mint_into(&1, 50));
+
+						// when, then it cannot delegate to itself, nor move a delegation to itself.
+						assert_noop!(
+							barrel_staking::Barrel::<Runtime>::delegate(
+								RuntimeOrigin::signed(1),
+								1,
+								10
+							),
+							Error::<Runtime>::SelfDelegation
+						);
+						assert_ok!(barrel_staking::Barrel::<Runtime>::delegate(
+							RuntimeOrigin::signed(1),
+							2,
+							10
+						));
+						assert_noop!(
+							barrel_staking::Barrel::<Runtime>::redelegate(
+								RuntimeOrigin::signed(1),
+								2,
+								1,
+								10
+							),
+							Error::<Runtime>::SelfDelegation
+						);
+					})
+				}
+
+				#[test]
+				fn validators_and_delegators_are_bounded() {
+					ExtBuilder::default().add_delegator(42, 1, 10).build_and_execute(|| {
+						// given 3 validators and 1 delegator, which is all that is allowed.
+						MaxValidators::set(3);
+						MaxDelegators::set(1);
+						assert_ok!(StakeCurrency::mint_into(&4, 100));
+						assert_ok!(StakeCurrency::mint_into(&42, 100));
+						assert_ok!(StakeCurrency::mint_into(&43, 100));
+
+						// when, then no other validator can register.
+						assert_noop!(
+							barrel_staking::Barrel::<Runtime>::register(
+								RuntimeOrigin::signed(4),
+								10
+							),
+							Error::<Runtime>::TooManyValidators
+						);
+
+						// when, then no other account can delegate, but 42 can still delegate.
+						assert_noop!(
+							barrel_staking::Barrel::<Runtime>::delegate(
+								RuntimeOrigin::signed(43),
+								1,
+								10
+							),
+							Error::<Runtime>::TooManyDelegators
+						);
+						assert_ok!(barrel_staking::Barrel::<Runtime>::delegate(
+							RuntimeOrigin::signed(42),
+							2,
+							10
+						));
+
+						// when the limits are raised, then both can join.
+						MaxValidators::set(4);
+						MaxDelegators::set(2);
+						assert_ok!(barrel_staking::Barrel::<Runtime>::register(
+							RuntimeOrigin::signed(4),
+							10
+						));
+						assert_ok!(barrel_staking::Barrel::<Runtime>::delegate(
+							RuntimeOrigin::signed(43),
+							1,
+							10
+						));
+					})
+				}
+
+				#[test]
+				fn selects_validators() {
+					ExtBuilder::default().build_and_execute(|| {
+						// given initial state,
+
+						// when processing block 1, nothing will happen.
+						next_block();
+						assert!(ActiveValidators::<Runtime>::get().is_empty());
+
+						// when processing block 2, nothing will happen.
+						next_block();
+						assert!(ActiveValidators::<Runtime>::get().is_empty());
+						assert!(staking_events().is_empty());
+
+						// when processing block 3, new validators will be selected.
+						next_block();
+						assert_eq!(ActiveValidators::<Runtime>::get(), vec![3, 2]);
+						assert_eq!(
+							staking_events(),
+							vec![Event::NewValidatorSet { era: 1, validators: vec![3, 2] }]
+						);
+					})
+				}
+
+				#[test]
+				fn considers_delegators() {
+					// typically 2 and 3 win, and 1 and 3
+					ExtBuilder::default().add_delegator(42, 1, 30).build_and_execute(|| {
+						// given initial state,
+						assert!(barrel_staking::Delegators::<Runtime>::get(42).is_some());
+
+						// when processing block 1 and 2, nothing will happen.
+						next_block();
+						next_block();
+						assert!(ActiveValidators::<Runtime>::get().is_empty());
+
+						// when processing block 3, new validators will be selected.
+						next_block();
+						assert_eq!(ActiveValidators::<Runtime>::get(), vec![1, 3]);
+						assert_eq!(
+							staking_events(),
+							vec![Event::NewValidatorSet { era: 1, validators: vec![1, 3] }]
+						);
+					})
+				}
+
+				#[test]
+				fn selects_right_number_of_validators() {
+					ExtBuilder::default().build_and_execute(|| {
+						// when processing block 1 and 2, nothing will happen.
+						next_block();
+						next_block();
+						assert!(ActiveValidators::<Runtime>::get().is_empty());
+
+						// set the `Get` implementor static test variable to 3.
+						ValidatorCount::set(3);
+
+						next_block();
+						assert_eq!(ActiveValidators::<Runtime>::get(), vec![3, 2, 1]);
+						assert_eq!(
+							staking_events(),
+							vec![Event::NewValidatorSet { era: 1, validators: vec![3, 2, 1] }]
+						);
+
+						// this time, set to 1.
+						next_block();
+						next_block();
+						assert_eq!(ActiveValidators::<Runtime>::get(), vec![3, 2, 1]);
+						assert!(staking_events().is_empty());
+
+						ValidatorCount::set(1);
+						next_block();
+						assert_eq!(ActiveValidators::<Runtime>::get(), vec![3]);
+						assert_eq!(
+							staking_events(),
+							vec![Event::NewValidatorSet { era: 2, validators: vec![3] }]
+						);
+					})
+				}
+
+				fn next_era() {
+					for _ in 0..EraDuration::get() {
+						next_block();
+					}
+				}
+
+				#[test]
+				fn unregister_unbonds_own_stake() {
+					ExtBuilder::default().build_and_execute(|| {
+						// given 3 is active in era 1.
+						next_era();
+						assert_eq!(CurrentEra::<Runtime>::get(), 1);
+						assert_eq!(ActiveValidators::<Runtime>::get(), vec![3, 2]);
+						assert_eq!(
+							staking_events(),
+							vec![Event::NewValidatorSet { era: 1, validators: vec![3, 2] }]
+						);
+
+						// when:
+						assert_ok!(barrel_staking::Barrel::<Runtime>::unregister(
+							RuntimeOrigin::signed(3)
+						));
+
+						// then it stops being a validator right away, but its stake is still held.
+						assert_eq!(Validators::<Runtime>::get(3), None);
+						assert_eq!(ActiveValidators::<Runtime>::get(), vec![2]);
+						assert_eq!(held(HoldReason::ValidatorBond, 3), 0);
+						assert_eq!(held(HoldReason::Unbonding, 3), 30);
+						assert_eq!(
+							Unbonding::<Runtime>::get(3),
+							vec![UnbondingChunk { amount: 30, era: 3 }]
+						);
+						assert_eq!(
+							staking_events(),
+							vec![
+								Event::ValidatorUnregistered { who: 3 },
+								Event::Unbonded { who: 3, amount: 30, era: 3 }
+							]
+						);
+						assert_noop!(
+							barrel_staking::Barrel::<Runtime>::unregister(RuntimeOrigin::signed(3)),
+							Error::<Runtime>::NotRegistered
+						);
+
+						// when, then it is not elected anymore, and cannot withdraw before era 3.
+						next_era();
+						assert_eq!(ActiveValidators::<Runtime>::get(), vec![2, 1]);
+						assert_eq!(
+							staking_events(),
+							vec![Event::NewValidatorSet { era: 2, validators: vec![2, 1] }]
+						);
+						assert_noop!(
+							barrel_staking::Barrel::<Runtime>::withdraw_unbonded(
+								RuntimeOrigin::signed(3)
+							),
+							Error::<Runtime>::NothingToWithdraw
+						);
+
+						// when:
+						next_era();
+						assert_ok!(barrel_staking::Barrel::<Runtime>::withdraw_unbonded(
+							RuntimeOrigin::signed(3)
+						));
+
+						// then:
+						assert_eq!(held(HoldReason::Unbonding, 3), 0);
+						assert_eq!(StakeCurrency::balance(&3), 30 + ExistentialDeposit::get());
+						assert!(Unbonding::<Runtime>::get(3).is_empty());
+						assert_eq!(
+							staking_events(),
+							vec![
+								Event::NewValidatorSet { era: 3, validators: vec![2, 1] },
+								Event::Withdrawn { who: 3, amount: 30 }
+							]
+						);
+					})
+				}
+
+				#[test]
+				fn undelegate_unbonds_delegation() {
+					ExtBuilder::default().add_delegator(42, 1, 30).build_and_execute(|| {
+						// when, then 42 cannot undelegate more than it delegated.
+						assert_noop!(
+							barrel_staking::Barrel::<Runtime>::undelegate(
+								RuntimeOrigin::signed(42),
+								1,
+								31
+							),
+							Error::<Runtime>::InsufficientStake
+						);
+
+						// when half of it is undelegated in era 0:
+						assert_ok!(barrel_staking::Barrel::<Runtime>::undelegate(
+							RuntimeOrigin::signed(42),
+							1,
+							15
+						));
+
+						// then 1 is backed by the other half only.
+						assert_eq!(Delegators::<Runtime>::get(42), Some(15));
+						assert_eq!(
+							Validators::<Runtime>::get(1),
+							Some(ValidatorStake { own: 10, delegated: 15 })
+						);
+						assert_eq!(
+							staking_events(),
+							vec![
+								Event::Undelegated { who: 42, from: 1, amount: 15 },
+								Event::Unbonded { who: 42, amount: 15, era: 2 }
+							]
+						);
+						next_era();
+						assert_eq!(
+							staking_events(),
+							vec![Event::NewValidatorSet { era: 1, validators: vec![3, 1] }]
+						);
+
+						// when the rest is undelegated in era 1:
+						assert_ok!(barrel_staking::Barrel::<Runtime>::undelegate(
+							RuntimeOrigin::signed(42),
+							1,
+							15
+						));
 
-		impl barrel_currency::Config for Runtime {}
+						// then 42 is no longer a delegator, and its stake unbonds in two chunks.
+						assert_eq!(Delegators::<Runtime>::get(42), None);
+						assert_eq!(Delegations::<Runtime>::get(42, 1), None);
+						assert_eq!(
+							Validators::<Runtime>::get(1),
+							Some(ValidatorStake { own: 10, delegated: 0 })
+						);
+						assert_eq!(held(HoldReason::Delegation, 42), 0);
+						assert_eq!(held(HoldReason::Unbonding, 42), 30);
+						assert_eq!(
+							Unbonding::<Runtime>::get(42),
+							vec![
+								UnbondingChunk { amount: 15, era: 2 },
+								UnbondingChunk { amount: 15, era: 3 }
+							]
+						);
+						assert_noop!(
+							barrel_staking::Barrel::<Runtime>::undelegate(
+								RuntimeOrigin::signed(42),
+								1,
+								1
+							),
+							Error::<Runtime>::NotDelegator
+						);
 
-		struct ExtBuilder {
-			validators: Vec<(AccountId, Balance)>,
-			delegators: Vec<(AccountId, AccountId, Balance)>,
-			balances: Vec<(AccountId, Balance)>,
+						// when it delegates and undelegates again in era 2, then it has too many
+						// chunks.
+						next_era();
+						assert_ok!(StakeCurrency::mint_into(&42, 5));
+						assert_ok!(barrel_staking::Barrel::<Runtime>::delegate(
+							RuntimeOrigin::signed(42),
+							1,
+							5
+						));
+						assert_noop!(
+							barrel_staking::Barrel::<Runtime>::undelegate(
+								RuntimeOrigin::signed(42),
+								1,
+								5
+							),
+							Error::<Runtime>::TooManyUnbondingChunks
+						);
+
+						// when the first chunk is withdrawn, then there is room again.
+						assert_ok!(barrel_staking::Barrel::<Runtime>::withdraw_unbonded(
+							RuntimeOrigin::signed(42)
+						));
+						assert_eq!(held(HoldReason::Unbonding, 42), 15);
+						assert_ok!(barrel_staking::Barrel::<Runtime>::undelegate(
+							RuntimeOrigin::signed(42),
+							1,
+							5
+						));
+						assert_eq!(
+							Unbonding::<Runtime>::get(42),
+							vec![
+								UnbondingChunk { amount: 15, era: 3 },
+								UnbondingChunk { amount: 5, era: 4 }
+							]
+						);
+						assert_eq!(
+							staking_events(),
+							vec![
+								Event::Undelegated { who: 42, from: 1, amount: 15 },
+								Event::Unbonded { who: 42, amount: 15, era: 3 },
+								Event::NewValidatorSet { era: 2, validators: vec![3, 2] },
+								Event::Delegated { who: 42, to: 1, amount: 5 },
+								Event::Withdrawn { who: 42, amount: 15 },
+								Event::Undelegated { who: 42, from: 1, amount: 5 },
+								Event::Unbonded { who: 42, amount: 5, era: 4 }
+							]
+						);
+					})
+				}
+
+				#[test]
+				fn unregistered_validator_is_kept_while_backed() {
+					ExtBuilder::default().add_delegator(42, 1, 30).build_and_execute(|| {
+						// when 1 unregisters while 42 still delegates to it:
+						assert_ok!(barrel_staking::Barrel::<Runtime>::unregister(
+							RuntimeOrigin::signed(1)
+						));
+
+						// then it is kept, but can neither be delegated to nor elected.
+						assert_eq!(
+							Validators::<Runtime>::get(1),
+							Some(ValidatorStake { own: 0, delegated: 30 })
+						);
+						assert!(!barrel_staking::Barrel::<Runtime>::is_registered(&1));
+						assert_eq!(
+							staking_events(),
+							vec![
+								Event::ValidatorUnregistered { who: 1 },
+								Event::Unbonded { who: 1, amount: 10, era: 2 }
+							]
+						);
+						assert_ok!(StakeCurrency::mint_into(&5, 100));
+						assert_noop!(
+							barrel_staking::Barrel::<Runtime>::delegate(
+								RuntimeOrigin::signed(5),
+								1,
+								10
+							),
+							Error::<Runtime>::NotRegistered
+						);
+						next_era();
+						assert_eq!(ActiveValidators::<Runtime>::get(), vec![3, 2]);
+						assert_eq!(
+							staking_events(),
+							vec![Event::NewValidatorSet { era: 1, validators: vec![3, 2] }]
+						);
+
+						// when it registers again, then it keeps its delegations.
+						assert_ok!(StakeCurrency::mint_into(&1, 5));
+						assert_ok!(barrel_staking::Barrel::<Runtime>::register(
+							RuntimeOrigin::signed(1),
+							5
+						));
+						assert_eq!(
+							Validators::<Runtime>::get(1),
+							Some(ValidatorStake { own: 5, delegated: 30 })
+						);
+						assert_eq!(
+							staking_events(),
+							vec![Event::ValidatorRegistered { who: 1, amount: 5 }]
+						);
+
+						// when it unregisters again, and 42 undelegates everything, then it is
+						// removed.
+						assert_ok!(barrel_staking::Barrel::<Runtime>::unregister(
+							RuntimeOrigin::signed(1)
+						));
+						assert_ok!(barrel_staking::Barrel::<Runtime>::undelegate(
+							RuntimeOrigin::signed(42),
+							1,
+							30
+						));
+						assert_eq!(Validators::<Runtime>::get(1), None);
+						assert_eq!(
+							staking_events(),
+							vec![
+								Event::ValidatorUnregistered { who: 1 },
+								Event::Unbonded { who: 1, amount: 5, era: 3 },
+								Event::Undelegated { who: 42, from: 1, amount: 30 },
+								Event::Unbonded { who: 42, amount: 30, era: 3 }
+							]
+						);
+					})
+				}
+
+				#[test]
+				fn pays_era_rewards_in_pages() {
+					ExtBuilder::default()
+						.add_delegator(42, 2, 10)
+						.add_delegator(43, 2, 10)
+						.add_delegator(44, 2, 10)
+						.build_and_execute(|| {
+							// given 2 takes a commission of 10% from era 1 on, in which it is
+							// selected along with 3.
+							assert_ok!(barrel_staking::Barrel::<Runtime>::set_commission(
+								RuntimeOrigin::signed(2),
+								Perbill::from_percent(10)
+							));
+							next_era();
+							assert_eq!(ActiveValidators::<Runtime>::get(), vec![2, 3]);
+							assert_eq!(
+								ErasStakersOverview::<Runtime>::get(1, 2),
+								Some(ExposureOverview {
+									total: 50,
+									own: 20,
+									commission: Perbill::from_percent(10),
+									page_count: 2
+								})
+							);
+
+							// when era 1 ends with 800 in the reward pot:
+							assert_ok!(StakeCurrency::mint_into(
+								&RewardPot::get(),
+								800 + ExistentialDeposit::get()
+							));
+							assert_noop!(
+								barrel_staking::Barrel::<Runtime>::payout_era(
+									RuntimeOrigin::signed(1),
+									2,
+									1
+								),
+								Error::<Runtime>::EraNotEnded
+							);
+							staking_events();
+							next_era();
+
+							// then all of it is the reward of era 1.
+							assert_eq!(
+								staking_events(),
+								vec![
+									Event::EraPaid { era: 1, reward: 800 },
+									Event::NewValidatorSet { era: 2, validators: vec![2, 3] }
+								]
+							);
+							assert_eq!(UnclaimedRewards::<Runtime>::get(), 800);
+
+							// when the first page of 2 is paid:
+							let balance = |who| StakeCurrency::balance(&who);
+							let delegators = || balance(42) + balance(43) + balance(44);
+							assert_ok!(barrel_staking::Barrel::<Runtime>::payout_era(
+								RuntimeOrigin::signed(1),
+								2,
+								1
+							));
+
+							// then 2 is paid its commission of 10% of its 500, and 40% of the
+							// remaining 450. Two of its delegators are paid 20% of it each.
+							assert_eq!(balance(2), ExistentialDeposit::get() + 230);
+							assert_eq!(delegators(), 3 * ExistentialDeposit::get() + 180);
+							let events = staking_events();
+							assert_eq!(events.len(), 3);
+							assert_eq!(events[0], Event::Rewarded { era: 1, who: 2, amount: 230 });
+
+							// when the second page is paid, then the last delegator is paid too.
+							assert_ok!(barrel_staking::Barrel::<Runtime>::payout_era(
+								RuntimeOrigin::signed(1),
+								2,
+								1
+							));
+							assert_eq!(delegators(), 3 * ExistentialDeposit::get() + 270);
+							assert_noop!(
+								barrel_staking::Barrel::<Runtime>::payout_era(
+									RuntimeOrigin::signed(1),
+									2,
+									1
+								),
+								Error::<Runtime>::AlreadyPaid
+							);
+
+							// when 3 is paid, then the pot is empty.
+							assert_ok!(barrel_staking::Barrel::<Runtime>::payout_era(
+								RuntimeOrigin::signed(1),
+								3,
+								1
+							));
+							assert_eq!(balance(3), ExistentialDeposit::get() + 300);
+							assert_eq!(balance(RewardPot::get()), ExistentialDeposit::get());
+							assert_eq!(UnclaimedRewards::<Runtime>::get(), 0);
+							assert_noop!(
+								barrel_staking::Barrel::<Runtime>::payout_era(
+									RuntimeOrigin::signed(1),
+									1,
+									1
+								),
+								Error::<Runtime>::NotExposed
+							);
+						})
+				}
+
+				#[test]
+				fn expired_exposures_are_removed_over_several_blocks() {
+					ExtBuilder::default().build_and_execute(|| {
+						// given 3 and 2 are selected in era 1, with one page each.
+						next_era();
+						let entries = || {
+							ErasStakersOverview::<Runtime>::iter_prefix(1).count() +
+								ErasStakersPaged::<Runtime>::iter_prefix((1,)).count()
+						};
+						assert_eq!(entries(), 4);
+
+						// when era 1 expires as era 4 starts:
+						next_era();
+						next_era();
+						next_era();
+
+						// then only 2 of its entries are removed per block.
+						assert_eq!(entries(), 2);
+						assert_eq!(
+							ExposureCleanup::<Runtime>::get().map(|cleanup| cleanup.era),
+							Some(1)
+						);
+						next_block();
+						assert_eq!(entries(), 0);
+						next_block();
+						assert_eq!(ExposureCleanup::<Runtime>::get(), None);
+					})
+				}
+
+				#[test]
+				fn unclaimed_rewards_expire() {
+					ExtBuilder::default().build_and_execute(|| {
+						// given 3 and 2 are selected in era 1, which ends with 100 in the reward
+						// pot, and 3 claims its part of it.
+						next_era();
+						assert_ok!(StakeCurrency::mint_into(
+							&RewardPot::get(),
+							100 + ExistentialDeposit::get()
+						));
+						next_era();
+						assert_ok!(barrel_staking::Barrel::<Runtime>::payout_era(
+							RuntimeOrigin::signed(1),
+							3,
+							1
+						));
+						assert_eq!(UnclaimedRewards::<Runtime>::get(), 40);
+						staking_events();
+
+						// when era 2 ends, then nothing is left in the pot for it.
+						next_era();
+						assert_eq!(
+							staking_events(),
+							vec![Event::NewValidatorSet { era: 3, validators: vec![3, 2] }]
+						);
+
+						// when era 3 ends, more than 2 eras after era 1:
+						next_era();
+
+						// then the part of 2 is not claimable anymore, and is the reward of era 3.
+						assert_noop!(
+							barrel_staking::Barrel::<Runtime>::payout_era(
+								RuntimeOrigin::signed(1),
+								2,
+								1
+							),
+							Error::<Runtime>::EraExpired
+						);
+						assert_eq!(ErasRewards::<Runtime>::get(1), None);
+						assert_eq!(ErasStakersOverview::<Runtime>::get(1, 2), None);
+						assert_eq!(
+							staking_events(),
+							vec![
+								Event::EraPaid { era: 3, reward: 40 },
+								Event::NewValidatorSet { era: 4, validators: vec![3, 2] }
+							]
+						);
+						assert_ok!(barrel_staking::Barrel::<Runtime>::payout_era(
+							RuntimeOrigin::signed(1),
+							2,
+							3
+						));
+						assert_eq!(StakeCurrency::balance(&2), ExistentialDeposit::get() + 16);
+					})
+				}
+
+				#[test]
+				fn commission_changes_are_bounded() {
+					ExtBuilder::default().build_and_execute(|| {
+						let set_commission = |who, percent| {
+							barrel_staking::Barrel::<Runtime>::set_commission(
+								RuntimeOrigin::signed(who),
+								Perbill::from_percent(percent),
+							)
+						};
+
+						// when, then only validators can set a commission, by at most 10% at once.
+						assert_noop!(set_commission(42, 5), Error::<Runtime>::NotRegistered);
+						assert_noop!(
+							set_commission(1, 11),
+							Error::<Runtime>::CommissionChangeTooLarge
+						);
+						assert_ok!(set_commission(1, 10));
+
+						// when, then it can only change once per era.
+						assert_noop!(
+							set_commission(1, 5),
+							Error::<Runtime>::CommissionAlreadyChanged
+						);
+						next_era();
+						assert_noop!(
+							set_commission(1, 25),
+							Error::<Runtime>::CommissionChangeTooLarge
+						);
+						assert_ok!(set_commission(1, 20));
+						assert_eq!(
+							Commissions::<Runtime>::get(1),
+							Some(ValidatorCommission {
+								rate: Perbill::from_percent(20),
+								changed_in: 1
+							})
+						);
+						assert_eq!(
+							staking_events(),
+							vec![
+								Event::CommissionSet {
+									who: 1,
+									commission: Perbill::from_percent(10)
+								},
+								Event::NewValidatorSet { era: 1, validators: vec![3, 2] },
+								Event::CommissionSet {
+									who: 1,
+									commission: Perbill::from_percent(20)
+								}
+							]
+						);
+
+						// when it unregisters, then its commission is forgotten.
+						assert_ok!(barrel_staking::Barrel::<Runtime>::unregister(
+							RuntimeOrigin::signed(1)
+						));
+						assert_eq!(Commissions::<Runtime>::get(1), None);
+					})
+				}
+
+				#[test]
+				fn offences_are_slashed_pro_rata_after_a_delay() {
+					ExtBuilder::default()
+						.add_delegator(42, 2, 20)
+						.add_delegator(43, 2, 10)
+						.add_delegator(43, 1, 10)
+						.build_and_execute(|| {
+							// given 2 and 3 are selected in era 1, with 50 behind 2.
+							next_era();
+							assert_eq!(ActiveValidators::<Runtime>::get(), vec![2, 3]);
+							staking_events();
+
+							// when, then only root can report an offence, of a selected validator.
+							let report = |origin, validator, percent| {
+								barrel_staking::Barrel::<Runtime>::report_offence(
+									origin,
+									validator,
+									Perbill::from_percent(percent),
+								)
+							};
+							assert_noop!(
+								report(RuntimeOrigin::signed(1), 2, 50),
+								DispatchError::BadOrigin
+							);
+							assert_noop!(
+								report(RuntimeOrigin::root(), 1, 50),
+								Error::<Runtime>::NotExposed
+							);
+							assert_ok!(report(RuntimeOrigin::root(), 2, 50));
+
+							// then 2 is removed from the active validators, and chilled.
+							assert_eq!(ActiveValidators::<Runtime>::get(), vec![3]);
+							assert_eq!(Chilled::<Runtime>::get(2), Some(1));
+							assert_eq!(
+								UnappliedSlashes::<Runtime>::get(1, 2),
+								Some(Perbill::from_percent(50))
+							);
+							assert_eq!(
+								staking_events(),
+								vec![Event::OffenceReported {
+									validator: 2,
+									era: 1,
+									fraction: Perbill::from_percent(50)
+								}]
+							);
+							assert_ok!(StakeCurrency::mint_into(&42, 5));
+							assert_noop!(
+								barrel_staking::Barrel::<Runtime>::delegate_more(
+									RuntimeOrigin::signed(42),
+									2,
+									5
+								),
+								Error::<Runtime>::ValidatorChilled
+							);
+
+							// when 42 undelegates all of its stake from 2, and era 2 starts:
+							assert_ok!(barrel_staking::Barrel::<Runtime>::undelegate(
+								RuntimeOrigin::signed(42),
+								2,
+								20
+							));
+							staking_events();
+							next_era();
+
+							// then half of the stake behind 2 in era 1 is slashed, including the
+							// stake that 42 is unbonding, and none of the delegation of 43 to 1.
+							let events = staking_events();
+							assert_eq!(events.len(), 4);
+							assert_eq!(
+								events[0],
+								Event::Slashed { who: 2, validator: 2, amount: 10 }
+							);
+							assert!(events.contains(&Event::Slashed {
+								who: 42,
+								validator: 2,
+								amount: 10
+							}));
+							assert!(events.contains(&Event::Slashed {
+								who: 43,
+								validator: 2,
+								amount: 5
+							}));
+							assert_eq!(
+								events[3],
+								Event::NewValidatorSet { era: 2, validators: vec![3, 1] }
+							);
+							assert_eq!(StakeCurrency::balance(&SlashReceiver::get()), 25);
+							assert_eq!(UnappliedSlashes::<Runtime>::get(1, 2), None);
+
+							assert_eq!(held(HoldReason::ValidatorBond, 2), 10);
+							assert_eq!(
+								Validators::<Runtime>::get(2),
+								Some(ValidatorStake { own: 10, delegated: 5 })
+							);
+							assert_eq!(held(HoldReason::Unbonding, 42), 10);
+							assert_eq!(
+								Unbonding::<Runtime>::get(42).into_inner(),
+								vec![UnbondingChunk { amount: 10, era: 3 }]
+							);
+							assert_eq!(held(HoldReason::Delegation, 43), 15);
+							assert_eq!(Delegations::<Runtime>::get(43, 2), Some(5));
+							assert_eq!(Delegations::<Runtime>::get(43, 1), Some(10));
+							assert_eq!(barrel_staking::Barrel::<Runtime>::total_delegated(&43), 15);
+						})
+				}
+
+				#[test]
+				fn unbonding_after_an_offence_is_still_slashed() {
+					ExtBuilder::default().build_and_execute(|| {
+						// given 3 is reported in era 1.
+						next_era();
+						assert_ok!(barrel_staking::Barrel::<Runtime>::report_offence(
+							RuntimeOrigin::root(),
+							3,
+							Perbill::from_percent(50),
+						));
+
+						// when 3 unregisters right away, and era 2 starts:
+						assert_ok!(barrel_staking::Barrel::<Runtime>::unregister(
+							RuntimeOrigin::signed(3)
+						));
+						next_era();
+
+						// then half of its unbonding stake is slashed, before it can be withdrawn.
+						assert_eq!(held(HoldReason::Unbonding, 3), 15);
+						assert_eq!(
+							Unbonding::<Runtime>::get(3).into_inner(),
+							vec![UnbondingChunk { amount: 15, era: 3 }]
+						);
+						assert_noop!(
+							barrel_staking::Barrel::<Runtime>::withdraw_unbonded(
+								RuntimeOrigin::signed(3)
+							),
+							Error::<Runtime>::NothingToWithdraw
+						);
+
+						// when era 3 starts, then only the rest can be withdrawn.
+						next_era();
+						assert_ok!(barrel_staking::Barrel::<Runtime>::withdraw_unbonded(
+							RuntimeOrigin::signed(3)
+						));
+						assert_eq!(held(HoldReason::Unbonding, 3), 0);
+						assert_eq!(StakeCurrency::balance(&SlashReceiver::get()), 15);
+					})
+				}
+
+				#[test]
+				fn stake_cannot_be_redelegated_away_from_a_pending_slash() {
+					ExtBuilder::default().add_delegator(42, 2, 20).build_and_execute(|| {
+						// given 2 is reported in era 1, with 20 of 42 behind it.
+						next_era();
+						assert_ok!(barrel_staking::Barrel::<Runtime>::report_offence(
+							RuntimeOrigin::root(),
+							2,
+							Perbill::from_percent(50),
+						));
+
+						// when, then 42 cannot move its delegation to 1.
+						let redelegate = || {
+							barrel_staking::Barrel::<Runtime>::redelegate(
+								RuntimeOrigin::signed(42),
+								2,
+								1,
+								20,
+							)
+						};
+						assert_noop!(redelegate(), Error::<Runtime>::PendingSlash);
+
+						// when era 2 starts, then the slash applies to its delegation, and the
+						// rest can be moved.
+						next_era();
+						assert_eq!(Delegations::<Runtime>::get(42, 2), Some(10));
+						assert_eq!(held(HoldReason::Delegation, 42), 10);
+						assert_noop!(redelegate(), Error::<Runtime>::InsufficientStake);
+						assert_ok!(barrel_staking::Barrel::<Runtime>::redelegate(
+							RuntimeOrigin::signed(42),
+							2,
+							1,
+							10,
+						));
+						assert_eq!(Delegations::<Runtime>::get(42, 1), Some(10));
+					})
+				}
+
+				#[test]
+				fn deferred_slashes_can_be_cancelled() {
+					ExtBuilder::default().build_and_execute(|| {
+						// given 3 is reported twice in era 1.
+						next_era();
+						staking_events();
+						let report = |percent| {
+							barrel_staking::Barrel::<Runtime>::report_offence(
+								RuntimeOrigin::root(),
+								3,
+								Perbill::from_percent(percent),
+							)
+						};
+						assert_ok!(report(20));
+						assert_ok!(report(10));
+						assert_eq!(
+							UnappliedSlashes::<Runtime>::get(1, 3),
+							Some(Perbill::from_percent(20))
+						);
+
+						// when, then only the admin origin can cancel the slash, once.
+						let cancel = |origin, era| {
+							barrel_staking::Barrel::<Runtime>::cancel_deferred_slash(origin, era, 3)
+						};
+						assert_noop!(cancel(RuntimeOrigin::signed(1), 1), DispatchError::BadOrigin);
+						assert_noop!(
+							cancel(RuntimeOrigin::root(), 0),
+							Error::<Runtime>::NoUnappliedSlash
+						);
+						assert_ok!(cancel(RuntimeOrigin::root(), 1));
+						assert_noop!(
+							cancel(RuntimeOrigin::root(), 1),
+							Error::<Runtime>::NoUnappliedSlash
+						);
+						assert_eq!(
+							staking_events(),
+							vec![
+								Event::OffenceReported {
+									validator: 3,
+									era: 1,
+									fraction: Perbill::from_percent(20)
+								},
+								Event::OffenceReported {
+									validator: 3,
+									era: 1,
+									fraction: Perbill::from_percent(10)
+								},
+								Event::SlashCancelled { validator: 3, era: 1 }
+							]
+						);
+
+						// when era 2 starts, then nothing is slashed, but 3 is still chilled.
+						next_era();
+						assert_eq!(held(HoldReason::ValidatorBond, 3), 30);
+						assert_eq!(
+							staking_events(),
+							vec![Event::NewValidatorSet { era: 2, validators: vec![2, 1] }]
+						);
+
+						// when 3 unregisters and registers again, then it can be selected again.
+						assert_ok!(barrel_staking::Barrel::<Runtime>::unregister(
+							RuntimeOrigin::signed(3)
+						));
+						assert_ok!(StakeCurrency::mint_into(&3, 40));
+						assert_ok!(barrel_staking::Barrel::<Runtime>::register(
+							RuntimeOrigin::signed(3),
+							40
+						));
+						assert_eq!(Chilled::<Runtime>::get(3), None);
+						staking_events();
+						next_era();
+						assert_eq!(
+							staking_events(),
+							vec![Event::NewValidatorSet { era: 3, validators: vec![3, 2] }]
+						);
+					})
+				}
+			};
 		}
 
-		impl Default for ExtBuilder {
-			fn default() -> Self {
-				let instance = Self {
-					validators: Default::default(),
-					delegators: Default::default(),
-					balances: Default::default(),
-				};
-				instance.add_validator(1, 10).add_validator(2, 20).add_validator(3, 30)
+		/// Staking, using the tutorial currency barrel.
+		pub(crate) mod with_currency {
+			use super::*;
+			use crate::currency::barrel as barrel_currency;
+
+			construct_runtime!(
+				pub struct Runtime {
+					System: frame_system,
+					Currency: barrel_currency,
+					Staking: barrel_staking,
+				}
+			);
+
+			#[derive_impl(frame_system::config_preludes::TestDefaultConfig as frame_system::DefaultConfig)]
+			impl frame_system::Config for Runtime {
+				type Block = MockBlock<Runtime>;
+				type AccountData = barrel_currency::AccountData;
+			}
+
+			// TODO: if we were to have private `struct` runtime, then these would also not need to
+			// be pub.
+			parameter_types! {
+				pub static ValidatorCount: u32 = 2;
+				pub static MaxValidators: u32 = 8;
+				pub static MaxDelegators: u32 = 8;
+				pub const EraDuration: BlockNumberFor<Runtime> = 3;
+				pub const BondingDuration: EraIndex = 2;
+				pub const RewardPot: u64 = 99;
+				pub const SlashReceiver: u64 = 98;
+				pub const MaxCommissionChange: Perbill = Perbill::from_percent(10);
+				pub const ExistentialDeposit: barrel_currency::Balance = 1;
 			}
-		}
 
-		impl ExtBuilder {
-			fn add_validator(mut self, validator: AccountId, self_stake: Balance) -> Self {
-				self.balances.push((validator, self_stake));
-				self.validators.push((validator, self_stake));
-				self
+			impl barrel_staking::Config for Runtime {
+				type RuntimeEvent = RuntimeEvent;
+				type Currency = Currency;
+				type RuntimeHoldReason = RuntimeHoldReason;
+				type ValidatorCount = ValidatorCount;
+				type EraDuration = EraDuration;
+				type BondingDuration = BondingDuration;
+				type MaxUnbondingChunks = ConstU32<2>;
+				type MaxDelegations = ConstU32<2>;
+				type MaxValidators = MaxValidators;
+				type MaxDelegators = MaxDelegators;
+				type RewardPot = RewardPot;
+				type HistoryDepth = ConstU32<2>;
+				type MaxExposurePageSize = ConstU32<2>;
+				type MaxCommissionChange = MaxCommissionChange;
+				type SlashDeferDuration = ConstU32<1>;
+				type AdminOrigin = frame_system::EnsureRoot<Self::AccountId>;
+				type OnSlash = SlashTo<SlashReceiver>;
+				type WeightInfo = ();
 			}
 
-			fn add_delegator(
-				mut self,
-				delegator: AccountId,
-				delegatee: AccountId,
-				stake: Balance,
-			) -> Self {
-				self.balances.push((delegator, stake));
-				self.delegators.push((delegator, delegatee, stake));
-				self
+			impl barrel_currency::Config for Runtime {
+				type RuntimeEvent = RuntimeEvent;
+				type MintOrigin = frame_system::EnsureRoot<Self::AccountId>;
+				type FreezeOrigin = frame_system::EnsureRoot<Self::AccountId>;
+				type ExistentialDeposit = ExistentialDeposit;
+				type DustRemoval = ();
+				type RuntimeHoldReason = RuntimeHoldReason;
+				type MaxVestingSchedules = ConstU32<1>;
+				type MaxTransfers = ConstU32<4>;
+				type MaxScheduledPerBlock = ConstU32<4>;
+				type MaxAgendaBlocks = ConstU32<4>;
+				type MaxReleasesPerBlock = ConstU32<4>;
+				type WeightInfo = ();
+				type AccountStore = System;
+				type EraDuration = EraDuration;
+				type BlocksPerYear = ConstU64<100>;
+				type YearlyInflation = ();
+				type OnInflation = ();
+				type TransferFee = ();
+				type FeeBurn = ();
+				type FeeCollector = ();
+				type OnTransfer = ();
+				type OnBalanceChanged = Staking;
+				type OnAccountCreated = ();
+				type OnAccountKilled = ();
 			}
 
-			fn build_and_execute(self, test: impl FnOnce() -> ()) {
-				// In this example, we care about the order of genesis-initialization, so we use the
-				// alternative syntax.
+			pub(crate) fn genesis(
+				balances: Vec<(u64, barrel_currency::Balance)>,
+				validators: Vec<(u64, barrel_currency::Balance)>,
+				delegators: Vec<(u64, u64, barrel_currency::Balance)>,
+			) -> RuntimeGenesisConfig {
+				// In this example, we care about the order of genesis-initialization, so we use
+				// the alternative syntax.
 				// let mut storage: Storage = Default::default();
 				// frame_system::GenesisConfig::default()
 				// 	.assimilate_storage::<Runtime>(&mut storage)
@@ -211,108 +2764,354 @@ pub mod barrel {
 				// let mut ext = TestState::new(storage);
 
 				let system = frame_system::GenesisConfig::default();
-				let currency = barrel_currency::GenesisConfig { balances: self.balances };
-				let staking = barrel_staking::GenesisConfig {
-					validators: self.validators,
-					delegators: self.delegators,
-				};
-				let runtime_genesis = RuntimeGenesisConfig { system, currency, staking };
-				let mut ext = TestState::new(runtime_genesis.build_storage().unwrap());
+				let currency = barrel_currency::GenesisConfig { balances, vesting: vec![] };
+				let staking = barrel_staking::GenesisConfig { validators, delegators };
+				RuntimeGenesisConfig { system, currency, staking }
+			}
 
-				// process block 0 to simulate a proper genesis. Not mandatory to be done this way.
-				// This sets the current block number (to be executed) to 1.
-				ext.execute_with(next_block);
-				ext.execute_with(test);
+			#[allow(unused)]
+			pub(crate) fn test_state_new() -> TestState {
+				TestState::new(genesis(vec![], vec![], vec![]).build_storage().unwrap())
 			}
-		}
 
-		fn next_block() {
-			let now = frame_system::Barrel::<Runtime>::block_number();
-			barrel_staking::Barrel::<Runtime>::on_initialize(now);
-			frame_system::Barrel::<Runtime>::set_block_number(now + 1);
-		}
+			staking_tests!();
 
-		#[test]
-		fn basic_setup_works() {
-			ExtBuilder::default().build_and_execute(|| {
-				assert_eq!(frame_system::Barrel::<Runtime>::block_number(), 1);
-				assert_eq!(
-					Validators::<Runtime>::get(1).unwrap(),
-					ValidatorStake { own: 10, delegated: 0 }
-				);
-				assert_eq!(
-					Validators::<Runtime>::get(2).unwrap(),
-					ValidatorStake { own: 20, delegated: 0 }
-				);
-				assert_eq!(
-					Validators::<Runtime>::get(3).unwrap(),
-					ValidatorStake { own: 30, delegated: 0 }
-				);
-				assert_eq!(Validators::<Runtime>::iter().count(), 3);
-				assert!(ActiveValidators::<Runtime>::get().is_empty());
-			})
-		}
+			#[test]
+			fn cannot_stake_vesting_funds() {
+				ExtBuilder::default().build_and_execute(|| {
+					// given 7 has 50, all of which is locked by vesting.
+					assert_ok!(StakeCurrency::mint_into(&5, 100));
+					let schedule = barrel_currency::VestingSchedule {
+						locked: 50,
+						per_block: 1,
+						starting_block: 100,
+					};
+					assert_ok!(barrel_currency::Barrel::<Runtime>::vested_transfer(
+						RuntimeOrigin::signed(5),
+						7,
+						schedule
+					));
 
-		#[test]
-		fn selects_validators() {
-			ExtBuilder::default().build_and_execute(|| {
-				// given initial state,
+					// when, then 7 cannot stake any of it.
+					assert_noop!(
+						barrel_staking::Barrel::<Runtime>::register(RuntimeOrigin::signed(7), 10),
+						Error::<Runtime>::InsufficientFunds
+					);
+					assert!(staking_events().is_empty());
+				})
+			}
 
-				// when processing block 1, nothing will happen.
-				next_block();
-				assert!(ActiveValidators::<Runtime>::get().is_empty());
+			#[test]
+			fn stake_follows_slashed_holds() {
+				ExtBuilder::default()
+					.add_delegator(42, 1, 30)
+					.add_delegator(43, 1, 20)
+					.add_delegator(44, 2, 10)
+					.add_delegator(44, 3, 10)
+					.build_and_execute(|| {
+						// given:
+						assert_eq!(
+							Validators::<Runtime>::get(1),
+							Some(ValidatorStake { own: 10, delegated: 50 })
+						);
 
-				// when processing block 2, nothing will happen.
-				next_block();
-				assert!(ActiveValidators::<Runtime>::get().is_empty());
+						// when part of the stake of 1 and 42 is slashed outside of staking.
+						let slash = |reason: HoldReason, who, amount| {
+							barrel_currency::Barrel::<Runtime>::slash_reserved(
+								reason.into(),
+								&who,
+								amount,
+							)
+						};
+						assert_eq!(slash(HoldReason::ValidatorBond, 1, 4), 4);
+						assert_eq!(slash(HoldReason::Delegation, 42, 10), 10);
 
-				// when processing block 3, new validators will be selected.
-				next_block();
-				assert_eq!(ActiveValidators::<Runtime>::get(), vec![3, 2]);
-			})
+						// then:
+						assert_eq!(
+							Validators::<Runtime>::get(1),
+							Some(ValidatorStake { own: 6, delegated: 40 })
+						);
+						assert_eq!(Delegators::<Runtime>::get(42), Some(20));
+
+						// when all of the stake of 43 is slashed, then it is no longer a
+						// delegator.
+						assert_eq!(slash(HoldReason::Delegation, 43, 20), 20);
+						assert_eq!(Delegators::<Runtime>::get(43), None);
+						assert_eq!(Delegations::<Runtime>::get(43, 1), None);
+						assert_eq!(
+							Validators::<Runtime>::get(1),
+							Some(ValidatorStake { own: 6, delegated: 20 })
+						);
+
+						// when most of the stake of 44 is slashed, then it is taken from both
+						// of its delegations.
+						assert_eq!(slash(HoldReason::Delegation, 44, 15), 15);
+						assert_eq!(Delegators::<Runtime>::get(44), Some(5));
+						let portfolio = barrel_staking::Barrel::<Runtime>::delegations_of(&44);
+						assert_eq!(portfolio.len(), 1);
+						assert_eq!(portfolio[0].1, 5);
+						let delegated_to =
+							|v| Validators::<Runtime>::get(v).map_or(0, |stake| stake.delegated);
+						assert_eq!(delegated_to(2) + delegated_to(3), 5);
+						// slashes are only noticed by staking, which does not report them.
+						assert!(staking_events().is_empty());
+					})
+			}
+
+			#[test]
+			fn validator_slashed_to_zero_is_removed() {
+				ExtBuilder::default().build_and_execute(|| {
+					// given 3 is active in era 1.
+					next_era();
+					assert_eq!(ActiveValidators::<Runtime>::get(), vec![3, 2]);
+
+					// when all of its bond is slashed outside of staking:
+					assert_eq!(
+						barrel_currency::Barrel::<Runtime>::slash_reserved(
+							HoldReason::ValidatorBond.into(),
+							&3,
+							30
+						),
+						30
+					);
+
+					// then it stops being a validator right away, and is not elected anymore.
+					assert_eq!(Validators::<Runtime>::get(3), None);
+					assert_eq!(ActiveValidators::<Runtime>::get(), vec![2]);
+					assert!(!barrel_staking::Barrel::<Runtime>::is_registered(&3));
+					next_era();
+					assert_eq!(ActiveValidators::<Runtime>::get(), vec![2, 1]);
+				})
+			}
+
+			#[test]
+			fn frozen_account_cannot_stake() {
+				ExtBuilder::default().build_and_execute(|| {
+					// given 5 has funds, but is frozen.
+					assert_ok!(StakeCurrency::mint_into(&5, 100));
+					assert_ok!(barrel_currency::Barrel::<Runtime>::freeze_account(
+						RuntimeOrigin::root(),
+						5
+					));
+
+					// when, then 5 can neither register nor delegate.
+					assert_noop!(
+						barrel_staking::Barrel::<Runtime>::register(RuntimeOrigin::signed(5), 10),
+						Error::<Runtime>::InsufficientFunds
+					);
+					assert_noop!(
+						barrel_staking::Barrel::<Runtime>::delegate(
+							RuntimeOrigin::signed(5),
+							1,
+							10
+						),
+						Error::<Runtime>::InsufficientFunds
+					);
+
+					// when thawed, then it can stake again.
+					assert_ok!(barrel_currency::Barrel::<Runtime>::thaw_account(
+						RuntimeOrigin::root(),
+						5
+					));
+					assert_ok!(barrel_staking::Barrel::<Runtime>::register(
+						RuntimeOrigin::signed(5),
+						10
+					));
+					assert_eq!(
+						staking_events(),
+						vec![Event::ValidatorRegistered { who: 5, amount: 10 }]
+					);
+				})
+			}
 		}
 
-		#[test]
-		fn considers_delegators() {
-			// typically 2 and 3 win, and 1 and 3
-			ExtBuilder::default().add_delegator(42, 1, 30).build_and_execute(|| {
-				// given initial state,
-				assert!(barrel_staking::Delegators::<Runtime>::get(42).is_some());
+		/// Staking, using a single asset of the tutorial assets barrel.
+		mod with_assets {
+			use super::*;
+			use crate::{assets::barrel as barrel_assets, currency::barrel as barrel_currency};
+			use frame::traits::fungible::ItemOf;
+
+			construct_runtime!(
+				pub struct Runtime {
+					System: frame_system,
+					Currency: barrel_currency,
+					Assets: barrel_assets,
+					Staking: barrel_staking,
+				}
+			);
 
-				// when processing block 1 and 2, nothing will happen.
-				next_block();
-				next_block();
-				assert!(ActiveValidators::<Runtime>::get().is_empty());
+			#[derive_impl(frame_system::config_preludes::TestDefaultConfig as frame_system::DefaultConfig)]
+			impl frame_system::Config for Runtime {
+				type Block = MockBlock<Runtime>;
+				type AccountData = barrel_currency::AccountData;
+			}
 
-				// when processing block 3, new validators will be selected.
-				next_block();
-				assert_eq!(ActiveValidators::<Runtime>::get(), vec![1, 3]);
-			})
+			parameter_types! {
+				pub static ValidatorCount: u32 = 2;
+				pub static MaxValidators: u32 = 8;
+				pub static MaxDelegators: u32 = 8;
+				pub const EraDuration: BlockNumberFor<Runtime> = 3;
+				pub const BondingDuration: EraIndex = 2;
+				pub const RewardPot: u64 = 99;
+				pub const SlashReceiver: u64 = 98;
+				pub const MaxCommissionChange: Perbill = Perbill::from_percent(10);
+				// the minimum balance of all assets.
+				pub const ExistentialDeposit: barrel_assets::Balance = 1;
+				pub const StakingAsset: u32 = 0;
+				pub const AssetDeposit: barrel_currency::Balance = 10;
+			}
+
+			impl barrel_staking::Config for Runtime {
+				type RuntimeEvent = RuntimeEvent;
+				type Currency = ItemOf<Assets, StakingAsset, u64>;
+				type RuntimeHoldReason = RuntimeHoldReason;
+				type ValidatorCount = ValidatorCount;
+				type EraDuration = EraDuration;
+				type BondingDuration = BondingDuration;
+				type MaxUnbondingChunks = ConstU32<2>;
+				type MaxDelegations = ConstU32<2>;
+				type MaxValidators = MaxValidators;
+				type MaxDelegators = MaxDelegators;
+				type RewardPot = RewardPot;
+				type HistoryDepth = ConstU32<2>;
+				type MaxExposurePageSize = ConstU32<2>;
+				type MaxCommissionChange = MaxCommissionChange;
+				type SlashDeferDuration = ConstU32<1>;
+				type AdminOrigin = frame_system::EnsureRoot<Self::AccountId>;
+				type OnSlash = SlashTo<SlashReceiver>;
+				type WeightInfo = ();
+			}
+
+			impl barrel_currency::Config for Runtime {
+				type RuntimeEvent = RuntimeEvent;
+				type MintOrigin = frame_system::EnsureRoot<Self::AccountId>;
+				type FreezeOrigin = frame_system::EnsureRoot<Self::AccountId>;
+				type ExistentialDeposit = ExistentialDeposit;
+				type DustRemoval = ();
+				type RuntimeHoldReason = RuntimeHoldReason;
+				type MaxVestingSchedules = ConstU32<1>;
+				type MaxTransfers = ConstU32<4>;
+				type MaxScheduledPerBlock = ConstU32<4>;
+				type MaxAgendaBlocks = ConstU32<4>;
+				type MaxReleasesPerBlock = ConstU32<4>;
+				type WeightInfo = ();
+				type AccountStore = System;
+				type EraDuration = EraDuration;
+				type BlocksPerYear = ConstU64<100>;
+				type YearlyInflation = ();
+				type OnInflation = ();
+				type TransferFee = ();
+				type FeeBurn = ();
+				type FeeCollector = ();
+				type OnTransfer = ();
+				type OnBalanceChanged = ();
+				type OnAccountCreated = ();
+				type OnAccountKilled = ();
+			}
+
+			impl barrel_assets::Config for Runtime {
+				type RuntimeEvent = RuntimeEvent;
+				type AssetId = u32;
+				type Currency = Currency;
+				type RuntimeHoldReason = RuntimeHoldReason;
+				type AssetDeposit = AssetDeposit;
+				type StringLimit = ConstU32<8>;
+				type WeightInfo = ();
+			}
+
+			fn genesis(
+				balances: Vec<(u64, barrel_assets::Balance)>,
+				validators: Vec<(u64, barrel_assets::Balance)>,
+				delegators: Vec<(u64, u64, barrel_assets::Balance)>,
+			) -> RuntimeGenesisConfig {
+				let system = frame_system::GenesisConfig::default();
+				let currency = Default::default();
+				let assets = barrel_assets::GenesisConfig {
+					assets: vec![(StakingAsset::get(), 0, ExistentialDeposit::get())],
+					accounts: balances
+						.into_iter()
+						.map(|(who, amount)| (StakingAsset::get(), who, amount))
+						.collect(),
+				};
+				let staking = barrel_staking::GenesisConfig { validators, delegators };
+				RuntimeGenesisConfig { system, currency, assets, staking }
+			}
+
+			staking_tests!();
 		}
 
-		#[test]
-		fn selects_right_number_of_validators() {
-			ExtBuilder::default().build_and_execute(|| {
-				// when processing block 1 and 2, nothing will happen.
-				next_block();
-				next_block();
-				assert!(ActiveValidators::<Runtime>::get().is_empty());
+		/// Staking, using `barrel_balances`.
+		mod with_balances {
+			use super::*;
 
-				// set the `Get` implementor static test variable to 3.
-				ValidatorCount::set(3);
+			construct_runtime!(
+				pub struct Runtime {
+					System: frame_system,
+					Balances: barrel_balances,
+					Staking: barrel_staking,
+				}
+			);
 
-				next_block();
-				assert_eq!(ActiveValidators::<Runtime>::get(), vec![3, 2, 1]);
+			#[derive_impl(frame_system::config_preludes::TestDefaultConfig as frame_system::DefaultConfig)]
+			impl frame_system::Config for Runtime {
+				type Block = MockBlock<Runtime>;
+				type AccountData = barrel_balances::AccountData<u128>;
+			}
 
-				// this time, set to 1.
-				next_block();
-				next_block();
-				assert_eq!(ActiveValidators::<Runtime>::get(), vec![3, 2, 1]);
+			parameter_types! {
+				pub static ValidatorCount: u32 = 2;
+				pub static MaxValidators: u32 = 8;
+				pub static MaxDelegators: u32 = 8;
+				pub const EraDuration: BlockNumberFor<Runtime> = 3;
+				pub const BondingDuration: EraIndex = 2;
+				pub const RewardPot: u64 = 99;
+				pub const SlashReceiver: u64 = 98;
+				pub const MaxCommissionChange: Perbill = Perbill::from_percent(10);
+				pub const ExistentialDeposit: u128 = 1;
+			}
 
-				ValidatorCount::set(1);
-				next_block();
-				assert_eq!(ActiveValidators::<Runtime>::get(), vec![3]);
-			})
+			impl barrel_staking::Config for Runtime {
+				type RuntimeEvent = RuntimeEvent;
+				type Currency = Balances;
+				type RuntimeHoldReason = RuntimeHoldReason;
+				type ValidatorCount = ValidatorCount;
+				type EraDuration = EraDuration;
+				type BondingDuration = BondingDuration;
+				type MaxUnbondingChunks = ConstU32<2>;
+				type MaxDelegations = ConstU32<2>;
+				type MaxValidators = MaxValidators;
+				type MaxDelegators = MaxDelegators;
+				type RewardPot = RewardPot;
+				type HistoryDepth = ConstU32<2>;
+				type MaxExposurePageSize = ConstU32<2>;
+				type MaxCommissionChange = MaxCommissionChange;
+				type SlashDeferDuration = ConstU32<1>;
+				type AdminOrigin = frame_system::EnsureRoot<Self::AccountId>;
+				type OnSlash = SlashTo<SlashReceiver>;
+				type WeightInfo = ();
+			}
+
+			#[derive_impl(barrel_balances::config_preludes::TestDefaultConfig as barrel_balances::DefaultConfig)]
+			impl barrel_balances::Config for Runtime {
+				type Balance = u128;
+				type ExistentialDeposit = ExistentialDeposit;
+				type AccountStore = System;
+				type RuntimeHoldReason = RuntimeHoldReason;
+				type MaxHolds = ConstU32<3>;
+			}
+
+			fn genesis(
+				balances: Vec<(u64, u128)>,
+				validators: Vec<(u64, u128)>,
+				delegators: Vec<(u64, u64, u128)>,
+			) -> RuntimeGenesisConfig {
+				let system = frame_system::GenesisConfig::default();
+				let balances = barrel_balances::GenesisConfig { balances };
+				let staking = barrel_staking::GenesisConfig { validators, delegators };
+				RuntimeGenesisConfig { system, balances, staking }
+			}
+
+			staking_tests!();
 		}
 	}
 }
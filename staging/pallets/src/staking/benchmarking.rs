@@ -0,0 +1,392 @@
+//! Benchmarks for the staking barrel.
+
+use super::*;
+use frame::{
+	deps::sp_runtime::Perbill,
+	prelude::*,
+	traits::{
+		fungible::{Inspect, InspectHold, Mutate, MutateHold},
+		tokens::Precision,
+		Zero,
+	},
+};
+use frame_benchmarking::v2::*;
+use frame_system::RawOrigin;
+
+/// A stake that is comfortably above the minimum balance of the currency.
+fn stake<T: Config>() -> BalanceOf<T> {
+	T::Currency::minimum_balance().max(1u32.into()).saturating_mul(1_000u32.into())
+}
+
+/// Create an account that can put `amount` at stake, while staying alive.
+fn funded<T: Config>(name: &'static str, index: u32, amount: BalanceOf<T>) -> T::AccountId {
+	let who = account(name, index, 0);
+	T::Currency::mint_into(&who, amount.saturating_add(T::Currency::minimum_balance())).unwrap();
+	who
+}
+
+/// Create a validator with `amount` of own stake.
+fn registered<T: Config>(index: u32, amount: BalanceOf<T>) -> T::AccountId {
+	let who = funded::<T>("validator", index, amount);
+	Barrel::<T>::register(RawOrigin::Signed(who.clone()).into(), amount).unwrap();
+	who
+}
+
+/// Create a delegator that delegates `amount` to each of `validators`.
+fn delegating<T: Config>(
+	index: u32,
+	validators: &[T::AccountId],
+	amount: BalanceOf<T>,
+) -> T::AccountId {
+	let total = amount.saturating_mul((validators.len() as u32).into());
+	let who = funded::<T>("delegator", index, total);
+	for validator in validators {
+		Barrel::<T>::delegate(RawOrigin::Signed(who.clone()).into(), validator.clone(), amount)
+			.unwrap();
+	}
+	who
+}
+
+#[benchmarks]
+mod benchmarks {
+	use super::*;
+
+	#[benchmark]
+	fn register() {
+		let amount = stake::<T>();
+		let who = funded::<T>("validator", 0, amount);
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(who.clone()), amount);
+
+		assert!(Validators::<T>::contains_key(&who));
+	}
+
+	// worst case: the delegator already delegates to all but one of the validators it can.
+	#[benchmark]
+	fn delegate() {
+		let amount = stake::<T>();
+		let max = T::MaxDelegations::get();
+		let validators = (0..max).map(|i| registered::<T>(i, amount)).collect::<Vec<_>>();
+		let delegator = delegating::<T>(0, &validators[..validators.len() - 1], amount);
+		T::Currency::mint_into(&delegator, amount).unwrap();
+		let validator = validators[validators.len() - 1].clone();
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(delegator.clone()), validator.clone(), amount);
+
+		assert_eq!(Delegations::<T>::get(&delegator, &validator), Some(amount));
+		assert_eq!(Delegators::<T>::get(&delegator), Some(amount.saturating_mul(max.into())));
+	}
+
+	#[benchmark]
+	fn delegate_more() {
+		let amount = stake::<T>();
+		let validator = registered::<T>(0, amount);
+		let delegator = delegating::<T>(0, &[validator.clone()], amount);
+		T::Currency::mint_into(&delegator, amount).unwrap();
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(delegator.clone()), validator.clone(), amount);
+
+		assert_eq!(
+			Delegations::<T>::get(&delegator, &validator),
+			Some(amount.saturating_mul(2u32.into()))
+		);
+	}
+
+	// worst case: part of a delegation is moved to a new validator, while the delegator delegates
+	// to all but one of the validators it can.
+	#[benchmark]
+	fn redelegate() {
+		let amount = stake::<T>();
+		let max = T::MaxDelegations::get();
+		let validators = (0..max).map(|i| registered::<T>(i, amount)).collect::<Vec<_>>();
+		let delegator = delegating::<T>(0, &validators[..validators.len() - 1], amount);
+		let (from, to) = (validators[0].clone(), validators[validators.len() - 1].clone());
+		let half = amount / 2u32.into();
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(delegator.clone()), from.clone(), to.clone(), half);
+
+		assert_eq!(Delegations::<T>::get(&delegator, &from), Some(amount - half));
+		assert_eq!(Delegations::<T>::get(&delegator, &to), Some(half));
+	}
+
+	// worst case: an era starts, `v` registered validators are sorted, and the exposures of those
+	// that are selected are recorded from `d` delegations. The era that ends has a reward, and
+	// another one expires.
+	//
+	// Each of the `d` delegators delegates once. The weight is linear in the number of
+	// delegations, and is extrapolated up to `MaxDelegators * MaxDelegations` of them.
+	#[benchmark]
+	fn select_validators(
+		v: Linear<1, { T::MaxValidators::get() }>,
+		d: Linear<0, { T::MaxDelegators::get() }>,
+	) {
+		let validators = (0..v)
+			.map(|i| registered::<T>(i, stake::<T>().saturating_add(i.into())))
+			.collect::<Vec<_>>();
+		for i in 0..d {
+			delegating::<T>(i, &[validators[(i % v) as usize].clone()], stake::<T>());
+		}
+		let era = T::HistoryDepth::get().saturating_add(1);
+		CurrentEra::<T>::put(era);
+		for kept in [1, era] {
+			ErasRewards::<T>::insert(
+				kept,
+				EraReward { total_stake: stake::<T>(), reward: Zero::zero(), paid: Zero::zero() },
+			);
+		}
+		T::Currency::mint_into(&T::RewardPot::get(), stake::<T>()).unwrap();
+		let now = T::EraDuration::get();
+
+		#[block]
+		{
+			Barrel::<T>::on_initialize(now);
+		}
+
+		assert!(!ActiveValidators::<T>::get().is_empty());
+		assert!(ErasRewards::<T>::get(1).is_none());
+		assert!(!ErasRewards::<T>::get(era).unwrap().reward.is_zero());
+	}
+
+	// worst case: the commission was set in an earlier era.
+	#[benchmark]
+	fn set_commission() {
+		let validator = registered::<T>(0, stake::<T>());
+		let commission = T::MaxCommissionChange::get();
+		Commissions::<T>::insert(
+			&validator,
+			ValidatorCommission { rate: Perbill::zero(), changed_in: 0 },
+		);
+		CurrentEra::<T>::put(1);
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(validator.clone()), commission);
+
+		assert_eq!(Commissions::<T>::get(&validator).unwrap().rate, commission);
+	}
+
+	// worst case: the first page is paid, with `n` delegators, to accounts that all exist.
+	#[benchmark]
+	fn payout_era(n: Linear<0, { T::MaxExposurePageSize::get() }>) {
+		let amount = stake::<T>();
+		let validator = registered::<T>(0, amount);
+		for i in 0..n {
+			delegating::<T>(i, &[validator.clone()], amount);
+		}
+		Barrel::<T>::on_initialize(T::EraDuration::get());
+		T::Currency::mint_into(&T::RewardPot::get(), amount.saturating_mul(1_000u32.into()))
+			.unwrap();
+		Barrel::<T>::on_initialize(T::EraDuration::get().saturating_mul(2u32.into()));
+		let caller = account("caller", 0, 0);
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(caller), validator.clone(), 1);
+
+		assert_eq!(ClaimedPages::<T>::get(1, &validator), 1);
+		assert!(UnclaimedRewards::<T>::get() < amount.saturating_mul(1_000u32.into()));
+	}
+
+	// worst case: the validator is active, and still backed by a delegation.
+	#[benchmark]
+	fn unregister() {
+		let amount = stake::<T>();
+		let validator = registered::<T>(0, amount);
+		let delegator = funded::<T>("delegator", 0, amount);
+		Barrel::<T>::delegate(RawOrigin::Signed(delegator).into(), validator.clone(), amount)
+			.unwrap();
+		Barrel::<T>::on_initialize(T::EraDuration::get());
+		assert!(ActiveValidators::<T>::get().contains(&validator));
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(validator.clone()));
+
+		assert!(!Barrel::<T>::is_registered(&validator));
+		assert!(ActiveValidators::<T>::get().is_empty());
+	}
+
+	// worst case: the whole delegation is undelegated from a validator that unregistered, which
+	// is removed.
+	#[benchmark]
+	fn undelegate() {
+		let amount = stake::<T>();
+		let validator = registered::<T>(0, amount);
+		let delegator = funded::<T>("delegator", 0, amount);
+		Barrel::<T>::delegate(
+			RawOrigin::Signed(delegator.clone()).into(),
+			validator.clone(),
+			amount,
+		)
+		.unwrap();
+		Barrel::<T>::unregister(RawOrigin::Signed(validator.clone()).into()).unwrap();
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(delegator.clone()), validator.clone(), amount);
+
+		assert!(!Delegators::<T>::contains_key(&delegator));
+		assert!(!Delegations::<T>::contains_key(&delegator, &validator));
+		assert!(!Validators::<T>::contains_key(&validator));
+	}
+
+	// worst case: the account has the maximum number of chunks, all but the last of which can be
+	// withdrawn.
+	#[benchmark]
+	fn withdraw_unbonded() {
+		let chunks = T::MaxUnbondingChunks::get();
+		let amount = stake::<T>();
+		let who = registered::<T>(0, amount.saturating_mul(chunks.into()));
+		let unbonding = (0..chunks).map(|era| UnbondingChunk { amount, era }).collect::<Vec<_>>();
+		Validators::<T>::remove(&who);
+		T::Currency::release(
+			&HoldReason::ValidatorBond.into(),
+			&who,
+			amount.saturating_mul(chunks.into()),
+			Precision::Exact,
+		)
+		.unwrap();
+		T::Currency::hold(
+			&HoldReason::Unbonding.into(),
+			&who,
+			amount.saturating_mul(chunks.into()),
+		)
+		.unwrap();
+		Unbonding::<T>::insert(&who, BoundedVec::truncate_from(unbonding));
+		CurrentEra::<T>::put(chunks.saturating_sub(2));
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(who.clone()));
+
+		assert_eq!(
+			T::Currency::balance_on_hold(&HoldReason::Unbonding.into(), &who),
+			if chunks > 1 { amount } else { Zero::zero() }
+		);
+	}
+
+	#[benchmark]
+	fn report_offence() {
+		let validator = registered::<T>(0, stake::<T>());
+		Barrel::<T>::on_initialize(T::EraDuration::get());
+		let fraction = Perbill::from_percent(10);
+
+		#[extrinsic_call]
+		_(RawOrigin::Root, validator.clone(), fraction);
+
+		assert_eq!(UnappliedSlashes::<T>::get(1, &validator), Some(fraction));
+		assert!(Chilled::<T>::contains_key(&validator));
+	}
+
+	#[benchmark]
+	fn cancel_deferred_slash() -> Result<(), BenchmarkError> {
+		let origin =
+			T::AdminOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let validator = registered::<T>(0, stake::<T>());
+		Barrel::<T>::on_initialize(T::EraDuration::get());
+		Barrel::<T>::on_offence(validator.clone(), Perbill::from_percent(10)).unwrap();
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, 1, validator.clone());
+
+		assert!(!UnappliedSlashes::<T>::contains_key(1, &validator));
+		Ok(())
+	}
+
+	// worst case: the validator is backed by `n - 1` delegators, each of which started unbonding
+	// part of its delegation after the offence, so that both its delegation and its unbonding
+	// funds are slashed.
+	#[benchmark]
+	fn apply_slash(n: Linear<1, { T::MaxDelegators::get().saturating_add(1) }>) {
+		let amount = stake::<T>();
+		let validator = registered::<T>(0, amount);
+		let delegators = (1..n)
+			.map(|i| delegating::<T>(i, &[validator.clone()], amount))
+			.collect::<Vec<_>>();
+		Barrel::<T>::on_initialize(T::EraDuration::get());
+		Barrel::<T>::on_offence(validator.clone(), Perbill::one()).unwrap();
+		for delegator in &delegators {
+			Barrel::<T>::undelegate(
+				RawOrigin::Signed(delegator.clone()).into(),
+				validator.clone(),
+				amount / 4u32.into(),
+			)
+			.unwrap();
+		}
+
+		#[block]
+		{
+			Barrel::<T>::apply_slash(1, &validator, Perbill::one());
+		}
+
+		assert!(!Barrel::<T>::is_registered(&validator));
+		for delegator in &delegators {
+			assert!(!Delegators::<T>::contains_key(delegator));
+			assert!(!Unbonding::<T>::contains_key(delegator));
+		}
+	}
+
+	// worst case: the account is a validator, delegates to as many validators as it can and is
+	// unbonding, while nothing is held for any of these, so that all of its records are reduced.
+	// The records are written directly, since slashing the holds through the currency would
+	// already sync them.
+	#[benchmark]
+	fn sync_stake(n: Linear<1, { T::MaxDelegations::get() }>) {
+		let amount = stake::<T>();
+		let validators = (0..n).map(|i| registered::<T>(i, amount)).collect::<Vec<_>>();
+		let who: T::AccountId = account("stash", 0, 0);
+		Validators::<T>::insert(&who, ValidatorStake { own: amount, delegated: Zero::zero() });
+		for validator in &validators {
+			Delegations::<T>::insert(&who, validator, amount);
+			Validators::<T>::mutate(validator, |maybe_stake| {
+				maybe_stake.as_mut().map(|stake| stake.delegated = amount)
+			});
+		}
+		Delegators::<T>::insert(&who, amount.saturating_mul(n.into()));
+		Unbonding::<T>::insert(
+			&who,
+			BoundedVec::truncate_from(vec![UnbondingChunk { amount, era: 0 }]),
+		);
+
+		#[block]
+		{
+			Barrel::<T>::sync_stake(&who);
+		}
+
+		assert!(!Barrel::<T>::is_registered(&who));
+		assert!(!Delegators::<T>::contains_key(&who));
+		assert!(!Unbonding::<T>::contains_key(&who));
+		for validator in &validators {
+			assert_eq!(
+				Validators::<T>::get(validator).map(|stake| stake.delegated),
+				Some(Zero::zero())
+			);
+		}
+	}
+
+	// worst case: the era expired, and `n` of its entries are left, all of which are removed.
+	#[benchmark]
+	fn remove_expired_exposures(n: Linear<0, { T::MaxExposurePageSize::get() }>) {
+		CurrentEra::<T>::put(T::HistoryDepth::get().saturating_add(2));
+		for i in 0..n {
+			ClaimedPages::<T>::insert(1, account::<T::AccountId>("validator", i, 0), 1);
+		}
+		ExposureCleanup::<T>::put(CleanupCursor {
+			era: 1,
+			map: ExposureMap::Overview,
+			cursor: None,
+		});
+
+		#[block]
+		{
+			Barrel::<T>::remove_expired_exposures();
+		}
+
+		assert_eq!(ClaimedPages::<T>::iter_prefix(1).count(), 0);
+	}
+
+	impl_benchmark_test_suite!(
+		Barrel,
+		crate::staking::barrel::tests::with_currency::test_state_new(),
+		crate::staking::barrel::tests::with_currency::Runtime
+	);
+}
@@ -0,0 +1,309 @@
+//! Storage migrations for the staking barrel.
+
+use super::barrel::*;
+use frame::{
+	deps::frame_support::{
+		storage_alias,
+		traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
+	},
+	prelude::*,
+	traits::{fungible::MutateHold, tokens::Precision, Saturating, Zero},
+};
+#[cfg(feature = "try-runtime")]
+use frame::{
+	deps::sp_runtime::TryRuntimeError,
+	derive::{Decode, Encode},
+};
+
+/// Version 1: delegations are kept per delegator and validator in [`Delegations`], instead of
+/// the single target of each delegator in the `DelegationTargets` map of version 0.
+pub mod v1 {
+	use super::*;
+
+	mod v0 {
+		use super::*;
+
+		/// The validator that each delegator delegated to, if it was recorded.
+		#[storage_alias]
+		pub type DelegationTargets<T: Config> = StorageMap<
+			Barrel<T>,
+			Blake2_128Concat,
+			<T as frame_system::Config>::AccountId,
+			<T as frame_system::Config>::AccountId,
+		>;
+	}
+
+	/// Move the delegation of each delegator into [`Delegations`].
+	///
+	/// Delegators whose target was never recorded cannot be attributed to any validator. Their
+	/// delegation is dropped, and its funds released. The delegated stake of all validators is
+	/// then recomputed from [`Delegations`], and validators that unregistered and are no longer
+	/// backed are removed.
+	pub struct MigrateToDelegations<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToDelegations<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if Barrel::<T>::on_chain_storage_version() != 0 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			let mut delegators: u64 = 0;
+			let mut dropped: u64 = 0;
+			for (who, amount) in Delegators::<T>::iter().collect::<Vec<_>>() {
+				delegators += 1;
+				match v0::DelegationTargets::<T>::take(&who) {
+					Some(target) => Delegations::<T>::insert(&who, target, amount),
+					None => {
+						dropped += 1;
+						Delegators::<T>::remove(&who);
+						// the funds can only fail to be released if they are not held anymore,
+						// in which case there is nothing to release.
+						let _ = T::Currency::release(
+							&HoldReason::Delegation.into(),
+							&who,
+							amount,
+							Precision::BestEffort,
+						);
+					},
+				}
+			}
+			// targets without a delegator are left over from removed delegators.
+			let _ = v0::DelegationTargets::<T>::clear(u32::MAX, None);
+
+			let mut validators: u64 = 0;
+			let mut delegated = Vec::<(T::AccountId, BalanceOf<T>)>::new();
+			for (_, target, amount) in Delegations::<T>::iter() {
+				match delegated.iter_mut().find(|(v, _)| *v == target) {
+					Some((_, total)) => *total = total.saturating_add(amount),
+					None => delegated.push((target, amount)),
+				}
+			}
+			Validators::<T>::translate::<ValidatorStake<BalanceOf<T>>, _>(|who, mut stake| {
+				validators += 1;
+				stake.delegated = delegated
+					.iter()
+					.find(|(v, _)| *v == who)
+					.map_or(Zero::zero(), |(_, total)| *total);
+				Some(stake).filter(|stake| !stake.own.is_zero() || !stake.delegated.is_zero())
+			});
+
+			StorageVersion::new(1).put::<Barrel<T>>();
+			// per delegator: its total and its target are read, and its target and delegation
+			// are written. Dropped delegators also have their total, holds and account written.
+			// Then every delegation is read again, and every validator is read and written.
+			T::DbWeight::get().reads_writes(
+				delegators * 3 + dropped * 2 + validators + 1,
+				delegators * 2 + dropped * 3 + validators + 1,
+			)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+			ensure!(Barrel::<T>::on_chain_storage_version() == 0, "can only upgrade from v0");
+			let targeted = Delegators::<T>::iter()
+				.filter_map(|(who, amount)| {
+					v0::DelegationTargets::<T>::get(&who).map(|target| (who, target, amount))
+				})
+				.collect::<Vec<_>>();
+			Ok(targeted.encode())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(state: Vec<u8>) -> Result<(), TryRuntimeError> {
+			let targeted: Vec<(T::AccountId, T::AccountId, BalanceOf<T>)> =
+				Decode::decode(&mut &state[..])
+					.map_err(|_| "cannot decode the pre-upgrade state")?;
+
+			ensure!(Barrel::<T>::on_chain_storage_version() == 1, "storage version not updated");
+			ensure!(v0::DelegationTargets::<T>::iter().next().is_none(), "old targets not removed");
+			ensure!(
+				Delegators::<T>::iter().count() == targeted.len(),
+				"untargeted delegators not removed"
+			);
+			for (who, target, amount) in targeted {
+				ensure!(
+					Delegations::<T>::get(&who, &target) == Some(amount),
+					"delegation not migrated"
+				);
+			}
+			Ok(())
+		}
+	}
+
+	#[cfg(test)]
+	mod tests {
+		use super::*;
+		use crate::staking::barrel::tests::with_currency::{
+			test_state_new, Runtime, RuntimeHoldReason, RuntimeOrigin,
+		};
+		use frame::{
+			testing_prelude::*,
+			traits::fungible::{InspectHold, Mutate},
+		};
+
+		type TutorialCurrency = crate::currency::Barrel<Runtime>;
+
+		#[test]
+		fn migrate_to_delegations_works() {
+			test_state_new().execute_with(|| {
+				// given a v0 state where 1 and 2 are validators, 42 delegates 30 to 1, and the
+				// target of the 20 that 43 delegates was never recorded.
+				StorageVersion::new(0).put::<Barrel<Runtime>>();
+				assert_ok!(TutorialCurrency::mint_into(&1, 100));
+				assert_ok!(TutorialCurrency::mint_into(&2, 100));
+				assert_ok!(TutorialCurrency::mint_into(&42, 100));
+				assert_ok!(TutorialCurrency::mint_into(&43, 100));
+				assert_ok!(Barrel::<Runtime>::register(RuntimeOrigin::signed(1), 10));
+				assert_ok!(Barrel::<Runtime>::register(RuntimeOrigin::signed(2), 20));
+				let delegation: RuntimeHoldReason = HoldReason::Delegation.into();
+				assert_ok!(TutorialCurrency::hold(&delegation, &42, 30));
+				assert_ok!(TutorialCurrency::hold(&delegation, &43, 20));
+				Delegators::<Runtime>::insert(42, 30);
+				Delegators::<Runtime>::insert(43, 20);
+				v0::DelegationTargets::<Runtime>::insert(42, 1);
+				Validators::<Runtime>::insert(1, ValidatorStake { own: 10, delegated: 30 });
+				Validators::<Runtime>::insert(2, ValidatorStake { own: 20, delegated: 20 });
+				// and 3 unregistered, but was still backed by the delegation of 43.
+				Validators::<Runtime>::insert(3, ValidatorStake { own: 0, delegated: 20 });
+				#[cfg(feature = "try-runtime")]
+				let state = MigrateToDelegations::<Runtime>::pre_upgrade().unwrap();
+
+				// when:
+				MigrateToDelegations::<Runtime>::on_runtime_upgrade();
+
+				// then:
+				#[cfg(feature = "try-runtime")]
+				MigrateToDelegations::<Runtime>::post_upgrade(state).unwrap();
+				assert_eq!(Barrel::<Runtime>::on_chain_storage_version(), 1);
+				assert_eq!(v0::DelegationTargets::<Runtime>::iter().count(), 0);
+				assert_eq!(Barrel::<Runtime>::delegations_of(&42), vec![(1, 30)]);
+				assert_eq!(Barrel::<Runtime>::total_delegated(&42), 30);
+				assert_eq!(Delegators::<Runtime>::get(43), None);
+				assert_eq!(TutorialCurrency::balance_on_hold(&delegation, &43), 0);
+				assert_eq!(
+					Validators::<Runtime>::get(1),
+					Some(ValidatorStake { own: 10, delegated: 30 })
+				);
+				assert_eq!(
+					Validators::<Runtime>::get(2),
+					Some(ValidatorStake { own: 20, delegated: 0 })
+				);
+				assert_eq!(Validators::<Runtime>::get(3), None);
+
+				// and running it again is a no-op.
+				v0::DelegationTargets::<Runtime>::insert(43, 2);
+				MigrateToDelegations::<Runtime>::on_runtime_upgrade();
+				assert_eq!(v0::DelegationTargets::<Runtime>::get(43), Some(2));
+			});
+		}
+	}
+}
+
+/// Version 2: [`Validators`] and [`Delegators`] are counted, so that their number can be bounded
+/// by [`Config::MaxValidators`] and [`Config::MaxDelegators`].
+pub mod v2 {
+	use super::*;
+
+	#[cfg(test)]
+	mod v1 {
+		use super::*;
+
+		/// [`Validators`] as it was stored in version 1, without a counter.
+		#[storage_alias]
+		pub type Validators<T: Config> = StorageMap<
+			Barrel<T>,
+			Blake2_128Concat,
+			<T as frame_system::Config>::AccountId,
+			ValidatorStake<BalanceOf<T>>,
+		>;
+
+		/// [`Delegators`] as it was stored in version 1, without a counter.
+		#[storage_alias]
+		pub type Delegators<T: Config> = StorageMap<
+			Barrel<T>,
+			Blake2_128Concat,
+			<T as frame_system::Config>::AccountId,
+			BalanceOf<T>,
+		>;
+	}
+
+	/// Initialize the counters of [`Validators`] and [`Delegators`] from their entries.
+	///
+	/// Existing entries are kept even if there are more of them than the new bounds allow. No new
+	/// validator or delegator is then accepted until enough of them leave.
+	pub struct InitializeCounters<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for InitializeCounters<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if Barrel::<T>::on_chain_storage_version() != 1 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			let validators = Validators::<T>::initialize_counter();
+			let delegators = Delegators::<T>::initialize_counter();
+
+			StorageVersion::new(2).put::<Barrel<T>>();
+			// every validator and delegator is read once, and both counters are written.
+			T::DbWeight::get().reads_writes(validators as u64 + delegators as u64 + 1, 3)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+			ensure!(Barrel::<T>::on_chain_storage_version() == 1, "can only upgrade from v1");
+			Ok(Vec::new())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(_: Vec<u8>) -> Result<(), TryRuntimeError> {
+			ensure!(Barrel::<T>::on_chain_storage_version() == 2, "storage version not updated");
+			ensure!(
+				Validators::<T>::count() as usize == Validators::<T>::iter_keys().count(),
+				"validators not counted"
+			);
+			ensure!(
+				Delegators::<T>::count() as usize == Delegators::<T>::iter_keys().count(),
+				"delegators not counted"
+			);
+			Ok(())
+		}
+	}
+
+	#[cfg(test)]
+	mod tests {
+		use super::*;
+		use crate::staking::barrel::tests::with_currency::{test_state_new, Runtime};
+		use frame::testing_prelude::*;
+
+		#[test]
+		fn initialize_counters_works() {
+			test_state_new().execute_with(|| {
+				// given a v1 state with 2 validators and a delegator, none of which are counted.
+				StorageVersion::new(1).put::<Barrel<Runtime>>();
+				let _ = Validators::<Runtime>::clear(u32::MAX, None);
+				let _ = Delegators::<Runtime>::clear(u32::MAX, None);
+				let stake = ValidatorStake { own: 10, delegated: 0 };
+				v1::Validators::<Runtime>::insert(1, stake.clone());
+				v1::Validators::<Runtime>::insert(2, stake);
+				v1::Delegators::<Runtime>::insert(42, 30);
+				assert_eq!(Validators::<Runtime>::count(), 0);
+				#[cfg(feature = "try-runtime")]
+				let state = InitializeCounters::<Runtime>::pre_upgrade().unwrap();
+
+				// when:
+				InitializeCounters::<Runtime>::on_runtime_upgrade();
+
+				// then:
+				#[cfg(feature = "try-runtime")]
+				InitializeCounters::<Runtime>::post_upgrade(state).unwrap();
+				assert_eq!(Barrel::<Runtime>::on_chain_storage_version(), 2);
+				assert_eq!(Validators::<Runtime>::count(), 2);
+				assert_eq!(Delegators::<Runtime>::count(), 1);
+
+				// and running it again is a no-op.
+				v1::Delegators::<Runtime>::insert(43, 20);
+				InitializeCounters::<Runtime>::on_runtime_upgrade();
+				assert_eq!(Delegators::<Runtime>::count(), 1);
+			});
+		}
+	}
+}
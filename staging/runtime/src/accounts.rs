@@ -0,0 +1,128 @@
+//! The account data of the runtime.
+//!
+//! `frame_system` keeps a single [`AccountData`] per account, which is shared by `barrel_balances`
+//! and the tutorial currency. Each of them only sees its own part of it, through [`SystemPart`].
+
+use crate::{
+	interface::{AccountId, Balance, Nonce},
+	Runtime, System, TutorialCurrency,
+};
+#[cfg(feature = "try-runtime")]
+use frame::deps::sp_runtime::TryRuntimeError;
+use frame::{
+	deps::frame_support::traits::{GetStorageVersion, OnRuntimeUpgrade},
+	derive::{Decode, Encode, MaxEncodedLen, TypeInfo},
+	prelude::*,
+	traits::StoredMap,
+};
+
+/// The data that `frame_system` keeps for each account.
+#[derive(Encode, Decode, MaxEncodedLen, TypeInfo, Clone, Default, Eq, PartialEq, Debug)]
+pub struct AccountData {
+	pub balances: barrel_balances::AccountData<Balance>,
+	pub tutorial_currency: staging_barrels::currency::AccountData,
+}
+
+/// A part of [`AccountData`].
+pub trait Part {
+	type Data: Clone + Default + PartialEq;
+
+	fn part(data: &mut AccountData) -> &mut Self::Data;
+}
+
+/// The part of [`AccountData`] that belongs to `barrel_balances`.
+pub struct BalancesPart;
+impl Part for BalancesPart {
+	type Data = barrel_balances::AccountData<Balance>;
+
+	fn part(data: &mut AccountData) -> &mut Self::Data {
+		&mut data.balances
+	}
+}
+
+/// The part of [`AccountData`] that belongs to the tutorial currency.
+pub struct TutorialCurrencyPart;
+impl Part for TutorialCurrencyPart {
+	type Data = staging_barrels::currency::AccountData;
+
+	fn part(data: &mut AccountData) -> &mut Self::Data {
+		&mut data.tutorial_currency
+	}
+}
+
+/// A [`StoredMap`] over the part `P` of the [`AccountData`] kept in `frame_system`.
+///
+/// A part exists if it is not the default. An account exists in `frame_system` as long as any of
+/// its parts exists.
+pub struct SystemPart<P>(PhantomData<P>);
+
+impl<P: Part> StoredMap<AccountId, P::Data> for SystemPart<P> {
+	fn get(who: &AccountId) -> P::Data {
+		let mut data = <System as StoredMap<_, AccountData>>::get(who);
+		core::mem::take(P::part(&mut data))
+	}
+
+	fn try_mutate_exists<R, E: From<DispatchError>>(
+		who: &AccountId,
+		f: impl FnOnce(&mut Option<P::Data>) -> Result<R, E>,
+	) -> Result<R, E> {
+		<System as StoredMap<_, AccountData>>::try_mutate_exists(who, |maybe_data| {
+			let mut data = maybe_data.take().unwrap_or_default();
+			let mut maybe_part = Some(core::mem::take(P::part(&mut data)))
+				.filter(|part| *part != Default::default());
+			let result = f(&mut maybe_part)?;
+			*P::part(&mut data) = maybe_part.unwrap_or_default();
+			*maybe_data = Some(data).filter(|data| *data != Default::default());
+			Ok(result)
+		})
+	}
+}
+
+/// Extend the [`AccountData`] of all existing accounts, which used to only be that of
+/// `barrel_balances`, with that of the tutorial currency.
+///
+/// This runs in the same upgrade as the v1 migration of the tutorial currency, and must come
+/// before it.
+pub struct ExtendAccountData;
+
+type OldAccountInfo = frame_system::AccountInfo<Nonce, barrel_balances::AccountData<Balance>>;
+
+impl OnRuntimeUpgrade for ExtendAccountData {
+	fn on_runtime_upgrade() -> Weight {
+		let db_weight = <Runtime as frame_system::Config>::DbWeight::get();
+		if TutorialCurrency::on_chain_storage_version() != 0 {
+			return db_weight.reads(1)
+		}
+
+		let mut translated: u64 = 0;
+		frame_system::Account::<Runtime>::translate::<OldAccountInfo, _>(|_, info| {
+			translated += 1;
+			Some(frame_system::AccountInfo {
+				nonce: info.nonce,
+				consumers: info.consumers,
+				providers: info.providers,
+				sufficients: info.sufficients,
+				data: AccountData { balances: info.data, tutorial_currency: Default::default() },
+			})
+		});
+
+		db_weight.reads_writes(translated + 1, translated)
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+		let accounts = frame_system::Account::<Runtime>::iter_keys().count() as u32;
+		Ok(accounts.encode())
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade(state: Vec<u8>) -> Result<(), TryRuntimeError> {
+		let accounts = u32::decode(&mut &state[..]).map_err(|_| "cannot decode the state")?;
+		// accounts that cannot be decoded are skipped by `iter`.
+		ensure!(
+			frame_system::Account::<Runtime>::iter().count() as u32 == accounts,
+			"not all accounts were translated"
+		);
+		Ok(())
+	}
+}
@@ -21,6 +21,13 @@
 #[cfg(feature = "std")]
 include!(concat!(env!("OUT_DIR"), "/wasm_binary.rs"));
 
+mod accounts;
+
+use frame::deps::{
+	frame_support::PalletId,
+	sp_runtime::{traits::AccountIdConversion, Perbill},
+};
+
 use frame::{
 	deps::frame_support::weights::FixedFee, // TODO: needs to come from somewhere reasonable.
 	prelude::*,
@@ -42,7 +49,7 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	spec_name: create_runtime_str!("minimal-runtime"),
 	impl_name: create_runtime_str!("minimal-runtime"),
 	authoring_version: 1,
-	spec_version: 0,
+	spec_version: 2,
 	impl_version: 1,
 	apis: RUNTIME_API_VERSIONS,
 	transaction_version: 1,
@@ -76,12 +83,26 @@ construct_runtime!(
 		TransactionPayment: barrel_transaction_payment,
 
 		TutorialCurrency: staging_barrels::currency,
+		TutorialAssets: staging_barrels::assets,
 		TutorialStaking: staging_barrels::staking,
 	}
 );
 
 parameter_types! {
 	pub const Version: RuntimeVersion = VERSION;
+	pub const TutorialExistentialDeposit: staging_barrels::currency::Balance = 1;
+	pub const TutorialAssetDeposit: staging_barrels::currency::Balance = 100;
+	pub const TutorialEraDuration: BlockNumberFor<Runtime> = 200;
+	// with the default block time of the node, 3 seconds.
+	pub const TutorialBlocksPerYear: BlockNumberFor<Runtime> = 365 * 24 * 60 * 20;
+	pub const TutorialYearlyInflation: Perbill = Perbill::from_percent(5);
+	pub const TutorialTransferFee: Perbill = Perbill::from_parts(1_000_000);
+	pub const TutorialFeeBurn: Perbill = Perbill::from_percent(50);
+	pub TutorialTreasury: interface::AccountId =
+		PalletId(*b"tut/trsy").into_account_truncating();
+	pub TutorialRewardPot: interface::AccountId =
+		PalletId(*b"tut/rwrd").into_account_truncating();
+	pub const TutorialMaxCommissionChange: Perbill = Perbill::from_percent(1);
 }
 
 #[derive_impl(frame_system::config_preludes::SolochainDefaultConfig as frame_system::DefaultConfig)]
@@ -89,12 +110,12 @@ impl frame_system::Config for Runtime {
 	type Block = Block;
 	type Version = Version;
 	type BlockHashCount = ConstU32<1024>;
-	type AccountData = barrel_balances::AccountData<<Runtime as barrel_balances::Config>::Balance>;
+	type AccountData = accounts::AccountData;
 }
 
 #[derive_impl(barrel_balances::config_preludes::TestDefaultConfig as barrel_balances::DefaultConfig)]
 impl barrel_balances::Config for Runtime {
-	type AccountStore = System;
+	type AccountStore = accounts::SystemPart<accounts::BalancesPart>;
 }
 
 #[derive_impl(barrel_sudo::config_preludes::TestDefaultConfig as barrel_sudo::DefaultConfig)]
@@ -111,20 +132,105 @@ impl barrel_transaction_payment::Config for Runtime {
 	type OnChargeTransaction = barrel_transaction_payment::CurrencyAdapter<Balances, ()>;
 }
 
-impl staging_barrels::currency::Config for Runtime {}
+impl staging_barrels::currency::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	// minting is only possible through sudo.
+	type MintOrigin = frame_system::EnsureRoot<interface::AccountId>;
+	type FreezeOrigin = frame_system::EnsureRoot<interface::AccountId>;
+	type ExistentialDeposit = TutorialExistentialDeposit;
+	type DustRemoval = ();
+	type RuntimeHoldReason = RuntimeHoldReason;
+	type MaxVestingSchedules = ConstU32<8>;
+	type MaxTransfers = ConstU32<256>;
+	type MaxScheduledPerBlock = ConstU32<64>;
+	type MaxAgendaBlocks = ConstU32<128>;
+	// scheduled transfers that are due beyond this are released in the following blocks.
+	type MaxReleasesPerBlock = ConstU32<32>;
+	type WeightInfo = staging_barrels::currency::weights::MatterWeight<Runtime>;
+	type AccountStore = accounts::SystemPart<accounts::TutorialCurrencyPart>;
+	type EraDuration = TutorialEraDuration;
+	type BlocksPerYear = TutorialBlocksPerYear;
+	type YearlyInflation = TutorialYearlyInflation;
+	// inflation is paid into the staking reward pot. It is minted before staking starts a new
+	// era, in the same block, so that it becomes the reward of the era that ends.
+	type OnInflation = staging_barrels::currency::InflationTo<Runtime, TutorialRewardPot>;
+	// 0.1% of each transfer, half of which is burned and the other half paid into the treasury.
+	type TransferFee = staging_barrels::currency::PercentageFee<TutorialTransferFee>;
+	type FeeBurn = TutorialFeeBurn;
+	type FeeCollector = TutorialTreasury;
+	type OnTransfer = ();
+	// staking follows the funds that it holds in the tutorial currency.
+	type OnBalanceChanged = TutorialStaking;
+	type OnAccountCreated = ();
+	type OnAccountKilled = ();
+}
+impl staging_barrels::assets::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type AssetId = u32;
+	// asset deposits are held in the tutorial currency.
+	type Currency = TutorialCurrency;
+	type RuntimeHoldReason = RuntimeHoldReason;
+	type AssetDeposit = TutorialAssetDeposit;
+	type StringLimit = ConstU32<32>;
+	type WeightInfo = staging_barrels::assets::weights::MatterWeight<Runtime>;
+}
 impl staging_barrels::staking::Config for Runtime {
-	type EraDuration = ConstU32<200>;
+	type RuntimeEvent = RuntimeEvent;
+	type Currency = TutorialCurrency;
+	type RuntimeHoldReason = RuntimeHoldReason;
+	type EraDuration = TutorialEraDuration;
+	type BondingDuration = ConstU32<28>;
+	type MaxUnbondingChunks = ConstU32<16>;
+	type MaxDelegations = ConstU32<16>;
+	// every validator and delegation is looked at when an era starts, so both are bounded.
+	type MaxValidators = ConstU32<256>;
+	type MaxDelegators = ConstU32<256>;
+	type RewardPot = TutorialRewardPot;
+	type HistoryDepth = ConstU32<84>;
+	type MaxExposurePageSize = ConstU32<64>;
+	type MaxCommissionChange = TutorialMaxCommissionChange;
+	// slashes can be cancelled for 27 eras, well within the 84 eras of history that are kept, and
+	// are applied before the funds unbonded right after the offence can be withdrawn.
+	type SlashDeferDuration = ConstU32<27>;
+	type AdminOrigin = frame_system::EnsureRoot<interface::AccountId>;
+	// slashed funds are paid into the treasury.
+	type OnSlash = staging_barrels::staking::SlashTo<TutorialTreasury>;
 	type ValidatorCount = ConstU32<4>;
+	type WeightInfo = staging_barrels::staking::weights::MatterWeight<Runtime>;
 }
 
 type Block = frame::runtime::types_common::BlockOf<Runtime, SignedExtra>;
 type Header = HeaderFor<Runtime>;
 
-type RuntimeExecutive =
-	Executive<Runtime, Block, frame_system::ChainContext<Runtime>, Runtime, AllBarrelsWithSystem>;
+/// The migrations to run on the next runtime upgrade.
+type Migrations = (
+	accounts::ExtendAccountData,
+	staging_barrels::currency::migrations::v1::MigrateToAccountData<Runtime>,
+	staging_barrels::staking::migrations::v1::MigrateToDelegations<Runtime>,
+	staging_barrels::staking::migrations::v2::InitializeCounters<Runtime>,
+	staging_barrels::assets::migrations::v1::MigrateToMinBalance<Runtime>,
+);
+
+type RuntimeExecutive = Executive<
+	Runtime,
+	Block,
+	frame_system::ChainContext<Runtime>,
+	Runtime,
+	AllBarrelsWithSystem,
+	Migrations,
+>;
 
 use barrel_transaction_payment::{FeeDetails, RuntimeDispatchInfo};
 
+#[cfg(feature = "runtime-benchmarks")]
+mod benches {
+	frame_benchmarking::define_benchmarks!(
+		[staging_barrels::currency, TutorialCurrency]
+		[staging_barrels::assets, TutorialAssets]
+		[staging_barrels::staking, TutorialStaking]
+	);
+}
+
 impl_runtime_apis! {
 	impl apis::Core<Block> for Runtime {
 		fn version() -> RuntimeVersion {
@@ -228,6 +334,55 @@ impl_runtime_apis! {
 			TransactionPayment::length_to_fee(length)
 		}
 	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	impl frame_benchmarking::Benchmark<Block> for Runtime {
+		fn benchmark_metadata(extra: bool) -> (
+			Vec<frame_benchmarking::BenchmarkList>,
+			Vec<frame::deps::frame_support::traits::StorageInfo>,
+		) {
+			use frame::deps::frame_support::traits::StorageInfoTrait;
+			use frame_benchmarking::{BenchmarkList, Benchmarking};
+
+			let mut list = Vec::<BenchmarkList>::new();
+			list_benchmarks!(list, extra);
+
+			let storage_info = AllBarrelsWithSystem::storage_info();
+			(list, storage_info)
+		}
+
+		fn dispatch_benchmark(
+			config: frame_benchmarking::BenchmarkConfig,
+		) -> Result<Vec<frame_benchmarking::BenchmarkBatch>, frame::deps::sp_runtime::RuntimeString> {
+			use frame_benchmarking::{BenchmarkBatch, Benchmarking, TrackedStorageKey};
+
+			let whitelist: Vec<TrackedStorageKey> = vec![];
+			let mut batches = Vec::<BenchmarkBatch>::new();
+			let params = (&config, &whitelist);
+			add_benchmarks!(params, batches);
+
+			Ok(batches)
+		}
+	}
+
+	#[api_version(2)]
+	impl staging_barrels::currency::runtime_api::CurrencyApi<Block, interface::AccountId> for Runtime {
+		fn balance_of(who: interface::AccountId) -> interface::TutorialBalance {
+			TutorialCurrency::free_balance(&who)
+		}
+
+		fn total_issuance() -> interface::TutorialBalance {
+			staging_barrels::currency::TotalIssuance::<Runtime>::get()
+		}
+
+		fn holders_count() -> u32 {
+			TutorialCurrency::holders_count()
+		}
+
+		fn projected_issuance(eras: u32) -> Option<interface::TutorialBalance> {
+			TutorialCurrency::projected_issuance(eras)
+		}
+	}
 }
 
 /// Some re-exports that the node side code needs to know. Some are useful in this context as well.
@@ -245,4 +400,18 @@ pub mod interface {
 	pub type Balance = <Runtime as barrel_balances::Config>::Balance;
 
 	pub type MinimumBalance = <Runtime as barrel_balances::Config>::ExistentialDeposit;
+
+	pub type TutorialBalance = staging_barrels::currency::Balance;
+	pub use staging_barrels::currency::{runtime_api::CurrencyApi, MAX_PROJECTED_ERAS};
+
+	pub type GenesisProblem = staging_barrels::genesis::Problem<AccountId>;
+
+	/// Check that the genesis of the tutorial barrels in `config` can be built, see
+	/// [`staging_barrels::genesis::validate`].
+	pub fn validate_genesis(config: &RuntimeGenesisConfig) -> Result<(), Vec<GenesisProblem>> {
+		staging_barrels::genesis::validate::<Runtime>(
+			&config.tutorial_currency,
+			&config.tutorial_staking,
+		)
+	}
 }